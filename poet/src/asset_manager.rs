@@ -44,12 +44,12 @@ impl AssetManager {
             }
         }
 
-        Err(format!("Asset not found: '{asset}'"))
+        Err(missing_asset_message(asset))
     }
 
     fn rhai_add(&mut self, asset: String) -> Result<(), Box<EvalAltResult>> {
         if self.http_preloader.register_input(&asset).is_none() {
-            return Err(format!("Asset not found: {asset}").into());
+            return Err(missing_asset_message(&asset).into());
         }
 
         Ok(())
@@ -111,6 +111,12 @@ impl AssetManager {
     }
 }
 
+fn missing_asset_message(asset: &str) -> String {
+    format!(
+        "Asset '{asset}' has no output in the esbuild metafile, which may be stale. Try regenerating the metafile by rebuilding the assets"
+    )
+}
+
 impl CustomType for AssetManager {
     fn build(mut builder: TypeBuilder<Self>) {
         builder
@@ -123,3 +129,52 @@ impl CustomType for AssetManager {
             .with_fn("stylesheet", Self::rhai_stylesheet);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr as _;
+
+    use anyhow::Result;
+    use anyhow::anyhow;
+
+    use super::*;
+
+    fn stale_asset_manager() -> Result<AssetManager> {
+        let esbuild_metafile = EsbuildMetaFile::from_str(
+            r#"{
+                "outputs": {
+                    "static/app.js": {
+                        "entryPoint": "resources/ts/app.ts",
+                        "imports": []
+                    }
+                }
+            }"#,
+        )?;
+
+        Ok(AssetManager::from_esbuild_metafile(
+            Arc::new(esbuild_metafile),
+            AssetPathRenderer {
+                base_path: "/".to_string(),
+            },
+        ))
+    }
+
+    #[test]
+    fn test_missing_output_in_stale_metafile_is_an_error() -> Result<()> {
+        let mut asset_manager = stale_asset_manager()?;
+
+        let Err(err) = asset_manager.file("resources/ts/admin.ts") else {
+            return Err(anyhow!("Expected the missing output to be an error"));
+        };
+
+        assert!(err.contains("'resources/ts/admin.ts'"));
+        assert!(err.contains("regenerating the metafile"));
+        assert!(
+            asset_manager
+                .rhai_add("resources/ts/admin.ts".to_string())
+                .is_err()
+        );
+
+        Ok(())
+    }
+}
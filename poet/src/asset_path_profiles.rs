@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use anyhow::anyhow;
+
+use crate::asset_path_renderer::AssetPathRenderer;
+
+/// Base paths of assets in each environment (like a staging or production
+/// CDN), so the same build can render asset paths for any of them
+#[derive(Clone, Debug, Default)]
+pub struct AssetPathProfiles {
+    pub base_paths: BTreeMap<String, String>,
+}
+
+impl AssetPathProfiles {
+    pub fn asset_path_renderer(&self, profile: &str) -> Result<AssetPathRenderer> {
+        match self.base_paths.get(profile) {
+            Some(base_path) => Ok(AssetPathRenderer {
+                base_path: base_path.clone(),
+            }),
+            None => Err(anyhow!(
+                "Asset path profile '{profile}' is not defined, expected one of: {}",
+                self.base_paths
+                    .keys()
+                    .map(|name| format!("'{name}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+}
+
+impl FromIterator<(String, String)> for AssetPathProfiles {
+    fn from_iter<TIterator>(iterator: TIterator) -> Self
+    where
+        TIterator: IntoIterator<Item = (String, String)>,
+    {
+        Self {
+            base_paths: iterator.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use esbuild_metafile::renders_path::RendersPath as _;
+
+    use super::*;
+
+    #[test]
+    fn test_same_asset_renders_under_each_profile() -> Result<()> {
+        let asset_path_profiles: AssetPathProfiles = [
+            (
+                "production".to_string(),
+                "https://cdn.example.com/".to_string(),
+            ),
+            (
+                "staging".to_string(),
+                "https://staging-cdn.example.com/".to_string(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            asset_path_profiles
+                .asset_path_renderer("production")?
+                .render_path("assets/main.js"),
+            "https://cdn.example.com/assets/main.js"
+        );
+        assert_eq!(
+            asset_path_profiles
+                .asset_path_renderer("staging")?
+                .render_path("assets/main.js"),
+            "https://staging-cdn.example.com/assets/main.js"
+        );
+        assert!(asset_path_profiles.asset_path_renderer("local").is_err());
+
+        Ok(())
+    }
+}
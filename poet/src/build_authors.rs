@@ -39,6 +39,6 @@ pub async fn build_authors(source_filesystem: Arc<Storage>) -> Result<AuthorColl
     if error_collection.is_empty() {
         Ok(authors)
     } else {
-        Err(anyhow!("{error_collection}"))
+        Err(error_collection.into())
     }
 }
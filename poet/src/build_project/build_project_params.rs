@@ -6,6 +6,8 @@ use rhai_components::rhai_template_renderer::RhaiTemplateRenderer;
 use crate::asset_path_renderer::AssetPathRenderer;
 use crate::author_collection::AuthorCollection;
 use crate::filesystem::storage::Storage;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+use crate::prompt_discovery::PromptDiscovery;
 
 pub struct BuildProjectParams {
     pub asset_path_renderer: AssetPathRenderer,
@@ -14,6 +16,9 @@ pub struct BuildProjectParams {
     pub generated_page_base_path: String,
     pub generate_sitemap: bool,
     pub is_watching: bool,
+    /// Content files that are built as prompts are left out
+    pub prompt_discovery: PromptDiscovery,
     pub rhai_template_renderer: RhaiTemplateRenderer,
     pub source_filesystem: Arc<Storage>,
+    pub unknown_fields_policy: FrontMatterUnknownFieldsPolicy,
 }
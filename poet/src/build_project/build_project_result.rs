@@ -18,6 +18,25 @@ pub struct BuildProjectResult {
     pub memory_filesystem: Arc<Memory>,
 }
 
+impl BuildProjectResult {
+    /// Both builds read the same content documents, so only assets could
+    /// have changed between them
+    pub fn has_same_content_documents_as(&self, other: &BuildProjectResult) -> bool {
+        self.content_document_sources.len() == other.content_document_sources.len()
+            && self
+                .content_document_sources
+                .iter()
+                .all(|(basename, content_document_source)| {
+                    other.content_document_sources.get(basename).is_some_and(
+                        |other_content_document_source| {
+                            other_content_document_source.file_entry.contents_hash
+                                == content_document_source.file_entry.contents_hash
+                        },
+                    )
+                })
+    }
+}
+
 impl From<BuildProjectResultStub> for BuildProjectResult {
     fn from(
         BuildProjectResultStub {
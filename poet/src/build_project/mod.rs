@@ -11,11 +11,13 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use anyhow::Context as _;
 use anyhow::Result;
 use anyhow::anyhow;
 use dashmap::DashMap;
 use log::debug;
 use log::info;
+use log::warn;
 use rayon::iter::IntoParallelRefIterator as _;
 use rayon::iter::ParallelIterator as _;
 use rhai::Dynamic;
@@ -29,9 +31,11 @@ use crate::build_project::content_document_rendering_context::ContentDocumentRen
 use crate::build_timer::BuildTimer;
 use crate::content_document::ContentDocument;
 use crate::content_document_basename::ContentDocumentBasename;
+use crate::content_document_basename_by_id::ContentDocumentBasenameById;
 use crate::content_document_collection::ContentDocumentCollection;
 use crate::content_document_collection_ranked::ContentDocumentCollectionRanked;
 use crate::content_document_component_context::ContentDocumentComponentContext;
+use crate::content_document_content_by_basename::ContentDocumentContentByBasename;
 use crate::content_document_front_matter::ContentDocumentFrontMatter;
 use crate::content_document_in_collection::ContentDocumentInCollection;
 use crate::content_document_linker::ContentDocumentLinker;
@@ -43,6 +47,7 @@ use crate::filesystem::Filesystem as _;
 use crate::filesystem::memory::Memory;
 use crate::find_front_matter_in_mdast::find_front_matter_in_mdast;
 use crate::find_table_of_contents_in_mdast::find_table_of_contents_in_mdast;
+use crate::find_unknown_context_references_in_mdast::find_unknown_context_references_in_mdast;
 use crate::generate_sitemap::create_sitemap;
 use crate::string_to_mdast::string_to_mdast;
 
@@ -73,7 +78,7 @@ fn render_document<'render>(
     let component_context = ContentDocumentComponentContext {
         asset_manager: AssetManager::from_esbuild_metafile(esbuild_metafile, asset_path_renderer),
         authors: authors.clone(),
-        available_authors: available_authors,
+        available_authors,
         available_collections,
         content_document_collections_ranked,
         content_document_linker,
@@ -115,8 +120,10 @@ pub async fn build_project(
         generated_page_base_path,
         generate_sitemap,
         is_watching,
+        prompt_discovery,
         rhai_template_renderer,
         source_filesystem,
+        unknown_fields_policy,
     }: BuildProjectParams,
 ) -> Result<BuildProjectResultStub> {
     info!(
@@ -130,8 +137,8 @@ pub async fn build_project(
     let memory_filesystem = Arc::new(Memory::default());
     let syntax_set = SyntaxSet::load_defaults_newlines();
 
-    let mut content_document_basename_by_id: HashMap<String, ContentDocumentBasename> =
-        HashMap::new();
+    let mut content_document_basename_by_id = ContentDocumentBasenameById::default();
+    let mut content_document_content_by_basename = ContentDocumentContentByBasename::default();
     let mut content_document_by_basename: HashMap<
         ContentDocumentBasename,
         ContentDocumentReference,
@@ -145,12 +152,16 @@ pub async fn build_project(
         Default::default();
 
     for file in source_filesystem.read_project_files().await? {
-        if file.kind.is_content() {
+        if file.kind.is_content() && !prompt_discovery.is_included(&file.relative_path) {
             let mdast = string_to_mdast(&file.contents)?;
-            let front_matter: ContentDocumentFrontMatter = find_front_matter_in_mdast(&mdast)?
-                .ok_or_else(|| {
-                    anyhow!("No front matter found in file: {:?}", file.relative_path)
-                })?;
+            let front_matter: ContentDocumentFrontMatter =
+                find_front_matter_in_mdast(&mdast, unknown_fields_policy)
+                    .with_context(|| {
+                        format!("Invalid front matter in file: {:?}", file.relative_path)
+                    })?
+                    .ok_or_else(|| {
+                        anyhow!("No front matter found in file: {:?}", file.relative_path)
+                    })?;
 
             let basename_path = file.get_stem_path_relative_to(&PathBuf::from("content"));
             let basename: ContentDocumentBasename = basename_path.clone().into();
@@ -160,17 +171,28 @@ pub async fn build_project(
                 generated_page_base_path: generated_page_base_path.clone(),
             };
 
-            if let Some(id) = &front_matter.id {
-                if content_document_basename_by_id.contains_key(id) {
-                    error_collection.register_error(
-                        content_document_reference.basename().to_string(),
-                        anyhow!("Duplicate document id: #{id} in '{basename}'"),
-                    );
-                }
+            for reference in find_unknown_context_references_in_mdast(
+                &mdast,
+                "context.front_matter.props",
+                &front_matter
+                    .props
+                    .keys()
+                    .map(|key| key.to_string())
+                    .collect(),
+            ) {
+                warn!(
+                    "Document '{basename}' references '{reference}', which is not defined in its front matter"
+                );
+            }
 
-                content_document_basename_by_id.insert(id.clone(), basename.clone());
+            if let Some(id) = &front_matter.id
+                && let Err(err) = content_document_basename_by_id.register(id, basename.clone())
+            {
+                error_collection
+                    .register_error(content_document_reference.basename().to_string(), err);
             }
 
+            content_document_content_by_basename.register(basename.clone(), &mdast, &file.contents);
             content_document_by_basename
                 .insert(basename.clone(), content_document_reference.clone());
             content_document_list.push(ContentDocument {
@@ -257,7 +279,7 @@ pub async fn build_project(
     }
 
     if !error_collection.is_empty() {
-        return Err(anyhow!("{error_collection}"));
+        return Err(error_collection.into());
     }
 
     let authors_arc = Arc::new(authors);
@@ -276,8 +298,27 @@ pub async fn build_project(
     let content_document_linker = ContentDocumentLinker {
         content_document_basename_by_id: content_document_basename_by_id_arc.clone(),
         content_document_by_basename: content_document_by_basename_arc.clone(),
+        content_document_content_by_basename: Arc::new(content_document_content_by_basename),
     };
 
+    for content_document in &mut content_document_list {
+        let basename = content_document.reference.basename().to_string();
+
+        for reference in &mut content_document.reference.front_matter.references {
+            match content_document_linker.resolve_reference(reference) {
+                Ok(resolved_basename) => *reference = resolved_basename.to_string(),
+                Err(message) => error_collection.register_error(
+                    basename.clone(),
+                    anyhow!("Dangling reference '{reference}': {message}"),
+                ),
+            }
+        }
+    }
+
+    if !error_collection.is_empty() {
+        return Err(error_collection.into());
+    }
+
     content_document_list
         .par_iter()
         .filter(|content_document| {
@@ -382,6 +423,119 @@ pub async fn build_project(
             memory_filesystem,
         })
     } else {
-        Err(anyhow!("{error_collection}"))
+        Err(error_collection.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::formatdoc;
+    use indoc::indoc;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::asset_path_renderer::AssetPathRenderer;
+    use crate::build_authors::build_authors;
+    use crate::compile_shortcodes::compile_shortcodes;
+    use crate::filesystem::read_file_contents_result::ReadFileContentsResult;
+    use crate::filesystem::storage::DEFAULT_MAX_FILE_BYTES;
+    use crate::filesystem::storage::Storage;
+    use crate::prompt_discovery::PromptDiscovery;
+
+    async fn build_with_references(references: &str) -> Result<BuildProjectResultStub> {
+        let base_directory = tempdir()?;
+        let source_filesystem = Arc::new(Storage {
+            base_directory: base_directory.path().to_path_buf(),
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            source_encodings: Default::default(),
+        });
+
+        source_filesystem
+            .set_file_contents(
+                Path::new("shortcodes/Page.rhai"),
+                indoc! {r#"
+                fn template(context, props, content) {
+                    let rendered = "";
+
+                    for reference in context.front_matter.references {
+                        rendered += reference;
+                    }
+
+                    rendered
+                }
+                "#},
+            )
+            .await?;
+        source_filesystem
+            .set_file_contents(
+                Path::new("content/guides/api.md"),
+                indoc! {r#"
+                +++
+                description = "API"
+                id = "api-guide"
+                layout = "Page"
+                title = "API"
+                +++
+                "#},
+            )
+            .await?;
+        source_filesystem
+            .set_file_contents(
+                Path::new("content/overview.md"),
+                &formatdoc! {r#"
+                +++
+                description = "Overview"
+                layout = "Page"
+                references = {references}
+                title = "Overview"
+                +++
+                "#},
+            )
+            .await?;
+
+        let rhai_template_renderer = compile_shortcodes(source_filesystem.clone()).await?;
+        let authors = build_authors(source_filesystem.clone()).await?;
+
+        build_project(BuildProjectParams {
+            asset_path_renderer: AssetPathRenderer {
+                base_path: "/".to_string(),
+            },
+            authors,
+            esbuild_metafile: Default::default(),
+            generated_page_base_path: "/".to_string(),
+            generate_sitemap: false,
+            is_watching: false,
+            prompt_discovery: PromptDiscovery::new(&["prompts/**/*.md".to_string()], &[])?,
+            rhai_template_renderer,
+            source_filesystem,
+            unknown_fields_policy: Default::default(),
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_references_are_resolved() -> Result<()> {
+        let BuildProjectResultStub {
+            memory_filesystem, ..
+        } = build_with_references(r##"["#api-guide"]"##).await?;
+
+        let ReadFileContentsResult::Found { contents } = memory_filesystem
+            .read_file_contents(Path::new("overview/index.html"))
+            .await?
+        else {
+            return Err(anyhow!("Referencing document should be rendered"));
+        };
+
+        assert_eq!(contents, "guides/api");
+
+        let Err(err) = build_with_references(r##"["#api-guide", "guides/missing"]"##).await else {
+            return Err(anyhow!("Build should fail when a reference is dangling"));
+        };
+
+        assert!(err.to_string().contains(
+            "Dangling reference 'guides/missing': Document does not exist: guides/missing"
+        ));
+
+        Ok(())
     }
 }
@@ -1,33 +1,968 @@
+use anyhow::Context as _;
 use anyhow::Result;
 use anyhow::anyhow;
+use chrono::Utc;
+use log::warn;
 
 use crate::build_prompt_document_controller_params::BuildPromptDocumentControllerParams;
+use crate::find_fetch_urls_in_mdast::find_fetch_urls_in_mdast;
 use crate::find_front_matter_in_mdast::find_front_matter_in_mdast;
+use crate::find_missing_component_props_in_mdast::find_missing_component_props_in_mdast;
+use crate::find_unknown_context_references_in_mdast::find_unknown_context_references_in_mdast;
+use crate::mdast_uses_assets::mdast_uses_assets;
 use crate::prompt_document_controller::PromptDocumentController;
 use crate::prompt_document_front_matter::PromptDocumentFrontMatter;
+use crate::prompt_provenance::PromptProvenance;
+use crate::prompt_uncategorized_policy::PromptUncategorizedPolicy;
 use crate::string_to_mdast::string_to_mdast;
+use crate::take_title_heading_from_mdast::take_title_heading_from_mdast;
+
+/// Argument names are used as keys in dotted template access, like
+/// `context.arguments.name`, so they have to be identifiers
+fn is_valid_argument_name(argument_name: &str) -> bool {
+    let mut characters = argument_name.chars();
+
+    characters
+        .next()
+        .is_some_and(|character| character.is_ascii_alphabetic() || character == '_')
+        && characters.all(|character| character.is_ascii_alphanumeric() || character == '_')
+}
 
 pub fn build_prompt_document_controller(
     BuildPromptDocumentControllerParams {
+        allow_remote_images,
         asset_path_renderer,
         content_document_linker,
         esbuild_metafile,
+        fetch_policy,
+        fetcher,
         file,
+        image_policy,
         name,
+        prompt_collection_configs,
+        provenance_meta,
+        random_seed,
+        render_limits_policy,
+        render_pipeline,
         rhai_template_renderer,
+        token_counter,
+        uncategorized_policy,
+        unknown_fields_policy,
     }: BuildPromptDocumentControllerParams,
 ) -> Result<PromptDocumentController> {
-    let mdast = string_to_mdast(&file.contents)?;
-    let front_matter: PromptDocumentFrontMatter = find_front_matter_in_mdast(&mdast)?
-        .ok_or_else(|| anyhow!("No front matter found in file: {:?}", file.relative_path))?;
+    let mut mdast = string_to_mdast(&file.contents)?;
+    let title_heading = take_title_heading_from_mdast(&mut mdast)?;
+    let mut front_matter: PromptDocumentFrontMatter =
+        find_front_matter_in_mdast(&mdast, unknown_fields_policy)
+            .with_context(|| format!("Invalid front matter in file: {:?}", file.relative_path))?
+            .ok_or_else(|| anyhow!("No front matter found in file: {:?}", file.relative_path))?;
+
+    front_matter.inherit_collection_arguments(&prompt_collection_configs);
+    front_matter
+        .parse_argument_defaults()
+        .with_context(|| format!("Prompt '{name}' declares invalid arguments"))?;
+
+    if let Some(argument_name) = front_matter
+        .arguments
+        .keys()
+        .find(|argument_name| !is_valid_argument_name(argument_name))
+    {
+        return Err(anyhow!(
+            "Prompt '{name}' declares argument '{argument_name}', which is not a valid name (use letters, digits, and underscores, not starting with a digit)"
+        ));
+    }
+
+    if front_matter
+        .excerpts
+        .iter()
+        .any(|excerpt| excerpt.excerpt_type.trim().is_empty())
+    {
+        return Err(anyhow!(
+            "Prompt '{name}' declares an excerpt without a type"
+        ));
+    }
+
+    if front_matter.title.is_empty() {
+        front_matter.title = title_heading.ok_or_else(|| {
+            anyhow!("Prompt '{name}' has no title and no heading to derive it from")
+        })?;
+    }
+
+    for reference in find_unknown_context_references_in_mdast(
+        &mdast,
+        "context.arguments",
+        &front_matter.arguments.keys().cloned().collect(),
+    ) {
+        warn!(
+            "Prompt '{name}' references '{reference}', which is not declared in its front matter"
+        );
+    }
+
+    for reference in find_unknown_context_references_in_mdast(
+        &mdast,
+        "context.front_matter.props",
+        &front_matter
+            .props
+            .keys()
+            .map(|key| key.to_string())
+            .collect(),
+    ) {
+        warn!(
+            "Prompt '{name}' references '{reference}', which is not declared in its front matter"
+        );
+    }
+
+    if front_matter.deprecated {
+        warn!("Prompt '{name}' is deprecated");
+    }
+
+    if let Some(layout) = &front_matter.layout
+        && !rhai_template_renderer.has_template(layout)
+    {
+        return Err(anyhow!(
+            "Prompt '{name}' references layout '{layout}', which does not exist"
+        ));
+    }
+
+    let missing_component_props =
+        find_missing_component_props_in_mdast(&mdast, &rhai_template_renderer);
+
+    if !missing_component_props.is_empty() {
+        return Err(anyhow!(
+            "Prompt '{name}' uses components without their required props: {}",
+            missing_component_props
+                .iter()
+                .map(|(component, prop)| format!("'{prop}' on <{component}>"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+    }
+
+    if !front_matter.is_categorized() {
+        let message = format!("Prompt '{name}' does not belong to any collection");
+
+        match uncategorized_policy {
+            PromptUncategorizedPolicy::Allow => {}
+            PromptUncategorizedPolicy::Deny => return Err(anyhow!(message)),
+            PromptUncategorizedPolicy::Warn => warn!("{message}"),
+        }
+    }
+
+    let provenance = provenance_meta.then(|| PromptProvenance {
+        built_at: Utc::now(),
+        poet_version: env!("CARGO_PKG_VERSION").to_string(),
+        source_path: file.relative_path.display().to_string(),
+    });
+    let render_limits = render_limits_policy.resolve(&front_matter.limits)?;
+    let uses_assets = front_matter.layout.is_some() || mdast_uses_assets(&mdast);
 
     Ok(PromptDocumentController {
+        allow_remote_images,
         asset_path_renderer,
         content_document_linker,
         esbuild_metafile,
+        fetch_policy,
+        fetch_urls: find_fetch_urls_in_mdast(&mdast)?,
+        fetcher,
         front_matter,
+        image_policy,
+        inline_images: Default::default(),
         name,
         mdast,
+        prompt_collection_configs,
+        provenance,
+        random_seed,
+        relative_path: file.relative_path,
+        render_limits,
+        render_limits_policy,
+        render_pipeline,
         rhai_template_renderer,
+        source: file.contents,
+        token_counter,
+        uncategorized_policy,
+        unknown_fields_policy,
+        uses_assets,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use indoc::formatdoc;
+    use indoc::indoc;
+    use log::LevelFilter;
+    use log::Log;
+    use log::Metadata;
+    use log::Record;
+    use rhai_components::builds_engine::BuildsEngine as _;
+    use rhai_components::component_syntax::component_reference::ComponentReference;
+    use rhai_components::rhai_template_renderer::RhaiTemplateRenderer;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::content_document_content_by_basename::ContentDocumentContentByBasename;
+    use crate::content_document_front_matter::ContentDocumentFrontMatter;
+    use crate::content_document_linker::ContentDocumentLinker;
+    use crate::content_document_reference::ContentDocumentReference;
+    use crate::filesystem::file_entry_stub::FileEntryStub;
+    use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+    use crate::mcp::content_block::ContentBlock;
+    use crate::mcp::content_block::embedded_resource::EmbeddedResource;
+    use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
+    use crate::mcp::prompt_controller::PromptController as _;
+    use crate::mcp::resource_content::ResourceContent;
+    use crate::mcp::resource_content::TextResourceContent;
+    use crate::prompt_collection_config::PromptCollectionConfig;
+    use crate::prompt_document_controller::PromptDocumentController;
+    use crate::rhai_template_renderer_factory::RhaiTemplateRendererFactory;
+    use crate::string_to_mdast::string_to_mdast;
+
+    #[test]
+    fn test_missing_layout_fails_build() -> Result<()> {
+        let result = build_prompt_document_controller(BuildPromptDocumentControllerParams::mock(
+            "with-layout",
+            indoc! {r#"
+            +++
+            description = "with layout"
+            layout = "missing-layout"
+            title = "With layout"
+
+            [arguments]
+            +++
+
+            **user**: Hello
+            "#},
+        )?);
+
+        let Err(err) = result else {
+            return Err(anyhow!("Build should fail when the layout is missing"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Prompt 'with-layout' references layout 'missing-layout', which does not exist"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_argument_name_with_space_fails_build() -> Result<()> {
+        let result = build_prompt_document_controller(BuildPromptDocumentControllerParams::mock(
+            "spaced",
+            indoc! {r#"
+            +++
+            description = "spaced"
+            title = "Spaced"
+
+            [arguments."my arg"]
+            description = "Badly named"
+            required = true
+            title = "My arg"
+            +++
+
+            **user**: Hello
+            "#},
+        )?);
+
+        let Err(err) = result else {
+            return Err(anyhow!(
+                "Build should fail when an argument name has a space"
+            ));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Prompt 'spaced' declares argument 'my arg', which is not a valid name (use letters, digits, and underscores, not starting with a digit)"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_required_component_prop_fails_build() -> Result<()> {
+        let base_directory = tempdir()?;
+
+        let contents = indoc! {r#"
+            //! [[props]]
+            //! name = "title"
+            //! required = true
+            //! type = "string"
+
+            fn template(context, props, content) {
+                props.title
+            }
+        "#};
+
+        fs::create_dir(base_directory.path().join("shortcodes"))?;
+        fs::write(base_directory.path().join("shortcodes/Card.rhai"), contents)?;
+
+        let rhai_template_factory = RhaiTemplateRendererFactory::new(
+            base_directory.path().to_path_buf(),
+            PathBuf::from("shortcodes"),
+        );
+
+        rhai_template_factory.register_component_file(
+            FileEntryStub {
+                contents: contents.to_string(),
+                relative_path: PathBuf::from("shortcodes/Card.rhai"),
+            }
+            .try_into()?,
+        )?;
+
+        let rhai_template_renderer: RhaiTemplateRenderer = rhai_template_factory.try_into()?;
+        let build_with_card = |card: &str| {
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                rhai_template_renderer: rhai_template_renderer.clone(),
+                ..BuildPromptDocumentControllerParams::mock(
+                    "carded",
+                    &formatdoc! {r#"
+                    +++
+                    description = "carded"
+                    title = "Carded"
+
+                    [arguments]
+                    +++
+
+                    **user**: Hello
+
+                    {card}
+                    "#},
+                )?
+            })
+        };
+
+        assert!(build_with_card(r#"<Card title="Greeting" />"#).is_ok());
+        assert!(build_with_card("<Card title={context.front_matter.title} />").is_ok());
+
+        let Err(err) = build_with_card("<Card />") else {
+            return Err(anyhow!("Build should fail when a required prop is missing"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Prompt 'carded' uses components without their required props: 'title' on <Card>"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_component_pulls_excerpt_by_type() -> Result<()> {
+        let base_directory = tempdir()?;
+
+        fs::create_dir(base_directory.path().join("shortcodes"))?;
+        fs::write(
+            base_directory.path().join("shortcodes/Snippet.rhai"),
+            indoc! {r#"
+                fn template(context, props, content) {
+                    context.excerpt(props.kind)
+                }
+            "#},
+        )?;
+
+        let rhai_template_factory = RhaiTemplateRendererFactory::new(
+            base_directory.path().to_path_buf(),
+            PathBuf::from("shortcodes"),
+        );
+
+        rhai_template_factory
+            .component_registry()
+            .register_component(ComponentReference {
+                description: None,
+                name: "Snippet".to_string(),
+                path: "Snippet".to_string(),
+                props: Default::default(),
+            })?;
+
+        let rhai_template_renderer: RhaiTemplateRenderer = rhai_template_factory.try_into()?;
+        let build_with_excerpt_type = |excerpt_type: &str| {
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                rhai_template_renderer: rhai_template_renderer.clone(),
+                ..BuildPromptDocumentControllerParams::mock(
+                    "excerpted",
+                    &formatdoc! {r#"
+                    +++
+                    description = "excerpted"
+                    title = "Excerpted"
+
+                    [arguments]
+
+                    [[excerpts]]
+                    content = "Answer in English."
+                    type = "{excerpt_type}"
+                    +++
+
+                    **user**:
+
+                    <Snippet kind="language" />
+                    "#},
+                )?
+            })
+        };
+
+        let response = build_with_excerpt_type("language")?
+            .respond_to(PromptsGet::mock("excerpted", Default::default()))
+            .await?;
+
+        assert_eq!(response.messages[0].content, "Answer in English.".into());
+
+        let Err(err) = build_with_excerpt_type(" ") else {
+            return Err(anyhow!("Build should fail when an excerpt has no type"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Prompt 'excerpted' declares an excerpt without a type"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_front_matter_key_fails_build() -> Result<()> {
+        let result = build_prompt_document_controller(BuildPromptDocumentControllerParams::mock(
+            "duplicated",
+            indoc! {r#"
+            +++
+            description = "duplicated"
+            title = "Duplicated"
+            title = "Duplicated again"
+
+            [arguments]
+            +++
+
+            **user**: Hello
+            "#},
+        )?);
+
+        let Err(err) = result else {
+            return Err(anyhow!(
+                "Build should fail when a front matter key is duplicated"
+            ));
+        };
+
+        assert_eq!(
+            format!("{err:#}"),
+            "Invalid front matter in file: \"prompts/duplicated.md\": Front matter defines 'title' more than once"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_front_matter_field_is_policy_dependent() -> Result<()> {
+        let build_with_policy = |unknown_fields_policy: FrontMatterUnknownFieldsPolicy| {
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                unknown_fields_policy,
+                ..BuildPromptDocumentControllerParams::mock(
+                    "migrated",
+                    indoc! {r#"
+                    +++
+                    description = "migrated"
+                    generated_by = "other-tool"
+                    title = "Migrated"
+
+                    [arguments.topic]
+                    description = "Topic"
+                    required = true
+                    title = "Topic"
+                    widget = "textarea"
+                    +++
+
+                    **user**: Hello
+                    "#},
+                )?
+            })
+        };
+
+        let controller = build_with_policy(FrontMatterUnknownFieldsPolicy::Warn)?;
+
+        assert_eq!(controller.front_matter.title, "Migrated");
+        assert!(controller.front_matter.arguments["topic"].required);
+
+        let Err(err) = build_with_policy(FrontMatterUnknownFieldsPolicy::Deny) else {
+            return Err(anyhow!(
+                "Build should fail on unknown fields in strict mode"
+            ));
+        };
+
+        assert!(
+            format!("{err:#}")
+                .contains("Unknown front matter fields: generated_by, arguments.topic.widget")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_argument_pattern_fails_build() -> Result<()> {
+        let result = build_prompt_document_controller(BuildPromptDocumentControllerParams::mock(
+            "dated",
+            indoc! {r#"
+            +++
+            description = "dated"
+            title = "Dated"
+
+            [arguments.date]
+            description = "ISO date"
+            pattern = '\d{4}-(\d{2}'
+            required = true
+            title = "Date"
+            +++
+
+            **user**: Hello
+            "#},
+        )?);
+
+        let Err(err) = result else {
+            return Err(anyhow!(
+                "Build should fail when an argument pattern is invalid"
+            ));
+        };
+
+        assert!(format!("{err:#}").contains("Invalid argument pattern '\\d{4}-(\\d{2}'"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_argument_default_fails_build() -> Result<()> {
+        let build_with_argument = |argument: &str| {
+            build_prompt_document_controller(BuildPromptDocumentControllerParams::mock(
+                "defaults",
+                &formatdoc! {r#"
+                +++
+                description = "defaults"
+                title = "Defaults"
+
+                [arguments.priority]
+                {argument}
+                description = "Priority"
+                required = false
+                title = "Priority"
+                +++
+
+                **user**: Hello
+                "#},
+            )?)
+        };
+
+        let Err(err) = build_with_argument(
+            r#"choices = ["low", "high"]
+                default = "urgent""#,
+        ) else {
+            return Err(anyhow!(
+                "Build should fail when the default is not a choice"
+            ));
+        };
+
+        assert_eq!(
+            format!("{err:#}"),
+            "Prompt 'defaults' declares invalid arguments: Argument 'priority' has an invalid default: Argument 'priority' must be one of [low, high], got 'urgent'"
+        );
+
+        let Err(err) = build_with_argument(
+            r#"choices = ["1", "two"]
+                kind = "integer""#,
+        ) else {
+            return Err(anyhow!("Build should fail when a choice is not an integer"));
+        };
+
+        assert_eq!(
+            format!("{err:#}"),
+            "Prompt 'defaults' declares invalid arguments: Argument 'priority' has an invalid choice 'two': Argument 'priority' expects integer input: Expected a whole number: invalid digit found in string"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_title_and_heading_fails_build() -> Result<()> {
+        let result = build_prompt_document_controller(BuildPromptDocumentControllerParams::mock(
+            "untitled",
+            indoc! {r#"
+            +++
+            description = "untitled"
+
+            [arguments]
+            +++
+
+            **user**: Hello
+            "#},
+        )?);
+
+        let Err(err) = result else {
+            return Err(anyhow!("Build should fail without a title or a heading"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Prompt 'untitled' has no title and no heading to derive it from"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_collection_arguments_are_inherited() -> Result<()> {
+        let prompt_collection_config: PromptCollectionConfig = toml::from_str(indoc! {r#"
+            [arguments.code]
+            description = "Code to review"
+            required = true
+            title = "Code"
+
+            [arguments.language]
+            description = "Language of the code"
+            required = false
+            title = "Language"
+        "#})?;
+
+        let controller = build_prompt_document_controller(BuildPromptDocumentControllerParams {
+            prompt_collection_configs: Arc::new(HashMap::from([(
+                "review".to_string(),
+                prompt_collection_config,
+            )])),
+            ..BuildPromptDocumentControllerParams::mock(
+                "security-review",
+                indoc! {r#"
+                +++
+                collections = ["review"]
+                description = "security review"
+                title = "Security review"
+
+                [arguments.language]
+                description = "Language the code is written in"
+                required = true
+                title = "Language"
+                +++
+
+                **user**: Review {context.arguments.code.input}
+                "#},
+            )?
+        })?;
+
+        let arguments = &controller.front_matter.arguments;
+
+        assert_eq!(
+            arguments.keys().collect::<Vec<_>>(),
+            vec!["language", "code"]
+        );
+        assert!(arguments["code"].required);
+        assert_eq!(
+            arguments["language"].description,
+            "Language the code is written in"
+        );
+
+        Ok(())
+    }
+
+    thread_local! {
+        static CAPTURED_LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Keeps messages on the thread that logged them, so logs of tests
+    /// running concurrently on other threads stay out
+    struct ThreadLogCapture;
+
+    impl Log for ThreadLogCapture {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            CAPTURED_LOGS.with_borrow_mut(|captured_logs| {
+                captured_logs.push(record.args().to_string());
+            });
+        }
+
+        fn flush(&self) {}
+    }
+
+    static THREAD_LOG_CAPTURE: ThreadLogCapture = ThreadLogCapture;
+
+    #[tokio::test]
+    async fn test_render_tree_is_traced() -> Result<()> {
+        let base_directory = tempdir()?;
+
+        fs::create_dir(base_directory.path().join("shortcodes"))?;
+        fs::write(
+            base_directory.path().join("shortcodes/Emphasis.rhai"),
+            indoc! {r#"
+                fn template(context, props, content) {
+                    component {
+                        <em>{props.text}</em>
+                    }
+                }
+            "#},
+        )?;
+
+        let rhai_template_factory = RhaiTemplateRendererFactory::new(
+            base_directory.path().to_path_buf(),
+            PathBuf::from("shortcodes"),
+        );
+
+        rhai_template_factory
+            .component_registry()
+            .register_component(ComponentReference {
+                description: None,
+                name: "Emphasis".to_string(),
+                path: "Emphasis".to_string(),
+                props: Default::default(),
+            })?;
+
+        log::set_logger(&THREAD_LOG_CAPTURE)
+            .map_err(|err| anyhow!("Unable to capture logs: {err}"))?;
+        log::set_max_level(LevelFilter::Trace);
+
+        build_prompt_document_controller(BuildPromptDocumentControllerParams {
+            rhai_template_renderer: rhai_template_factory.try_into()?,
+            ..BuildPromptDocumentControllerParams::mock(
+                "traced",
+                indoc! {r#"
+                +++
+                description = "traced"
+                title = "Traced"
+
+                [arguments]
+                +++
+
+                **user**:
+
+                <Emphasis text="traced" />
+
+                **assistant**: Understood
+                "#},
+            )?
+        })?
+        .respond_to(PromptsGet::mock("traced", Default::default()))
+        .await?;
+
+        let captured_logs = CAPTURED_LOGS.take();
+        let has_message = |prefix: &str| {
+            captured_logs
+                .iter()
+                .any(|captured_log| captured_log.starts_with(prefix))
+        };
+
+        assert!(has_message("Calling component 'Emphasis'"));
+        assert!(has_message("Evaluating tag stack: Tag {"));
+        assert!(has_message("Switching role to Message(Assistant)"));
+        assert!(has_message("Flushing User message"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resource_component_embeds_content_document_from_components() -> Result<()> {
+        let reference = ContentDocumentReference {
+            basename_path: PathBuf::from("guides/api"),
+            front_matter: ContentDocumentFrontMatter::mock("guides/api"),
+            generated_page_base_path: "/".to_string(),
+        };
+        let contents = "+++\ntitle = \"API\"\n+++\n\n# API guide\n";
+        let mut content_document_content_by_basename = ContentDocumentContentByBasename::default();
+
+        content_document_content_by_basename.register(
+            reference.basename(),
+            &string_to_mdast(contents)?,
+            contents,
+        );
+
+        let content_document_linker = ContentDocumentLinker {
+            content_document_basename_by_id: Default::default(),
+            content_document_by_basename: Arc::new(HashMap::from([(
+                reference.basename(),
+                reference,
+            )])),
+            content_document_content_by_basename: Arc::new(content_document_content_by_basename),
+        };
+        let base_directory = tempdir()?;
+        let contents = indoc! {r#"
+            fn template(context, props, content) {
+                component {
+                    <Resource uri={props.uri} />
+                }
+            }
+        "#};
+
+        fs::create_dir(base_directory.path().join("shortcodes"))?;
+        fs::write(
+            base_directory.path().join("shortcodes/Guide.rhai"),
+            contents,
+        )?;
+
+        let rhai_template_factory = RhaiTemplateRendererFactory::new(
+            base_directory.path().to_path_buf(),
+            PathBuf::from("shortcodes"),
+        );
+
+        rhai_template_factory.register_component_file(
+            FileEntryStub {
+                contents: contents.to_string(),
+                relative_path: PathBuf::from("shortcodes/Guide.rhai"),
+            }
+            .try_into()?,
+        )?;
+
+        let rhai_template_renderer: RhaiTemplateRenderer = rhai_template_factory.try_into()?;
+        let respond_with_resource = async |component: &str| {
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                content_document_linker: content_document_linker.clone(),
+                rhai_template_renderer: rhai_template_renderer.clone(),
+                ..BuildPromptDocumentControllerParams::mock(
+                    "embedding",
+                    &formatdoc! {r#"
+                    +++
+                    description = "embedding"
+                    title = "Embedding"
+
+                    [arguments]
+                    +++
+
+                    **user**: Answer using this guide
+
+                    {component}
+                    "#},
+                )?
+            })?
+            .respond_to(PromptsGet::mock("embedding", Default::default()))
+            .await
+        };
+
+        let embedded_resource = ContentBlock::EmbeddedResource(EmbeddedResource {
+            resource: ResourceContent::Text(TextResourceContent {
+                mime_type: "text/markdown".to_string(),
+                text: "# API guide\n".to_string(),
+                uri: "poet://content/guides/api".to_string(),
+            }),
+        });
+
+        for component in [
+            r#"<Resource uri="poet://content/guides/api" />"#,
+            r#"<Guide uri="poet://content/guides/api" />"#,
+        ] {
+            let response = respond_with_resource(component).await?;
+
+            assert_eq!(response.messages.len(), 2);
+            assert_eq!(response.messages[1].content, embedded_resource);
+        }
+
+        let Err(err) =
+            respond_with_resource(r#"<Resource uri="poet://content/guides/missing" />"#).await
+        else {
+            return Err(anyhow!("Embedding a missing document should fail"));
+        };
+
+        assert!(format!("{err:#}").contains("'poet://content/guides/missing'"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shortcodes_shadow_builtin_names() -> Result<()> {
+        let base_directory = tempdir()?;
+        let contents = indoc! {r#"
+            fn template(context, props, content) {
+                `Now playing: ${props.src}`
+            }
+        "#};
+
+        let rhai_template_factory = RhaiTemplateRendererFactory::new(
+            base_directory.path().to_path_buf(),
+            PathBuf::from("shortcodes"),
+        );
+
+        fs::create_dir(base_directory.path().join("shortcodes"))?;
+
+        // Covers both a built-in component and a tag built into the evaluator
+        for name in ["Audio", "Comment"] {
+            let relative_path = PathBuf::from(format!("shortcodes/{name}.rhai"));
+
+            fs::write(base_directory.path().join(&relative_path), contents)?;
+            rhai_template_factory.register_component_file(
+                FileEntryStub {
+                    contents: contents.to_string(),
+                    relative_path,
+                }
+                .try_into()?,
+            )?;
+        }
+
+        let response = build_prompt_document_controller(BuildPromptDocumentControllerParams {
+            rhai_template_renderer: rhai_template_factory.try_into()?,
+            ..BuildPromptDocumentControllerParams::mock(
+                "playlist",
+                indoc! {r#"
+                +++
+                description = "playlist"
+                title = "Playlist"
+
+                [arguments]
+                +++
+
+                **user**:
+
+                <Audio src="intro.mp3" />
+
+                <Comment src="outro.mp3" />
+                "#},
+            )?
+        })?
+        .respond_to(PromptsGet::mock("playlist", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(
+            response.messages[0].content,
+            "Now playing: intro.mp3\n\nNow playing: outro.mp3".into()
+        );
+
+        Ok(())
+    }
+
+    fn build_with_collections(collections: &str) -> Result<PromptDocumentController> {
+        build_prompt_document_controller(BuildPromptDocumentControllerParams {
+            uncategorized_policy: PromptUncategorizedPolicy::Deny,
+            ..BuildPromptDocumentControllerParams::mock(
+                "catalog",
+                &formatdoc! {r#"
+                    +++
+                    {collections}
+                    description = "catalog"
+                    title = "Catalog"
+
+                    [arguments]
+                    +++
+
+                    **user**: Hello
+                "#},
+            )?
+        })
+    }
+
+    #[test]
+    fn test_uncategorized_prompt_is_reported() -> Result<()> {
+        let Err(err) = build_with_collections("") else {
+            return Err(anyhow!("Build should fail for an uncategorized prompt"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Prompt 'catalog' does not belong to any collection"
+        );
+        assert!(
+            build_with_collections(r#"collections = ["onboarding"]"#)?
+                .front_matter
+                .is_categorized()
+        );
+        assert!(build_with_collections(r#"primary_collection = "onboarding""#).is_ok());
+
+        Ok(())
+    }
+}
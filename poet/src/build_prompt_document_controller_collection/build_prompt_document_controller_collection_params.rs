@@ -6,11 +6,38 @@ use rhai_components::rhai_template_renderer::RhaiTemplateRenderer;
 use crate::asset_path_renderer::AssetPathRenderer;
 use crate::content_document_linker::ContentDocumentLinker;
 use crate::filesystem::storage::Storage;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+use crate::mcp::prompt_metrics::PromptMetrics;
+use crate::prompt_discovery::PromptDiscovery;
+use crate::prompt_fetch_policy::PromptFetchPolicy;
+use crate::prompt_image_policy::PromptImagePolicy;
+use crate::prompt_render_limits_policy::PromptRenderLimitsPolicy;
+use crate::prompt_render_pipeline::PromptRenderPipeline;
+use crate::prompt_uncategorized_policy::PromptUncategorizedPolicy;
+use crate::token_counter::TokenCounter;
 
 pub struct BuildPromptControllerCollectionParams {
+    pub allow_remote_images: bool,
+    pub argument_meta: bool,
     pub asset_path_renderer: AssetPathRenderer,
     pub content_document_linker: ContentDocumentLinker,
     pub esbuild_metafile: Arc<EsbuildMetaFile>,
+    /// Fails the whole build if any prompt is broken. Otherwise the prompts
+    /// that built are returned together with the errors of the rest.
+    pub fail_fast: bool,
+    pub fetch_policy: PromptFetchPolicy,
+    pub image_policy: PromptImagePolicy,
+    pub metrics: Arc<dyn PromptMetrics>,
+    /// Which files are built as prompts
+    pub prompt_discovery: PromptDiscovery,
+    pub provenance_meta: bool,
+    /// Makes randomness helpers deterministic across renders
+    pub random_seed: Option<u64>,
+    pub render_limits_policy: PromptRenderLimitsPolicy,
+    pub render_pipeline: PromptRenderPipeline,
     pub rhai_template_renderer: RhaiTemplateRenderer,
+    pub token_counter: Arc<dyn TokenCounter>,
+    pub uncategorized_policy: PromptUncategorizedPolicy,
+    pub unknown_fields_policy: FrontMatterUnknownFieldsPolicy,
     pub source_filesystem: Arc<Storage>,
 }
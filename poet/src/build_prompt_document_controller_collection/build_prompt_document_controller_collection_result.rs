@@ -0,0 +1,9 @@
+use crate::document_error_collection::DocumentErrorCollection;
+use crate::mcp::prompt_controller_collection::PromptControllerCollection;
+
+pub struct BuildPromptControllerCollectionResult {
+    /// Prompts that failed to build, when the build continues on errors
+    pub error_collection: DocumentErrorCollection,
+    /// Prompts that were built successfully
+    pub prompt_controller_collection: PromptControllerCollection,
+}
@@ -1,70 +1,357 @@
 pub mod build_prompt_document_controller_collection_params;
+pub mod build_prompt_document_controller_collection_result;
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use anyhow::Result;
 use anyhow::anyhow;
 use dashmap::DashMap;
+use dashmap::Entry;
 use log::info;
 use rayon::iter::IntoParallelIterator as _;
 use rayon::iter::ParallelIterator as _;
 
 use crate::build_prompt_document_controller::build_prompt_document_controller;
 use crate::build_prompt_document_controller_collection::build_prompt_document_controller_collection_params::BuildPromptControllerCollectionParams;
+use crate::build_prompt_document_controller_collection::build_prompt_document_controller_collection_result::BuildPromptControllerCollectionResult;
 use crate::build_prompt_document_controller_params::BuildPromptDocumentControllerParams;
 use crate::build_timer::BuildTimer;
 use crate::document_error_collection::DocumentErrorCollection;
 use crate::filesystem::Filesystem as _;
 use crate::mcp::prompt_controller::PromptController;
 use crate::mcp::prompt_controller_collection::PromptControllerCollection;
+use crate::mcp::prompt_controller_metered::PromptControllerMetered;
+use crate::parse_front_matter::parse_front_matter;
+use crate::prompt_document_controller::PromptDocumentController;
+use crate::prompt_collection_config::PromptCollectionConfig;
+use crate::prompt_fetcher::PromptFetcher;
+use crate::prompt_fetcher_http::PromptFetcherHttp;
 
 pub async fn build_prompt_document_controller_collection(
     BuildPromptControllerCollectionParams {
+        allow_remote_images,
+        argument_meta,
         asset_path_renderer,
         content_document_linker,
         esbuild_metafile,
+        fail_fast,
+        fetch_policy,
+        image_policy,
+        metrics,
+        prompt_discovery,
+        provenance_meta,
+        random_seed,
+        render_limits_policy,
+        render_pipeline,
         rhai_template_renderer,
         source_filesystem,
+        token_counter,
+        uncategorized_policy,
+        unknown_fields_policy,
     }: BuildPromptControllerCollectionParams,
-) -> Result<PromptControllerCollection> {
+) -> Result<BuildPromptControllerCollectionResult> {
     info!("Processing prompt files...");
 
     let _build_timer = BuildTimer::default();
     let error_collection: DocumentErrorCollection = Default::default();
+    let prompt_document_controller_map: DashMap<String, PromptDocumentController> =
+        Default::default();
     let prompt_controller_map: DashMap<String, Arc<dyn PromptController>> = Default::default();
+    let project_files = source_filesystem.read_project_files().await?;
+    let mut prompt_collection_configs: HashMap<String, PromptCollectionConfig> = Default::default();
 
-    source_filesystem
-        .read_project_files()
-        .await?
+    for file in project_files
+        .iter()
+        .filter(|file| file.kind.is_prompt_collection())
+    {
+        let collection = file.get_stem_relative_to(&PathBuf::from("prompt_collections"));
+
+        match parse_front_matter::<PromptCollectionConfig>(&file.contents, unknown_fields_policy) {
+            Ok(prompt_collection_config) => {
+                prompt_collection_configs.insert(collection, prompt_collection_config);
+            }
+            Err(err) => {
+                error_collection.register_error(
+                    file.relative_path.display().to_string(),
+                    anyhow!("Failed to parse prompt collection file: {err}"),
+                );
+            }
+        }
+    }
+
+    let prompt_collection_configs = Arc::new(prompt_collection_configs);
+    let fetcher: Arc<dyn PromptFetcher> = Arc::new(PromptFetcherHttp::new()?);
+
+    project_files
         .into_par_iter()
-        .filter(|file| file.kind.is_prompt())
+        .filter(|file| prompt_discovery.is_prompt(file))
         .for_each(|file| {
-            let name = file
-                .get_stem_path_relative_to(&PathBuf::from("prompts"))
-                .display()
-                .to_string();
+            let name = prompt_discovery.prompt_name(&file);
 
             match build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                allow_remote_images,
                 asset_path_renderer: asset_path_renderer.clone(),
                 content_document_linker: content_document_linker.clone(),
                 esbuild_metafile: esbuild_metafile.clone(),
+                fetch_policy: fetch_policy.clone(),
+                fetcher: fetcher.clone(),
                 file,
+                image_policy,
                 name: name.clone(),
+                prompt_collection_configs: prompt_collection_configs.clone(),
+                provenance_meta,
+                random_seed,
+                render_limits_policy,
+                render_pipeline: render_pipeline.clone(),
                 rhai_template_renderer: rhai_template_renderer.clone(),
+                token_counter: token_counter.clone(),
+                uncategorized_policy,
+                unknown_fields_policy,
             }) {
-                Ok(prompt_document_controller) => {
-                    prompt_controller_map.insert(name, Arc::new(prompt_document_controller));
-                }
+                Ok(prompt_document_controller) => match prompt_document_controller_map.entry(name) {
+                    Entry::Occupied(occupied) => {
+                        // Sorted, so the message does not depend on which file
+                        // was built first
+                        let mut relative_paths = [
+                            occupied.get().relative_path.display().to_string(),
+                            prompt_document_controller.relative_path.display().to_string(),
+                        ];
+
+                        relative_paths.sort();
+
+                        let [first_relative_path, second_relative_path] = relative_paths;
+
+                        error_collection.register_error(
+                            occupied.key().clone(),
+                            anyhow!(
+                                "Prompt name is derived from both '{first_relative_path}' and '{second_relative_path}'"
+                            ),
+                        );
+                    }
+                    Entry::Vacant(vacant) => {
+                        vacant.insert(prompt_document_controller);
+                    }
+                },
                 Err(err) => {
                     error_collection.register_error(name, err);
                 }
             }
         });
 
-    if !error_collection.is_empty() {
-        return Err(anyhow!("{error_collection}"));
+    for (name, mut prompt_document_controller) in prompt_document_controller_map {
+        match prompt_document_controller
+            .load_inline_images(&*source_filesystem)
+            .await
+        {
+            Ok(()) => {
+                prompt_controller_map.insert(
+                    name,
+                    Arc::new(PromptControllerMetered {
+                        inner: Arc::new(prompt_document_controller),
+                        metrics: metrics.clone(),
+                    }),
+                );
+            }
+            Err(err) => {
+                error_collection.register_error(name, err);
+            }
+        }
+    }
+
+    if fail_fast && !error_collection.is_empty() {
+        return Err(error_collection.into());
+    }
+
+    let mut prompt_controller_collection: PromptControllerCollection = prompt_controller_map.into();
+
+    prompt_controller_collection.argument_meta = argument_meta;
+
+    Ok(BuildPromptControllerCollectionResult {
+        error_collection,
+        prompt_controller_collection,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::asset_path_renderer::AssetPathRenderer;
+    use crate::filesystem::storage::DEFAULT_MAX_FILE_BYTES;
+    use crate::filesystem::storage::Storage;
+    use crate::mcp::prompt_metrics_noop::PromptMetricsNoop;
+    use crate::prompt_discovery::PromptDiscovery;
+    use crate::rhai_template_renderer_factory::RhaiTemplateRendererFactory;
+    use crate::token_counter_heuristic::TokenCounterHeuristic;
+
+    fn build_params(
+        base_directory: &Path,
+        prompt_discovery: PromptDiscovery,
+    ) -> Result<BuildPromptControllerCollectionParams> {
+        Ok(BuildPromptControllerCollectionParams {
+            allow_remote_images: false,
+            argument_meta: false,
+            asset_path_renderer: AssetPathRenderer {
+                base_path: "https://example.com/".to_string(),
+            },
+            content_document_linker: Default::default(),
+            esbuild_metafile: Default::default(),
+            fail_fast: true,
+            fetch_policy: Default::default(),
+            image_policy: Default::default(),
+            metrics: Arc::new(PromptMetricsNoop),
+            prompt_discovery,
+            provenance_meta: false,
+            random_seed: None,
+            render_limits_policy: Default::default(),
+            render_pipeline: Default::default(),
+            rhai_template_renderer: RhaiTemplateRendererFactory::new(
+                PathBuf::from(env!("CARGO_MANIFEST_DIR")),
+                PathBuf::from("shortcodes"),
+            )
+            .try_into()?,
+            token_counter: Arc::new(TokenCounterHeuristic),
+            uncategorized_policy: Default::default(),
+            unknown_fields_policy: Default::default(),
+            source_filesystem: Arc::new(Storage {
+                base_directory: base_directory.to_path_buf(),
+                max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+                source_encodings: Default::default(),
+            }),
+        })
     }
 
-    Ok(prompt_controller_map.into())
+    fn write_prompts(base_directory: &Path, names: &[&str]) -> Result<()> {
+        for name in names {
+            let path = base_directory.join(format!("prompts/{name}.md"));
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            fs::write(
+                path,
+                format!(
+                    "+++\ndescription = \"{name}\"\ntitle = \"{name}\"\n\n[arguments]\n+++\n\n**user**: {name}\n"
+                ),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_only_prompts_matching_name_filter_are_built() -> Result<()> {
+        let base_directory = tempdir()?;
+
+        write_prompts(
+            base_directory.path(),
+            &[
+                "guides/install",
+                "guides/upgrade",
+                "guides/advanced/tuning",
+                "review",
+            ],
+        )?;
+
+        let BuildPromptControllerCollectionResult {
+            prompt_controller_collection,
+            ..
+        } = build_prompt_document_controller_collection(build_params(
+            base_directory.path(),
+            PromptDiscovery::new(&["prompts/**/*.md".to_string()], &[])?
+                .with_name_filter(&["guides/*".to_string()])?,
+        )?)
+        .await?;
+
+        assert_eq!(
+            prompt_controller_collection
+                .prompt_controllers
+                .keys()
+                .collect::<Vec<&String>>(),
+            ["guides/install", "guides/upgrade"]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_continue_on_error_keeps_prompts_that_built() -> Result<()> {
+        let base_directory = tempdir()?;
+
+        write_prompts(base_directory.path(), &["good"])?;
+        fs::write(
+            base_directory.path().join("prompts/broken.md"),
+            "**user**: No front matter",
+        )?;
+
+        let BuildPromptControllerCollectionResult {
+            error_collection,
+            prompt_controller_collection,
+        } = build_prompt_document_controller_collection(BuildPromptControllerCollectionParams {
+            fail_fast: false,
+            ..build_params(
+                base_directory.path(),
+                PromptDiscovery::new(&["prompts/**/*.md".to_string()], &[])?,
+            )?
+        })
+        .await?;
+
+        assert_eq!(
+            prompt_controller_collection
+                .prompt_controllers
+                .keys()
+                .collect::<Vec<&String>>(),
+            ["good"]
+        );
+        assert_eq!(
+            error_collection.to_json().as_array().map(|errors| errors
+                .iter()
+                .map(|error| error["name"].clone())
+                .collect::<Vec<_>>()),
+            Some(vec!["broken".into()])
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_colliding_names_from_include_roots_are_an_error() -> Result<()> {
+        let base_directory = tempdir()?;
+
+        write_prompts(base_directory.path(), &["shared/greet", "team/greet"])?;
+
+        let BuildPromptControllerCollectionResult {
+            error_collection, ..
+        } = build_prompt_document_controller_collection(BuildPromptControllerCollectionParams {
+            fail_fast: false,
+            ..build_params(
+                base_directory.path(),
+                PromptDiscovery::new(
+                    &[
+                        "prompts/shared/**/*.md".to_string(),
+                        "prompts/team/**/*.md".to_string(),
+                    ],
+                    &[],
+                )?,
+            )?
+        })
+        .await?;
+
+        assert_eq!(
+            error_collection.to_json(),
+            serde_json::json!([{
+                "message": "Prompt name is derived from both 'prompts/shared/greet.md' and 'prompts/team/greet.md'",
+                "name": "greet",
+            }])
+        );
+
+        Ok(())
+    }
 }
@@ -1,17 +1,89 @@
+use std::collections::HashMap;
+#[cfg(test)]
+use std::path::PathBuf;
 use std::sync::Arc;
 
+#[cfg(test)]
+use anyhow::Result;
 use esbuild_metafile::EsbuildMetaFile;
 use rhai_components::rhai_template_renderer::RhaiTemplateRenderer;
 
 use crate::asset_path_renderer::AssetPathRenderer;
 use crate::content_document_linker::ContentDocumentLinker;
 use crate::filesystem::file_entry::FileEntry;
+#[cfg(test)]
+use crate::filesystem::file_entry_stub::FileEntryStub;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+use crate::prompt_collection_config::PromptCollectionConfig;
+use crate::prompt_fetch_policy::PromptFetchPolicy;
+use crate::prompt_fetcher::PromptFetcher;
+#[cfg(test)]
+use crate::prompt_fetcher_http::PromptFetcherHttp;
+use crate::prompt_image_policy::PromptImagePolicy;
+use crate::prompt_render_limits_policy::PromptRenderLimitsPolicy;
+use crate::prompt_render_pipeline::PromptRenderPipeline;
+use crate::prompt_uncategorized_policy::PromptUncategorizedPolicy;
+#[cfg(test)]
+use crate::rhai_template_renderer_factory::RhaiTemplateRendererFactory;
+use crate::token_counter::TokenCounter;
+#[cfg(test)]
+use crate::token_counter_heuristic::TokenCounterHeuristic;
 
 pub struct BuildPromptDocumentControllerParams {
+    pub allow_remote_images: bool,
     pub asset_path_renderer: AssetPathRenderer,
     pub content_document_linker: ContentDocumentLinker,
     pub esbuild_metafile: Arc<EsbuildMetaFile>,
+    pub fetch_policy: PromptFetchPolicy,
+    pub fetcher: Arc<dyn PromptFetcher>,
     pub file: FileEntry,
+    pub image_policy: PromptImagePolicy,
     pub name: String,
+    pub prompt_collection_configs: Arc<HashMap<String, PromptCollectionConfig>>,
+    pub provenance_meta: bool,
+    pub random_seed: Option<u64>,
+    pub render_limits_policy: PromptRenderLimitsPolicy,
+    pub render_pipeline: PromptRenderPipeline,
     pub rhai_template_renderer: RhaiTemplateRenderer,
+    pub token_counter: Arc<dyn TokenCounter>,
+    pub uncategorized_policy: PromptUncategorizedPolicy,
+    pub unknown_fields_policy: FrontMatterUnknownFieldsPolicy,
+}
+
+impl BuildPromptDocumentControllerParams {
+    #[cfg(test)]
+    pub fn mock(name: &str, contents: &str) -> Result<Self> {
+        let rhai_template_renderer: RhaiTemplateRenderer = RhaiTemplateRendererFactory::new(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")),
+            PathBuf::from("shortcodes"),
+        )
+        .try_into()?;
+
+        Ok(Self {
+            allow_remote_images: false,
+            asset_path_renderer: AssetPathRenderer {
+                base_path: "https://example.com/".to_string(),
+            },
+            content_document_linker: Default::default(),
+            esbuild_metafile: Default::default(),
+            fetch_policy: Default::default(),
+            fetcher: Arc::new(PromptFetcherHttp::new()?),
+            file: FileEntryStub {
+                contents: contents.to_string(),
+                relative_path: PathBuf::from(format!("prompts/{name}.md")),
+            }
+            .try_into()?,
+            image_policy: Default::default(),
+            name: name.to_string(),
+            prompt_collection_configs: Default::default(),
+            provenance_meta: false,
+            random_seed: None,
+            render_limits_policy: Default::default(),
+            render_pipeline: Default::default(),
+            rhai_template_renderer,
+            token_counter: Arc::new(TokenCounterHeuristic),
+            uncategorized_policy: Default::default(),
+            unknown_fields_policy: Default::default(),
+        })
+    }
 }
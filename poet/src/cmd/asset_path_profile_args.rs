@@ -0,0 +1,33 @@
+use anyhow::Result;
+use clap::Args;
+
+use crate::asset_path_profiles::AssetPathProfiles;
+use crate::asset_path_renderer::AssetPathRenderer;
+use crate::cmd::value_parser::parse_asset_path_profile;
+
+#[derive(Args, Clone)]
+pub struct AssetPathProfileArgs {
+    /// Base path of assets in an environment, as `name=base_path`
+    #[arg(long, value_parser = parse_asset_path_profile)]
+    asset_path_profile: Vec<(String, String)>,
+
+    /// Environment to render asset paths for, instead of the public path
+    #[arg(long)]
+    asset_profile: Option<String>,
+}
+
+impl AssetPathProfileArgs {
+    pub fn asset_path_renderer(&self, public_path: &str) -> Result<AssetPathRenderer> {
+        match &self.asset_profile {
+            Some(asset_profile) => self
+                .asset_path_profile
+                .iter()
+                .cloned()
+                .collect::<AssetPathProfiles>()
+                .asset_path_renderer(asset_profile),
+            None => Ok(AssetPathRenderer {
+                base_path: public_path.to_string(),
+            }),
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::cmd::source_storage_args::SourceStorageArgs;
 use crate::filesystem::storage::Storage;
 
 pub trait BuildsProject {
     fn source_directory(&self) -> PathBuf;
 
+    fn source_storage(&self) -> &SourceStorageArgs;
+
     fn assets_directory(&self) -> PathBuf {
         let mut static_files_directory: PathBuf = self.source_directory().clone();
 
@@ -17,6 +20,8 @@ pub trait BuildsProject {
     fn source_filesystem(&self) -> Arc<Storage> {
         Arc::new(Storage {
             base_directory: self.source_directory(),
+            max_file_bytes: self.source_storage().max_file_bytes,
+            source_encodings: self.source_storage().into(),
         })
     }
 }
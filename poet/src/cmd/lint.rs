@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Parser;
+use log::info;
+
+use crate::cmd::builds_project::BuildsProject;
+use crate::cmd::handler::Handler;
+use crate::cmd::source_storage_args::SourceStorageArgs;
+use crate::cmd::value_parser::validate_is_directory;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+use crate::lint_project::lint_project;
+
+#[derive(Parser)]
+pub struct Lint {
+    #[arg(value_parser = validate_is_directory)]
+    source_directory: PathBuf,
+
+    #[command(flatten)]
+    source_storage: SourceStorageArgs,
+
+    #[arg(long, value_enum, default_value_t)]
+    unknown_front_matter_fields: FrontMatterUnknownFieldsPolicy,
+}
+
+impl BuildsProject for Lint {
+    fn source_directory(&self) -> PathBuf {
+        self.source_directory.clone()
+    }
+
+    fn source_storage(&self) -> &SourceStorageArgs {
+        &self.source_storage
+    }
+}
+
+#[async_trait(?Send)]
+impl Handler for Lint {
+    async fn handle(&self) -> Result<()> {
+        let error_collection =
+            lint_project(self.source_filesystem(), self.unknown_front_matter_fields).await?;
+
+        if !error_collection.is_empty() {
+            return Err(error_collection.into());
+        }
+
+        info!("No problems found");
+
+        Ok(())
+    }
+}
@@ -14,6 +14,7 @@ use crate::app_dir_desktop_entry::AppDirDesktopEntry;
 use crate::assert_valid_desktop_entry_string::assert_valid_desktop_entry_string;
 use crate::cmd::builds_project::BuildsProject;
 use crate::cmd::handler::Handler;
+use crate::cmd::source_storage_args::SourceStorageArgs;
 use crate::cmd::value_parser::validate_is_directory;
 use crate::cmd::value_parser::validate_is_directory_or_create;
 use crate::copy_esbuild_metafile_assets_to::copy_esbuild_metafile_assets_to;
@@ -36,6 +37,9 @@ pub struct AppDir {
     #[arg(value_parser = validate_is_directory)]
     source_directory: PathBuf,
 
+    #[command(flatten)]
+    source_storage: SourceStorageArgs,
+
     #[arg(long)]
     title: String,
 
@@ -107,6 +111,10 @@ impl BuildsProject for AppDir {
     fn source_directory(&self) -> PathBuf {
         self.source_directory.clone()
     }
+
+    fn source_storage(&self) -> &SourceStorageArgs {
+        &self.source_storage
+    }
 }
 
 #[async_trait(?Send)]
@@ -118,6 +126,8 @@ impl Handler for AppDir {
 
         let app_dir_filesystem = Arc::new(Storage {
             base_directory: app_dir_path.clone(),
+            max_file_bytes: self.source_storage.max_file_bytes,
+            source_encodings: Default::default(),
         });
 
         info!("Copying project files to AppDir...");
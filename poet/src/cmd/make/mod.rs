@@ -1,2 +1,3 @@
 pub mod app_dir;
+pub mod prompt_manifest_file;
 pub mod static_pages;
@@ -0,0 +1,127 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Parser;
+use log::info;
+use tokio::fs;
+
+use crate::asset_path_renderer::AssetPathRenderer;
+use crate::build_authors::build_authors;
+use crate::build_project::build_project;
+use crate::build_project::build_project_params::BuildProjectParams;
+use crate::build_project::build_project_result_stub::BuildProjectResultStub;
+use crate::build_prompt_document_controller_collection::build_prompt_document_controller_collection;
+use crate::build_prompt_document_controller_collection::build_prompt_document_controller_collection_params::BuildPromptControllerCollectionParams;
+use crate::build_prompt_document_controller_collection::build_prompt_document_controller_collection_result::BuildPromptControllerCollectionResult;
+use crate::cmd::builds_project::BuildsProject;
+use crate::cmd::handler::Handler;
+use crate::cmd::prompt_discovery_args::PromptDiscoveryArgs;
+use crate::cmd::source_storage_args::SourceStorageArgs;
+use crate::cmd::value_parser::validate_is_directory;
+use crate::compile_shortcodes::compile_shortcodes;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+use crate::mcp::prompt_metrics_noop::PromptMetricsNoop;
+use crate::prompt_discovery::PromptDiscovery;
+use crate::prompt_manifest::PromptManifest;
+use crate::read_esbuild_metafile_or_default::read_esbuild_metafile_or_default;
+use crate::token_counter_heuristic::TokenCounterHeuristic;
+
+/// Asset paths do not show up in the manifest
+const MANIFEST_ASSET_BASE_PATH: &str = "/";
+
+#[derive(Parser)]
+pub struct PromptManifestFile {
+    #[arg(long)]
+    output_file: PathBuf,
+
+    #[command(flatten)]
+    prompt_discovery: PromptDiscoveryArgs,
+
+    #[arg(value_parser = validate_is_directory)]
+    source_directory: PathBuf,
+
+    #[command(flatten)]
+    source_storage: SourceStorageArgs,
+
+    #[arg(long, value_enum, default_value_t)]
+    unknown_front_matter_fields: FrontMatterUnknownFieldsPolicy,
+}
+
+impl BuildsProject for PromptManifestFile {
+    fn source_directory(&self) -> PathBuf {
+        self.source_directory.clone()
+    }
+
+    fn source_storage(&self) -> &SourceStorageArgs {
+        &self.source_storage
+    }
+}
+
+#[async_trait(?Send)]
+impl Handler for PromptManifestFile {
+    async fn handle(&self) -> Result<()> {
+        let source_filesystem = self.source_filesystem();
+        let rhai_template_renderer = compile_shortcodes(source_filesystem.clone()).await?;
+        let authors = build_authors(source_filesystem.clone()).await?;
+        let asset_path_renderer = AssetPathRenderer {
+            base_path: MANIFEST_ASSET_BASE_PATH.to_string(),
+        };
+        let prompt_discovery: PromptDiscovery = (&self.prompt_discovery).try_into()?;
+
+        let BuildProjectResultStub {
+            content_document_linker,
+            esbuild_metafile,
+            ..
+        } = build_project(BuildProjectParams {
+            asset_path_renderer: asset_path_renderer.clone(),
+            authors,
+            esbuild_metafile: read_esbuild_metafile_or_default(source_filesystem.clone()).await?,
+            generated_page_base_path: MANIFEST_ASSET_BASE_PATH.to_string(),
+            generate_sitemap: false,
+            is_watching: false,
+            prompt_discovery: prompt_discovery.clone(),
+            rhai_template_renderer: rhai_template_renderer.clone(),
+            source_filesystem: source_filesystem.clone(),
+            unknown_fields_policy: self.unknown_front_matter_fields,
+        })
+        .await?;
+
+        let BuildPromptControllerCollectionResult {
+            prompt_controller_collection,
+            ..
+        } = build_prompt_document_controller_collection(BuildPromptControllerCollectionParams {
+            allow_remote_images: false,
+            argument_meta: false,
+            asset_path_renderer,
+            content_document_linker,
+            esbuild_metafile,
+            fail_fast: true,
+            fetch_policy: Default::default(),
+            image_policy: Default::default(),
+            metrics: Arc::new(PromptMetricsNoop),
+            prompt_discovery,
+            provenance_meta: false,
+            random_seed: None,
+            render_limits_policy: Default::default(),
+            render_pipeline: Default::default(),
+            rhai_template_renderer,
+            source_filesystem,
+            token_counter: Arc::new(TokenCounterHeuristic),
+            uncategorized_policy: Default::default(),
+            unknown_fields_policy: self.unknown_front_matter_fields,
+        })
+        .await?;
+
+        fs::write(
+            &self.output_file,
+            serde_json::to_string_pretty(&PromptManifest::from(&prompt_controller_collection))?,
+        )
+        .await?;
+
+        info!("Prompt manifest written to {}", self.output_file.display());
+
+        Ok(())
+    }
+}
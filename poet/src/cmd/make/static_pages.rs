@@ -5,26 +5,35 @@ use async_trait::async_trait;
 use clap::Parser;
 use log::info;
 
-use crate::asset_path_renderer::AssetPathRenderer;
 use crate::build_authors::build_authors;
 use crate::build_project::build_project;
 use crate::build_project::build_project_params::BuildProjectParams;
 use crate::build_project::build_project_result_stub::BuildProjectResultStub;
+use crate::cmd::asset_path_profile_args::AssetPathProfileArgs;
 use crate::cmd::builds_project::BuildsProject;
 use crate::cmd::handler::Handler;
+use crate::cmd::prompt_discovery_args::PromptDiscoveryArgs;
+use crate::cmd::source_storage_args::SourceStorageArgs;
 use crate::cmd::value_parser::validate_is_directory;
 use crate::cmd::value_parser::validate_is_directory_or_create;
 use crate::compile_shortcodes::compile_shortcodes;
 use crate::copy_esbuild_metafile_assets_to::copy_esbuild_metafile_assets_to;
 use crate::filesystem::Filesystem;
 use crate::filesystem::storage::Storage;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
 use crate::read_esbuild_metafile_or_default::read_esbuild_metafile_or_default;
 
 #[derive(Parser)]
 pub struct StaticPages {
+    #[command(flatten)]
+    asset_path_profiles: AssetPathProfileArgs,
+
     #[arg(long, value_parser = validate_is_directory_or_create)]
     output_directory: PathBuf,
 
+    #[command(flatten)]
+    prompt_discovery: PromptDiscoveryArgs,
+
     #[arg(long)]
     public_path: String,
 
@@ -33,12 +42,22 @@ pub struct StaticPages {
 
     #[arg(value_parser = validate_is_directory)]
     source_directory: PathBuf,
+
+    #[command(flatten)]
+    source_storage: SourceStorageArgs,
+
+    #[arg(long, value_enum, default_value_t)]
+    unknown_front_matter_fields: FrontMatterUnknownFieldsPolicy,
 }
 
 impl BuildsProject for StaticPages {
     fn source_directory(&self) -> PathBuf {
         self.source_directory.clone()
     }
+
+    fn source_storage(&self) -> &SourceStorageArgs {
+        &self.source_storage
+    }
 }
 
 #[async_trait(?Send)]
@@ -53,21 +72,25 @@ impl Handler for StaticPages {
             memory_filesystem,
             ..
         } = build_project(BuildProjectParams {
-            asset_path_renderer: AssetPathRenderer {
-                base_path: self.public_path.clone(),
-            },
+            asset_path_renderer: self
+                .asset_path_profiles
+                .asset_path_renderer(&self.public_path)?,
             authors,
             esbuild_metafile: read_esbuild_metafile_or_default(source_filesystem.clone()).await?,
             generated_page_base_path: self.public_path.clone(),
             generate_sitemap: self.sitemap,
             is_watching: false,
+            prompt_discovery: (&self.prompt_discovery).try_into()?,
             rhai_template_renderer,
             source_filesystem,
+            unknown_fields_policy: self.unknown_front_matter_fields,
         })
         .await?;
 
         let storage = Storage {
             base_directory: self.output_directory.clone(),
+            max_file_bytes: self.source_storage.max_file_bytes,
+            source_encodings: Default::default(),
         };
 
         info!("Saving generated files in output directory...");
@@ -1,11 +1,18 @@
+mod asset_path_profile_args;
 mod builds_project;
 pub mod handler;
+pub mod lint;
 pub mod make;
+mod prompt_discovery_args;
+mod prompt_fetch_args;
+mod prompt_render_limits_args;
+mod prompt_render_pipeline_args;
 mod respond_with_generated_page;
 mod respond_with_generated_page_holder;
 pub mod serve;
 mod service;
 mod service_manager;
+mod source_storage_args;
 mod value_parser;
 pub mod watch;
 
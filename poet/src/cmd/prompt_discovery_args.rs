@@ -0,0 +1,30 @@
+use anyhow::Result;
+use clap::Args;
+
+use crate::prompt_discovery::PromptDiscovery;
+
+#[derive(Args, Clone)]
+pub struct PromptDiscoveryArgs {
+    /// Globs of files that are skipped even if they match an include glob
+    #[arg(long, value_delimiter = ',')]
+    prompt_exclude: Vec<String>,
+
+    /// Globs, relative to the source directory, of files built as prompts.
+    /// Prompt names are paths relative to the directory each glob starts
+    /// with, without the file name suffix the glob ends with.
+    #[arg(long, value_delimiter = ',', default_value = "prompts/**/*.md")]
+    prompt_include: Vec<String>,
+
+    /// Globs of prompt names to build, leaving out all the others
+    #[arg(long, value_delimiter = ',')]
+    prompt_name_filter: Vec<String>,
+}
+
+impl TryFrom<&PromptDiscoveryArgs> for PromptDiscovery {
+    type Error = anyhow::Error;
+
+    fn try_from(args: &PromptDiscoveryArgs) -> Result<Self> {
+        PromptDiscovery::new(&args.prompt_include, &args.prompt_exclude)?
+            .with_name_filter(&args.prompt_name_filter)
+    }
+}
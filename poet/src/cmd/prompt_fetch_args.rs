@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use clap::Args;
+use url::Url;
+
+use crate::prompt_fetch_policy::PromptFetchPolicy;
+
+#[derive(Args, Clone)]
+pub struct PromptFetchArgs {
+    /// URL prefixes that `<Fetch>` components in prompts can retrieve
+    #[arg(long, value_delimiter = ',')]
+    prompt_fetch_allowlist: Vec<Url>,
+
+    /// Maximum size of a response retrieved by `<Fetch>`
+    #[arg(long, default_value_t = PromptFetchPolicy::default().max_bytes)]
+    prompt_fetch_max_bytes: usize,
+
+    /// Maximum time a single `<Fetch>` can take
+    #[arg(long, default_value_t = 5000)]
+    prompt_fetch_timeout_ms: u64,
+}
+
+impl From<&PromptFetchArgs> for PromptFetchPolicy {
+    fn from(args: &PromptFetchArgs) -> Self {
+        PromptFetchPolicy {
+            allowlist: args.prompt_fetch_allowlist.clone(),
+            max_bytes: args.prompt_fetch_max_bytes,
+            timeout: Duration::from_millis(args.prompt_fetch_timeout_ms),
+        }
+    }
+}
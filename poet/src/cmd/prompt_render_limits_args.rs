@@ -0,0 +1,48 @@
+use clap::Args;
+
+use crate::prompt_render_limits::PromptRenderLimits;
+use crate::prompt_render_limits_policy::PromptRenderLimitsPolicy;
+
+#[derive(Args, Clone)]
+pub struct PromptRenderLimitsArgs {
+    /// Maximum size of a single rendered prompt message
+    #[arg(long)]
+    prompt_max_message_bytes: Option<usize>,
+
+    /// Highest value prompts can raise `max_message_bytes` to in their front matter
+    #[arg(long)]
+    prompt_max_message_bytes_ceiling: Option<usize>,
+
+    /// Maximum number of messages a single prompt can produce
+    #[arg(long)]
+    prompt_max_messages: Option<usize>,
+
+    /// Highest value prompts can raise `max_messages` to in their front matter
+    #[arg(long)]
+    prompt_max_messages_ceiling: Option<usize>,
+
+    /// Maximum time a prompt can take to render
+    #[arg(long)]
+    prompt_render_deadline_ms: Option<u64>,
+
+    /// Highest value prompts can raise `render_deadline_ms` to in their front matter
+    #[arg(long)]
+    prompt_render_deadline_ms_ceiling: Option<u64>,
+}
+
+impl From<&PromptRenderLimitsArgs> for PromptRenderLimitsPolicy {
+    fn from(args: &PromptRenderLimitsArgs) -> Self {
+        PromptRenderLimitsPolicy {
+            ceiling: PromptRenderLimits {
+                max_message_bytes: args.prompt_max_message_bytes_ceiling,
+                max_messages: args.prompt_max_messages_ceiling,
+                render_deadline_ms: args.prompt_render_deadline_ms_ceiling,
+            },
+            defaults: PromptRenderLimits {
+                max_message_bytes: args.prompt_max_message_bytes,
+                max_messages: args.prompt_max_messages,
+                render_deadline_ms: args.prompt_render_deadline_ms,
+            },
+        }
+    }
+}
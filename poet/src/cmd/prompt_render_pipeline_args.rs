@@ -0,0 +1,31 @@
+use clap::Args;
+
+use crate::prompt_render_pass::PromptRenderPass;
+use crate::prompt_render_pipeline::PromptRenderPipeline;
+use crate::prompt_system_role_lowering::PromptSystemRoleLowering;
+
+#[derive(Args, Clone)]
+pub struct PromptRenderPipelineArgs {
+    /// Passes run over the rendered messages, in the given order
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "normalize-smart-punctuation,trim-empty-messages"
+    )]
+    prompt_render_passes: Vec<PromptRenderPass>,
+
+    /// How system blocks are turned into user messages, since MCP has no
+    /// system role
+    #[arg(long, value_enum, default_value_t)]
+    prompt_system_role_lowering: PromptSystemRoleLowering,
+}
+
+impl From<&PromptRenderPipelineArgs> for PromptRenderPipeline {
+    fn from(args: &PromptRenderPipelineArgs) -> Self {
+        PromptRenderPipeline {
+            passes: args.prompt_render_passes.clone(),
+            system_role_lowering: args.prompt_system_role_lowering,
+        }
+    }
+}
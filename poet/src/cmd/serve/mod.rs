@@ -13,10 +13,14 @@ use anyhow::Result;
 use indoc::formatdoc;
 use async_trait::async_trait;
 use clap::Parser;
+use log::error;
 use log::info;
+use log::warn;
+use tokio::io::BufReader;
+use tokio::io::stdin;
+use tokio::io::stdout;
 
 use crate::app_dir_desktop_entry::AppDirDesktopEntry;
-use crate::asset_path_renderer::AssetPathRenderer;
 use crate::build_authors::build_authors;
 use crate::build_project::build_project;
 use crate::build_project::build_project_params::BuildProjectParams;
@@ -24,11 +28,19 @@ use crate::build_project::build_project_result::BuildProjectResult;
 use crate::build_project::build_project_result_holder::BuildProjectResultHolder;
 use crate::build_prompt_document_controller_collection::build_prompt_document_controller_collection;
 use crate::build_prompt_document_controller_collection::build_prompt_document_controller_collection_params::BuildPromptControllerCollectionParams;
+use crate::build_prompt_document_controller_collection::build_prompt_document_controller_collection_result::BuildPromptControllerCollectionResult;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
 use crate::holder::Holder as _;
 use crate::cmd::STATIC_FILES_PUBLIC_PATH;
+use crate::cmd::asset_path_profile_args::AssetPathProfileArgs;
 use crate::cmd::builds_project::BuildsProject;
 use crate::cmd::handler::Handler;
+use crate::cmd::prompt_discovery_args::PromptDiscoveryArgs;
+use crate::cmd::prompt_fetch_args::PromptFetchArgs;
+use crate::cmd::prompt_render_limits_args::PromptRenderLimitsArgs;
+use crate::cmd::prompt_render_pipeline_args::PromptRenderPipelineArgs;
 use crate::cmd::serve::app_data::AppData;
+use crate::cmd::source_storage_args::SourceStorageArgs;
 use crate::cmd::value_parser::parse_socket_addr;
 use crate::cmd::value_parser::validate_is_directory;
 use crate::compile_shortcodes::compile_shortcodes;
@@ -36,17 +48,23 @@ use crate::filesystem::Filesystem;
 use crate::filesystem_http_route_index::FilesystemHttpRouteIndex;
 use crate::mcp::jsonrpc::implementation::Implementation;
 use crate::mcp::mcp_http_service_factory::McpHttpServiceFactory;
+use crate::mcp::mcp_stdio_transport::McpStdioTransport;
+use crate::mcp::prompt_metrics_noop::PromptMetricsNoop;
 use crate::mcp::resource_list_aggregate::ResourceListAggregate;
 use crate::mcp::resource_provider::ResourceProvider;
 use crate::mcp::session_manager::SessionManager;
 use crate::mcp::tool_registry::ToolRegistry;
 use crate::mcp_resource_provider_content_documents::McpResourceProviderContentDocuments;
 use crate::prompt_controller_collection_holder::PromptControllerCollectionHolder;
+use crate::prompt_discovery::PromptDiscovery;
+use crate::prompt_image_policy::PromptImagePolicy;
+use crate::prompt_uncategorized_policy::PromptUncategorizedPolicy;
 use crate::read_esbuild_metafile_or_default::read_esbuild_metafile_or_default;
 use crate::search_index::SearchIndex;
 use crate::search_index_reader::SearchIndexReader;
 use crate::search_index_reader_holder::SearchIndexReaderHolder;
 use crate::search_tool::SearchTool;
+use crate::token_counter_heuristic::TokenCounterHeuristic;
 
 #[derive(Parser)]
 pub struct Serve {
@@ -59,25 +77,80 @@ pub struct Serve {
     #[arg(long)]
     app_name: String,
 
+    #[command(flatten)]
+    asset_path_profiles: AssetPathProfileArgs,
+
     #[arg(long)]
     public_path: String,
 
+    #[arg(long, default_value = "false")]
+    prompt_argument_meta: bool,
+
+    #[arg(long, default_value = "false")]
+    prompt_continue_on_error: bool,
+
+    #[arg(long, default_value = "false")]
+    prompt_detect_duplicates: bool,
+
+    #[command(flatten)]
+    prompt_discovery: PromptDiscoveryArgs,
+
+    #[command(flatten)]
+    prompt_fetch: PromptFetchArgs,
+
+    #[arg(long, value_enum, default_value_t)]
+    prompt_image_policy: PromptImagePolicy,
+
+    #[arg(long, default_value = "false")]
+    prompt_provenance_meta: bool,
+
+    #[arg(long)]
+    prompt_random_seed: Option<u64>,
+
+    #[arg(long, default_value = "false")]
+    prompt_remote_images: bool,
+
+    #[command(flatten)]
+    prompt_render_limits: PromptRenderLimitsArgs,
+
+    #[command(flatten)]
+    prompt_render_pipeline: PromptRenderPipelineArgs,
+
+    #[arg(long, default_value = "false")]
+    prompt_warm_cache: bool,
+
     #[arg(long, default_value = "false")]
     sitemap: bool,
+
+    #[command(flatten)]
+    source_storage: SourceStorageArgs,
+
+    #[arg(long, default_value = "false")]
+    stdio: bool,
+
+    #[arg(long, value_enum, default_value_t)]
+    uncategorized_prompts: PromptUncategorizedPolicy,
+
+    #[arg(long, value_enum, default_value_t)]
+    unknown_front_matter_fields: FrontMatterUnknownFieldsPolicy,
 }
 
 impl BuildsProject for Serve {
     fn source_directory(&self) -> PathBuf {
         self.app_dir.clone()
     }
+
+    fn source_storage(&self) -> &SourceStorageArgs {
+        &self.source_storage
+    }
 }
 
 #[async_trait(?Send)]
 impl Handler for Serve {
     async fn handle(&self) -> Result<()> {
-        let asset_path_renderer = AssetPathRenderer {
-            base_path: self.public_path.clone(),
-        };
+        let asset_path_renderer = self
+            .asset_path_profiles
+            .asset_path_renderer(&self.public_path)?;
         let source_filesystem = self.source_filesystem();
         let rhai_template_renderer = compile_shortcodes(source_filesystem.clone()).await?;
         let app_dir_desktop_entry = AppDirDesktopEntry::parse(
@@ -114,6 +187,7 @@ impl Handler for Serve {
         };
 
         let authors = build_authors(source_filesystem.clone()).await?;
+        let prompt_discovery: PromptDiscovery = (&self.prompt_discovery).try_into()?;
 
         let build_project_result: BuildProjectResult = build_project(BuildProjectParams {
             asset_path_renderer: asset_path_renderer.clone(),
@@ -122,21 +196,57 @@ impl Handler for Serve {
             generated_page_base_path: self.public_path.clone(),
             generate_sitemap: self.sitemap,
             is_watching: false,
+            prompt_discovery: prompt_discovery.clone(),
             rhai_template_renderer: rhai_template_renderer.clone(),
             source_filesystem: source_filesystem.clone(),
+            unknown_fields_policy: self.unknown_front_matter_fields,
         })
         .await?
         .into();
 
-        let prompt_controller_collection =
-            build_prompt_document_controller_collection(BuildPromptControllerCollectionParams {
-                asset_path_renderer: asset_path_renderer.clone(),
-                content_document_linker: build_project_result.content_document_linker.clone(),
-                esbuild_metafile: build_project_result.esbuild_metafile.clone(),
-                rhai_template_renderer,
-                source_filesystem: source_filesystem.clone(),
-            })
-            .await?;
+        let BuildPromptControllerCollectionResult {
+            error_collection,
+            mut prompt_controller_collection,
+        } = build_prompt_document_controller_collection(BuildPromptControllerCollectionParams {
+            allow_remote_images: self.prompt_remote_images,
+            argument_meta: self.prompt_argument_meta,
+            asset_path_renderer: asset_path_renderer.clone(),
+            content_document_linker: build_project_result.content_document_linker.clone(),
+            esbuild_metafile: build_project_result.esbuild_metafile.clone(),
+            fail_fast: !self.prompt_continue_on_error,
+            fetch_policy: (&self.prompt_fetch).into(),
+            image_policy: self.prompt_image_policy,
+            metrics: Arc::new(PromptMetricsNoop),
+            prompt_discovery,
+            provenance_meta: self.prompt_provenance_meta,
+            random_seed: self.prompt_random_seed,
+            render_limits_policy: (&self.prompt_render_limits).into(),
+            render_pipeline: (&self.prompt_render_pipeline).into(),
+            rhai_template_renderer,
+            source_filesystem: source_filesystem.clone(),
+            token_counter: Arc::new(TokenCounterHeuristic),
+            uncategorized_policy: self.uncategorized_prompts,
+            unknown_fields_policy: self.unknown_front_matter_fields,
+        })
+        .await?;
+
+        if !error_collection.is_empty() {
+            error!("Some prompts failed to build, serving the rest: {error_collection}");
+        }
+
+        if self.prompt_detect_duplicates || self.prompt_warm_cache {
+            let static_prompt_renders = prompt_controller_collection.render_static_prompts().await;
+
+            if self.prompt_detect_duplicates {
+                for (name, duplicate_name) in static_prompt_renders.find_duplicates() {
+                    warn!("Prompt '{duplicate_name}' renders the same messages as '{name}'");
+                }
+            }
+
+            if self.prompt_warm_cache {
+                prompt_controller_collection.warm(&static_prompt_renders);
+            }
+        }
 
         let prompt_controller_collection_holder: PromptControllerCollectionHolder =
             Default::default();
@@ -187,6 +297,18 @@ impl Handler for Serve {
 
         let tool_registry_arc: Arc<ToolRegistry> = Arc::new(tool_registry);
 
+        if self.stdio {
+            return McpStdioTransport {
+                prompt_controller_collection_holder,
+                resource_list_aggregate,
+                server_info,
+                session_manager,
+                tool_registry: tool_registry_arc,
+            }
+            .serve(BufReader::new(stdin()), stdout())
+            .await;
+        }
+
         HttpServer::new(move || {
             App::new()
                 .app_data(app_data.clone())
@@ -198,6 +320,7 @@ impl Handler for Serve {
                     mount_path: "/mcp/streamable".to_string(),
                     prompt_controller_collection_holder: prompt_controller_collection_holder
                         .clone(),
+                    prompts_list_changed: false,
                     resource_list_aggregate: resource_list_aggregate.clone(),
                     server_info: server_info.clone(),
                     session_manager: session_manager.clone(),
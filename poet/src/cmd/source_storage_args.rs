@@ -0,0 +1,32 @@
+use clap::Args;
+use encoding_rs::Encoding;
+
+use crate::cmd::value_parser::parse_encoding;
+use crate::cmd::value_parser::parse_extension_encoding;
+use crate::filesystem::storage::DEFAULT_MAX_FILE_BYTES;
+use crate::filesystem::storage::source_encodings::SourceEncodings;
+
+#[derive(Args, Clone)]
+pub struct SourceStorageArgs {
+    /// Project files larger than this are skipped with a warning
+    #[arg(long, default_value_t = DEFAULT_MAX_FILE_BYTES)]
+    pub max_file_bytes: u64,
+
+    /// Encoding of project files without a more specific one
+    #[arg(long, default_value = "utf-8", value_parser = parse_encoding)]
+    source_encoding: &'static Encoding,
+
+    /// Encoding of project files with the given extension, as
+    /// `extension=encoding`
+    #[arg(long, value_parser = parse_extension_encoding)]
+    source_extension_encoding: Vec<(String, &'static Encoding)>,
+}
+
+impl From<&SourceStorageArgs> for SourceEncodings {
+    fn from(args: &SourceStorageArgs) -> Self {
+        SourceEncodings {
+            by_extension: args.source_extension_encoding.iter().cloned().collect(),
+            default: args.source_encoding,
+        }
+    }
+}
@@ -1,7 +1,13 @@
+mod parse_asset_path_profile;
+mod parse_encoding;
+mod parse_extension_encoding;
 mod parse_socket_addr;
 mod validate_is_directory;
 mod validate_is_directory_or_create;
 
+pub use self::parse_asset_path_profile::parse_asset_path_profile;
+pub use self::parse_encoding::parse_encoding;
+pub use self::parse_extension_encoding::parse_extension_encoding;
 pub use self::parse_socket_addr::parse_socket_addr;
 pub use self::validate_is_directory::validate_is_directory;
 pub use self::validate_is_directory_or_create::validate_is_directory_or_create;
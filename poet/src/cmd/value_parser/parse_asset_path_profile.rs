@@ -0,0 +1,14 @@
+use anyhow::Result;
+use anyhow::anyhow;
+
+/// Parses a `name=base_path` pair
+pub fn parse_asset_path_profile(arg: &str) -> Result<(String, String)> {
+    match arg.split_once('=') {
+        Some((name, base_path)) if !name.is_empty() && !base_path.is_empty() => {
+            Ok((name.to_string(), base_path.to_string()))
+        }
+        _ => Err(anyhow!(
+            "Asset path profile has to be in the 'name=base_path' format, got: '{arg}'"
+        )),
+    }
+}
@@ -0,0 +1,7 @@
+use anyhow::Result;
+use anyhow::anyhow;
+use encoding_rs::Encoding;
+
+pub fn parse_encoding(label: &str) -> Result<&'static Encoding> {
+    Encoding::for_label(label.as_bytes()).ok_or_else(|| anyhow!("Unknown encoding: '{label}'"))
+}
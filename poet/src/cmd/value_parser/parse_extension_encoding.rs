@@ -0,0 +1,17 @@
+use anyhow::Result;
+use anyhow::anyhow;
+use encoding_rs::Encoding;
+
+use crate::cmd::value_parser::parse_encoding;
+
+/// Parses an `extension=encoding` pair
+pub fn parse_extension_encoding(arg: &str) -> Result<(String, &'static Encoding)> {
+    match arg.split_once('=') {
+        Some((extension, label)) if !extension.is_empty() => {
+            Ok((extension.to_string(), parse_encoding(label)?))
+        }
+        _ => Err(anyhow!(
+            "Extension encoding has to be in the 'extension=encoding' format, got: '{arg}'"
+        )),
+    }
+}
@@ -0,0 +1,41 @@
+use std::collections::BTreeSet;
+use std::mem::take;
+use std::path::PathBuf;
+
+/// Prompt files changed since the prompts were last built. Edits to files
+/// that already exist can be reloaded one by one, anything else (new,
+/// removed or renamed files, collection changes) needs a full rebuild.
+#[derive(Default)]
+pub struct ChangedPromptFiles {
+    edited: BTreeSet<PathBuf>,
+    needs_full_rebuild: bool,
+}
+
+impl ChangedPromptFiles {
+    pub fn clear(&mut self) {
+        self.edited.clear();
+        self.needs_full_rebuild = false;
+    }
+
+    pub fn mark_edited(&mut self, relative_path: PathBuf) {
+        self.edited.insert(relative_path);
+    }
+
+    pub fn mark_needs_full_rebuild(&mut self) {
+        self.needs_full_rebuild = true;
+    }
+
+    /// Edited files, or `None` when they cannot be reloaded one by one
+    pub fn take_edited(&mut self) -> Option<BTreeSet<PathBuf>> {
+        let needs_full_rebuild = self.needs_full_rebuild;
+        let edited = take(&mut self.edited);
+
+        self.needs_full_rebuild = false;
+
+        if needs_full_rebuild {
+            return None;
+        }
+
+        Some(edited)
+    }
+}
@@ -1,4 +1,5 @@
 mod app_data;
+mod changed_prompt_files;
 mod http_route;
 mod service;
 mod watch_project_files;
@@ -6,20 +7,27 @@ mod watch_project_files;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use async_trait::async_trait;
 use clap::Parser;
 use log::info;
+use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 
 use self::watch_project_files::WatchProjectHandle;
 use self::watch_project_files::watch_project_files;
-use crate::asset_path_renderer::AssetPathRenderer;
 use crate::build_project::build_project_result_holder::BuildProjectResultHolder;
+use crate::cmd::asset_path_profile_args::AssetPathProfileArgs;
 use crate::cmd::builds_project::BuildsProject;
 use crate::cmd::handler::Handler;
+use crate::cmd::prompt_discovery_args::PromptDiscoveryArgs;
+use crate::cmd::prompt_fetch_args::PromptFetchArgs;
+use crate::cmd::prompt_render_limits_args::PromptRenderLimitsArgs;
+use crate::cmd::prompt_render_pipeline_args::PromptRenderPipelineArgs;
 use crate::cmd::service_manager::ServiceManager;
+use crate::cmd::source_storage_args::SourceStorageArgs;
 use crate::cmd::value_parser::parse_socket_addr;
 use crate::cmd::value_parser::validate_is_directory;
 use crate::cmd::watch::service::esbuild_metafile_reader::EsbuildMetaFileReader;
@@ -27,35 +35,91 @@ use crate::cmd::watch::service::filesystem_http_route_index_builder::FilesystemH
 use crate::cmd::watch::service::http_server::HttpServer;
 use crate::cmd::watch::service::project_builder::ProjectBuilder;
 use crate::cmd::watch::service::prompt_controller_collection_builder::PromptControllerCollectionBuilder;
+use crate::cmd::watch::service::prompts_list_changed_notifier::PromptsListChangedNotifier;
 use crate::cmd::watch::service::search_index_builder::SearchIndexBuilder;
 use crate::cmd::watch::service::shortcodes_compiler::ShortcodesCompiler;
 use crate::esbuild_metafile_holder::EsbuildMetaFileHolder;
 use crate::filesystem_http_route_index_holder::FilesystemHttpRouteIndexHolder;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+use crate::mcp::prompt_metrics_noop::PromptMetricsNoop;
 use crate::mcp::resource_provider::ResourceProvider;
 use crate::mcp::session_manager::SessionManager;
 use crate::mcp::tool_registry::ToolRegistry;
 use crate::mcp_resource_provider_content_documents::McpResourceProviderContentDocuments;
 use crate::prompt_controller_collection_holder::PromptControllerCollectionHolder;
+use crate::prompt_discovery::PromptDiscovery;
+use crate::prompt_image_policy::PromptImagePolicy;
+use crate::prompt_uncategorized_policy::PromptUncategorizedPolicy;
 use crate::rhai_template_renderer_holder::RhaiTemplateRendererHolder;
 use crate::search_index_reader_holder::SearchIndexReaderHolder;
 use crate::search_tool::SearchTool;
+use crate::token_counter_heuristic::TokenCounterHeuristic;
 
 #[derive(Parser)]
 pub struct Watch {
     #[arg(long, default_value="127.0.0.1:8050", value_parser = parse_socket_addr)]
     addr: SocketAddr,
 
+    #[command(flatten)]
+    asset_path_profiles: AssetPathProfileArgs,
+
     #[arg(value_parser = validate_is_directory)]
     source_directory: PathBuf,
 
+    #[command(flatten)]
+    source_storage: SourceStorageArgs,
+
+    #[arg(long, default_value = "false")]
+    prompt_argument_meta: bool,
+
+    #[arg(long, default_value = "false")]
+    prompt_continue_on_error: bool,
+
+    #[arg(long, default_value = "false")]
+    prompt_detect_duplicates: bool,
+
+    #[command(flatten)]
+    prompt_discovery: PromptDiscoveryArgs,
+
+    #[command(flatten)]
+    prompt_fetch: PromptFetchArgs,
+
+    #[arg(long, value_enum, default_value_t)]
+    prompt_image_policy: PromptImagePolicy,
+
+    #[arg(long, default_value = "false")]
+    prompt_provenance_meta: bool,
+
+    #[arg(long)]
+    prompt_random_seed: Option<u64>,
+
+    #[arg(long, default_value = "false")]
+    prompt_remote_images: bool,
+
+    #[command(flatten)]
+    prompt_render_limits: PromptRenderLimitsArgs,
+
+    #[command(flatten)]
+    prompt_render_pipeline: PromptRenderPipelineArgs,
+
     #[arg(long, default_value = "false")]
     sitemap: bool,
+
+    #[arg(long, value_enum, default_value_t)]
+    uncategorized_prompts: PromptUncategorizedPolicy,
+
+    #[arg(long, value_enum, default_value_t)]
+    unknown_front_matter_fields: FrontMatterUnknownFieldsPolicy,
 }
 
 impl BuildsProject for Watch {
     fn source_directory(&self) -> PathBuf {
         self.source_directory.clone()
     }
+
+    fn source_storage(&self) -> &SourceStorageArgs {
+        &self.source_storage
+    }
 }
 
 #[async_trait(?Send)]
@@ -68,25 +132,32 @@ impl Handler for Watch {
             ctrlc_notifier_handler.cancel();
         })?;
 
+        let prompt_discovery: PromptDiscovery = (&self.prompt_discovery).try_into()?;
+
         let WatchProjectHandle {
+            changed_prompt_files,
             debouncer: _debouncer,
             on_author_file_changed,
             on_content_file_changed,
             on_esbuild_metafile_changed,
             on_prompt_file_changed,
             on_shortcode_file_changed,
-        } = watch_project_files(self.source_directory.clone())?;
+        } = watch_project_files(
+            self.source_directory.clone(),
+            prompt_discovery.directories(),
+        )?;
 
         let generated_page_base_path = format!("http://{}/", self.addr);
 
-        let asset_path_renderer = AssetPathRenderer {
-            base_path: generated_page_base_path.clone(),
-        };
+        let asset_path_renderer = self
+            .asset_path_profiles
+            .asset_path_renderer(&generated_page_base_path)?;
         let build_project_result_holder: BuildProjectResultHolder = Default::default();
         let esbuild_metafile_holder: EsbuildMetaFileHolder = Default::default();
         let filesystem_http_route_index_holder: FilesystemHttpRouteIndexHolder = Default::default();
         let mcp_resource_provider_content_documents: McpResourceProviderContentDocuments =
             McpResourceProviderContentDocuments(build_project_result_holder.clone());
+        let on_prompts_rebuilt: Arc<Notify> = Default::default();
         let prompt_controller_collection_holder: PromptControllerCollectionHolder =
             Default::default();
         let rhai_template_renderer_holder: RhaiTemplateRendererHolder = Default::default();
@@ -137,21 +208,49 @@ impl Handler for Watch {
             generated_page_base_path: generated_page_base_path.clone(),
             on_author_file_changed,
             on_content_file_changed,
+            prompt_discovery: prompt_discovery.clone(),
             rhai_template_renderer_holder: rhai_template_renderer_holder.clone(),
-            session_manager,
+            session_manager: session_manager.clone(),
             generate_sitemap: self.sitemap,
             source_filesystem: source_filesystem.clone(),
+            unknown_fields_policy: self.unknown_front_matter_fields,
         }));
 
         service_manager.register_service(Arc::new(PromptControllerCollectionBuilder {
+            allow_remote_images: self.prompt_remote_images,
+            argument_meta: self.prompt_argument_meta,
             asset_path_renderer,
             build_project_result_holder: build_project_result_holder.clone(),
+            changed_prompt_files,
             ctrlc_notifier: ctrlc_notifier.clone(),
             esbuild_metafile_holder,
+            detect_duplicates: self.prompt_detect_duplicates,
+            fail_fast: !self.prompt_continue_on_error,
+            fetch_policy: (&self.prompt_fetch).into(),
             on_prompt_file_changed,
-            prompt_controller_collection_holder,
+            on_prompts_rebuilt: on_prompts_rebuilt.clone(),
+            image_policy: self.prompt_image_policy,
+            last_build_project_result_holder: Default::default(),
+            metrics: Arc::new(PromptMetricsNoop),
+            prompt_controller_collection_holder: prompt_controller_collection_holder.clone(),
+            prompt_discovery,
+            provenance_meta: self.prompt_provenance_meta,
+            random_seed: self.prompt_random_seed,
+            render_limits_policy: (&self.prompt_render_limits).into(),
+            render_pipeline: (&self.prompt_render_pipeline).into(),
             rhai_template_renderer_holder: rhai_template_renderer_holder.clone(),
             source_filesystem: source_filesystem.clone(),
+            token_counter: Arc::new(TokenCounterHeuristic),
+            uncategorized_policy: self.uncategorized_prompts,
+            unknown_fields_policy: self.unknown_front_matter_fields,
+        }));
+
+        service_manager.register_service(Arc::new(PromptsListChangedNotifier {
+            ctrlc_notifier: ctrlc_notifier.clone(),
+            debounce: Duration::from_millis(200),
+            on_prompts_rebuilt,
+            prompt_controller_collection_holder,
+            session_manager,
         }));
 
         service_manager.register_service(Arc::new(SearchIndexBuilder {
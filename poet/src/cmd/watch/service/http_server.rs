@@ -81,6 +81,7 @@ impl Service for HttpServer {
                         mount_path: "/mcp/streamable".to_string(),
                         prompt_controller_collection_holder: prompt_controller_collection_holder
                             .clone(),
+                        prompts_list_changed: true,
                         resource_list_aggregate: resource_list_aggregate.clone(),
                         server_info: server_info.clone(),
                         session_manager: session_manager.clone(),
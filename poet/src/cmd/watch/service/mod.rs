@@ -3,5 +3,6 @@ pub mod filesystem_http_route_index_builder;
 pub mod http_server;
 pub mod project_builder;
 pub mod prompt_controller_collection_builder;
+pub mod prompts_list_changed_notifier;
 pub mod search_index_builder;
 pub mod shortcodes_compiler;
@@ -16,11 +16,13 @@ use crate::build_project::build_project_result_holder::BuildProjectResultHolder;
 use crate::cmd::service::Service;
 use crate::esbuild_metafile_holder::EsbuildMetaFileHolder;
 use crate::filesystem::storage::Storage;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
 use crate::holder::Holder as _;
 use crate::mcp::jsonrpc::JSONRPC_VERSION;
 use crate::mcp::jsonrpc::notification::resources_list_changed::ResourcesListChanged;
 use crate::mcp::jsonrpc::server_to_client_notification::ServerToClientNotification;
 use crate::mcp::session_manager::SessionManager;
+use crate::prompt_discovery::PromptDiscovery;
 use crate::rhai_template_renderer_holder::RhaiTemplateRendererHolder;
 
 pub struct ProjectBuilder {
@@ -31,10 +33,12 @@ pub struct ProjectBuilder {
     pub generated_page_base_path: String,
     pub on_author_file_changed: Arc<Notify>,
     pub on_content_file_changed: Arc<Notify>,
+    pub prompt_discovery: PromptDiscovery,
     pub rhai_template_renderer_holder: RhaiTemplateRendererHolder,
     pub session_manager: SessionManager,
     pub generate_sitemap: bool,
     pub source_filesystem: Arc<Storage>,
+    pub unknown_fields_policy: FrontMatterUnknownFieldsPolicy,
 }
 
 impl ProjectBuilder {
@@ -72,8 +76,10 @@ impl ProjectBuilder {
             generated_page_base_path: self.generated_page_base_path.clone(),
             generate_sitemap: self.generate_sitemap,
             is_watching: true,
+            prompt_discovery: self.prompt_discovery.clone(),
             rhai_template_renderer,
             source_filesystem: self.source_filesystem.clone(),
+            unknown_fields_policy: self.unknown_fields_policy,
         })
         .await
         {
@@ -1,42 +1,75 @@
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use anyhow::Result;
 use async_trait::async_trait;
 use log::debug;
 use log::error;
+use log::info;
+use log::warn;
 use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 
 use crate::asset_path_renderer::AssetPathRenderer;
-use crate::build_project::build_project_result::BuildProjectResult;
 use crate::build_project::build_project_result_holder::BuildProjectResultHolder;
 use crate::build_prompt_document_controller_collection::build_prompt_document_controller_collection;
 use crate::build_prompt_document_controller_collection::build_prompt_document_controller_collection_params::BuildPromptControllerCollectionParams;
+use crate::build_prompt_document_controller_collection::build_prompt_document_controller_collection_result::BuildPromptControllerCollectionResult;
 use crate::cmd::service::Service;
+use crate::cmd::watch::changed_prompt_files::ChangedPromptFiles;
 use crate::esbuild_metafile_holder::EsbuildMetaFileHolder;
+use crate::filesystem::Filesystem as _;
+use crate::filesystem::file_entry::FileEntry;
+use crate::filesystem::file_entry_stub::FileEntryStub;
 use crate::filesystem::storage::Storage;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
 use crate::holder::Holder as _;
+use crate::mcp::prompt_metrics::PromptMetrics;
 use crate::prompt_controller_collection_holder::PromptControllerCollectionHolder;
+use crate::prompt_discovery::PromptDiscovery;
+use crate::prompt_fetch_policy::PromptFetchPolicy;
+use crate::prompt_image_policy::PromptImagePolicy;
+use crate::prompt_render_limits_policy::PromptRenderLimitsPolicy;
+use crate::prompt_render_pipeline::PromptRenderPipeline;
+use crate::prompt_uncategorized_policy::PromptUncategorizedPolicy;
 use crate::rhai_template_renderer_holder::RhaiTemplateRendererHolder;
+use crate::token_counter::TokenCounter;
 
 pub struct PromptControllerCollectionBuilder {
+    pub allow_remote_images: bool,
+    pub argument_meta: bool,
     pub asset_path_renderer: AssetPathRenderer,
     pub build_project_result_holder: BuildProjectResultHolder,
+    /// Edits to existing prompts are reloaded without rebuilding the rest
+    pub changed_prompt_files: Arc<Mutex<ChangedPromptFiles>>,
     pub ctrlc_notifier: CancellationToken,
+    pub detect_duplicates: bool,
     pub esbuild_metafile_holder: EsbuildMetaFileHolder,
+    pub fail_fast: bool,
+    pub fetch_policy: PromptFetchPolicy,
+    pub image_policy: PromptImagePolicy,
+    /// Project build that the current prompts were built against
+    pub last_build_project_result_holder: BuildProjectResultHolder,
+    pub metrics: Arc<dyn PromptMetrics>,
     pub on_prompt_file_changed: Arc<Notify>,
+    pub on_prompts_rebuilt: Arc<Notify>,
     pub prompt_controller_collection_holder: PromptControllerCollectionHolder,
+    pub prompt_discovery: PromptDiscovery,
+    pub provenance_meta: bool,
+    pub random_seed: Option<u64>,
+    pub render_limits_policy: PromptRenderLimitsPolicy,
+    pub render_pipeline: PromptRenderPipeline,
     pub rhai_template_renderer_holder: RhaiTemplateRendererHolder,
     pub source_filesystem: Arc<Storage>,
+    pub token_counter: Arc<dyn TokenCounter>,
+    pub uncategorized_policy: PromptUncategorizedPolicy,
+    pub unknown_fields_policy: FrontMatterUnknownFieldsPolicy,
 }
 
 impl PromptControllerCollectionBuilder {
     async fn do_build_prompt_controllers(&self) {
-        let content_document_linker = match self.build_project_result_holder.get().await {
-            Some(BuildProjectResult {
-                content_document_linker,
-                ..
-            }) => content_document_linker,
+        let build_project_result = match self.build_project_result_holder.get().await {
+            Some(build_project_result) => build_project_result,
             None => {
                 debug!(
                     "Build project result is not ready yet to be used with prompt controllers builder"
@@ -68,21 +101,170 @@ impl PromptControllerCollectionBuilder {
             }
         };
 
+        // The full build reads every prompt file again
+        self.changed_prompt_files
+            .lock()
+            .expect("Changed prompt files lock is poisoned")
+            .clear();
+
         match build_prompt_document_controller_collection(BuildPromptControllerCollectionParams {
+            allow_remote_images: self.allow_remote_images,
+            argument_meta: self.argument_meta,
             asset_path_renderer: self.asset_path_renderer.clone(),
-            content_document_linker,
+            content_document_linker: build_project_result.content_document_linker.clone(),
             esbuild_metafile,
+            fail_fast: self.fail_fast,
+            fetch_policy: self.fetch_policy.clone(),
+            image_policy: self.image_policy,
+            metrics: self.metrics.clone(),
+            prompt_discovery: self.prompt_discovery.clone(),
+            provenance_meta: self.provenance_meta,
+            random_seed: self.random_seed,
+            render_limits_policy: self.render_limits_policy,
+            render_pipeline: self.render_pipeline.clone(),
             rhai_template_renderer,
             source_filesystem: self.source_filesystem.clone(),
+            token_counter: self.token_counter.clone(),
+            uncategorized_policy: self.uncategorized_policy,
+            unknown_fields_policy: self.unknown_fields_policy,
         })
         .await
         {
-            Ok(prompt_controller_collection) => {
+            Ok(BuildPromptControllerCollectionResult {
+                error_collection,
+                prompt_controller_collection,
+            }) => {
+                if !error_collection.is_empty() {
+                    error!("Some prompts failed to build, serving the rest: {error_collection}");
+                }
+
+                if self.detect_duplicates {
+                    for (name, duplicate_name) in prompt_controller_collection
+                        .render_static_prompts()
+                        .await
+                        .find_duplicates()
+                    {
+                        warn!("Prompt '{duplicate_name}' renders the same messages as '{name}'");
+                    }
+                }
+
                 self.prompt_controller_collection_holder
                     .set(Some(Arc::new(prompt_controller_collection)))
                     .await;
+                self.last_build_project_result_holder
+                    .set(Some(build_project_result))
+                    .await;
+                self.on_prompts_rebuilt.notify_one();
+            }
+            Err(err) => error!("Failed to build prompts, keeping the previous ones: {err}"),
+        }
+    }
+
+    /// Returns true when the changes cannot be reloaded one by one, for
+    /// example because a prompt was added or removed
+    async fn do_reload_edited_prompts(&self) -> bool {
+        let Some(edited_relative_paths) = self
+            .changed_prompt_files
+            .lock()
+            .expect("Changed prompt files lock is poisoned")
+            .take_edited()
+        else {
+            return true;
+        };
+
+        let Some(prompt_controller_collection) =
+            self.prompt_controller_collection_holder.get().await
+        else {
+            return true;
+        };
+
+        let mut prompt_controller_collection = (*prompt_controller_collection).clone();
+
+        for relative_path in edited_relative_paths {
+            let contents = match self
+                .source_filesystem
+                .read_file_contents_string(&relative_path)
+                .await
+            {
+                Ok(contents) => contents,
+                Err(err) => {
+                    debug!("Unable to read the edited prompt file, rebuilding all prompts: {err}");
+
+                    return true;
+                }
+            };
+            let file: FileEntry = match (FileEntryStub {
+                contents,
+                relative_path,
+            })
+            .try_into()
+            {
+                Ok(file) => file,
+                Err(err) => {
+                    debug!("Edited file is not a prompt file, rebuilding all prompts: {err}");
+
+                    return true;
+                }
+            };
+
+            if !self.prompt_discovery.is_prompt(&file) {
+                continue;
+            }
+
+            let name = self.prompt_discovery.prompt_name(&file);
+
+            // Prompts that failed to build before are not in the collection
+            if !prompt_controller_collection
+                .prompt_controllers
+                .contains_key(&name)
+            {
+                return true;
+            }
+
+            match prompt_controller_collection.reload(&name, &file.contents) {
+                Ok(()) => info!("Reloaded prompt '{name}'"),
+                Err(err) => error!("{err:#}, keeping the previous version"),
+            }
+        }
+
+        self.prompt_controller_collection_holder
+            .set(Some(Arc::new(prompt_controller_collection)))
+            .await;
+        self.on_prompts_rebuilt.notify_one();
+
+        false
+    }
+
+    /// Asset changes rebuild the project too, but prompts only need to be
+    /// rebuilt when the content documents they link to have changed
+    async fn content_documents_changed(&self) -> bool {
+        match (
+            self.build_project_result_holder.get().await,
+            self.last_build_project_result_holder.get().await,
+        ) {
+            (Some(build_project_result), Some(last_build_project_result)) => {
+                !build_project_result.has_same_content_documents_as(&last_build_project_result)
+            }
+            _ => true,
+        }
+    }
+
+    async fn do_swap_esbuild_metafile(&self) {
+        let Some(esbuild_metafile) = self.esbuild_metafile_holder.get().await else {
+            debug!("Esbuild metafile was unset, keeping the prompt controllers as they are");
+
+            return;
+        };
+
+        match self.prompt_controller_collection_holder.get().await {
+            Some(prompt_controller_collection) => {
+                self.prompt_controller_collection_holder
+                    .set(Some(Arc::new(
+                        prompt_controller_collection.with_esbuild_metafile(esbuild_metafile),
+                    )))
+                    .await;
             }
-            Err(err) => error!("Failed to build prompts: {err}"),
+            None => self.do_build_prompt_controllers().await,
         }
     }
 }
@@ -90,17 +272,215 @@ impl PromptControllerCollectionBuilder {
 #[async_trait]
 impl Service for PromptControllerCollectionBuilder {
     async fn run(&self) -> Result<()> {
+        let mut needs_rebuild = true;
+
         loop {
-            self.do_build_prompt_controllers().await;
+            if needs_rebuild {
+                self.do_build_prompt_controllers().await;
+            }
+
+            needs_rebuild = tokio::select! {
+                _ = self.build_project_result_holder.update_notifier.notified() => {
+                    if self.content_documents_changed().await {
+                        true
+                    } else {
+                        self.do_swap_esbuild_metafile().await;
 
-            tokio::select! {
-                _ = self.build_project_result_holder.update_notifier.notified() => continue,
-                _ = self.on_prompt_file_changed.notified() => continue,
-                _ = self.rhai_template_renderer_holder.update_notifier.notified() => continue,
+                        false
+                    }
+                }
+                _ = self.on_prompt_file_changed.notified() => self.do_reload_edited_prompts().await,
+                _ = self.rhai_template_renderer_holder.update_notifier.notified() => true,
                 _ = self.ctrlc_notifier.cancelled() => break,
-            }
+            };
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use anyhow::anyhow;
+    use indoc::indoc;
+    use rhai_components::rhai_template_renderer::RhaiTemplateRenderer;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::build_project::build_project_result_stub::BuildProjectResultStub;
+    use crate::filesystem::storage::DEFAULT_MAX_FILE_BYTES;
+    use crate::mcp::content_block::ContentBlock;
+    use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
+    use crate::mcp::prompt_metrics_noop::PromptMetricsNoop;
+    use crate::rhai_template_renderer_factory::RhaiTemplateRendererFactory;
+    use crate::token_counter_heuristic::TokenCounterHeuristic;
+
+    async fn serve_greeting(
+        prompt_controller_collection_holder: &PromptControllerCollectionHolder,
+    ) -> Result<ContentBlock> {
+        let Some(prompt_controller) = prompt_controller_collection_holder
+            .get()
+            .await
+            .and_then(|prompt_controller_collection| prompt_controller_collection.get("greeting"))
+        else {
+            return Err(anyhow!("Greeting prompt is not built"));
+        };
+        let response = prompt_controller
+            .respond_to(PromptsGet::mock("greeting", Default::default()))
+            .await?;
+
+        Ok(response.messages[0].content.clone())
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_serves_edited_prompt() -> Result<()> {
+        let base_directory = tempdir()?;
+        let prompt_path = base_directory.path().join("prompts/greeting.md");
+        let rhai_template_renderer: RhaiTemplateRenderer = RhaiTemplateRendererFactory::new(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")),
+            PathBuf::from("shortcodes"),
+        )
+        .try_into()?;
+        let build_project_result_holder: BuildProjectResultHolder = Default::default();
+        let esbuild_metafile_holder: EsbuildMetaFileHolder = Default::default();
+        let prompt_controller_collection_holder: PromptControllerCollectionHolder =
+            Default::default();
+        let rhai_template_renderer_holder: RhaiTemplateRendererHolder = Default::default();
+
+        build_project_result_holder
+            .set(Some(
+                BuildProjectResultStub {
+                    content_document_linker: Default::default(),
+                    content_document_sources: Default::default(),
+                    esbuild_metafile: Default::default(),
+                    memory_filesystem: Default::default(),
+                }
+                .into(),
+            ))
+            .await;
+        esbuild_metafile_holder.set(Some(Default::default())).await;
+        rhai_template_renderer_holder
+            .set(Some(rhai_template_renderer))
+            .await;
+
+        let prompt_controller_collection_builder = PromptControllerCollectionBuilder {
+            allow_remote_images: false,
+            argument_meta: false,
+            asset_path_renderer: AssetPathRenderer {
+                base_path: "https://example.com/".to_string(),
+            },
+            build_project_result_holder,
+            changed_prompt_files: Default::default(),
+            ctrlc_notifier: CancellationToken::new(),
+            detect_duplicates: false,
+            esbuild_metafile_holder,
+            fail_fast: true,
+            fetch_policy: Default::default(),
+            image_policy: Default::default(),
+            last_build_project_result_holder: Default::default(),
+            metrics: Arc::new(PromptMetricsNoop),
+            on_prompt_file_changed: Default::default(),
+            on_prompts_rebuilt: Default::default(),
+            prompt_controller_collection_holder: prompt_controller_collection_holder.clone(),
+            prompt_discovery: PromptDiscovery::new(&["prompts/**/*.md".to_string()], &[])?,
+            provenance_meta: false,
+            random_seed: None,
+            render_limits_policy: Default::default(),
+            render_pipeline: Default::default(),
+            rhai_template_renderer_holder,
+            source_filesystem: Arc::new(Storage {
+                base_directory: base_directory.path().to_path_buf(),
+                max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+                source_encodings: Default::default(),
+            }),
+            token_counter: Arc::new(TokenCounterHeuristic),
+            uncategorized_policy: Default::default(),
+            unknown_fields_policy: Default::default(),
+        };
+        let prompt_source = |greeting: &str| {
+            format!(
+                indoc! {r#"
+                +++
+                description = "Greeting"
+                title = "Greeting"
+
+                [arguments]
+                +++
+
+                **user**: {}
+                "#},
+                greeting
+            )
+        };
+
+        fs::create_dir_all(base_directory.path().join("prompts"))?;
+        fs::write(&prompt_path, prompt_source("Hello"))?;
+        prompt_controller_collection_builder
+            .do_build_prompt_controllers()
+            .await;
+
+        assert_eq!(
+            serve_greeting(&prompt_controller_collection_holder).await?,
+            "Hello".into()
+        );
+        assert!(
+            !prompt_controller_collection_builder
+                .content_documents_changed()
+                .await
+        );
+
+        fs::write(&prompt_path, prompt_source("Good morning"))?;
+        prompt_controller_collection_builder
+            .do_build_prompt_controllers()
+            .await;
+
+        assert_eq!(
+            serve_greeting(&prompt_controller_collection_holder).await?,
+            "Good morning".into()
+        );
+
+        fs::write(&prompt_path, "+++\nnot valid front matter")?;
+        prompt_controller_collection_builder
+            .do_build_prompt_controllers()
+            .await;
+
+        assert_eq!(
+            serve_greeting(&prompt_controller_collection_holder).await?,
+            "Good morning".into()
+        );
+
+        fs::write(&prompt_path, prompt_source("Good evening"))?;
+        prompt_controller_collection_builder
+            .changed_prompt_files
+            .lock()
+            .expect("Changed prompt files lock is poisoned")
+            .mark_edited(PathBuf::from("prompts/greeting.md"));
+
+        assert!(
+            !prompt_controller_collection_builder
+                .do_reload_edited_prompts()
+                .await
+        );
+        assert_eq!(
+            serve_greeting(&prompt_controller_collection_holder).await?,
+            "Good evening".into()
+        );
+
+        prompt_controller_collection_builder
+            .changed_prompt_files
+            .lock()
+            .expect("Changed prompt files lock is poisoned")
+            .mark_needs_full_rebuild();
+
+        assert!(
+            prompt_controller_collection_builder
+                .do_reload_edited_prompts()
+                .await
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::debug;
+use log::error;
+use tokio::sync::Notify;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+use crate::cmd::service::Service;
+use crate::holder::Holder as _;
+use crate::mcp::session_manager::SessionManager;
+use crate::prompt_controller_collection_holder::PromptControllerCollectionHolder;
+
+/// Tells sessions that were promised list change notifications that the
+/// prompt names changed. Rebuilds that follow each other within the
+/// debounce window produce a single notification.
+pub struct PromptsListChangedNotifier {
+    pub ctrlc_notifier: CancellationToken,
+    pub debounce: Duration,
+    pub on_prompts_rebuilt: Arc<Notify>,
+    pub prompt_controller_collection_holder: PromptControllerCollectionHolder,
+    pub session_manager: SessionManager,
+}
+
+impl PromptsListChangedNotifier {
+    async fn prompt_names(&self) -> Vec<String> {
+        match self.prompt_controller_collection_holder.get().await {
+            Some(prompt_controller_collection) => prompt_controller_collection
+                .prompt_controllers
+                .keys()
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns false if the service was cancelled while waiting
+    async fn wait_for_quiet_period(&self) -> bool {
+        loop {
+            tokio::select! {
+                _ = self.on_prompts_rebuilt.notified() => continue,
+                _ = sleep(self.debounce) => return true,
+                _ = self.ctrlc_notifier.cancelled() => return false,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Service for PromptsListChangedNotifier {
+    async fn run(&self) -> Result<()> {
+        // Sessions that connect before the first build see no prompts
+        let mut notified_prompt_names: Vec<String> = Vec::new();
+
+        loop {
+            tokio::select! {
+                _ = self.on_prompts_rebuilt.notified() => {}
+                _ = self.ctrlc_notifier.cancelled() => break,
+            }
+
+            if !self.wait_for_quiet_period().await {
+                break;
+            }
+
+            let prompt_names = self.prompt_names().await;
+
+            if prompt_names == notified_prompt_names {
+                debug!("Prompts were rebuilt, but the prompt list is the same");
+
+                continue;
+            }
+
+            notified_prompt_names = prompt_names;
+
+            debug!("Notifying MCP sessions that the prompt list changed");
+
+            if let Err(err) = self.session_manager.notify_prompts_list_changed().await {
+                error!("Failed to notify MCP sessions: {err:#?}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use tokio::sync::mpsc::Receiver;
+
+    use super::*;
+    use crate::mcp::MCP_PROTOCOL_VERSION;
+    use crate::mcp::jsonrpc::implementation::Implementation;
+    use crate::mcp::jsonrpc::server_to_client_notification::ServerToClientNotification;
+    use crate::mcp::prompt_controller_collection::PromptControllerCollection;
+    use crate::mcp::session_with_notifications_receiver::SessionWithNotificationsReceiver;
+    use crate::prompt_document_controller::PromptDocumentController;
+
+    async fn start_session(
+        session_manager: &SessionManager,
+        prompts_list_changed: bool,
+    ) -> Result<Receiver<ServerToClientNotification>> {
+        let SessionWithNotificationsReceiver {
+            notification_rx, ..
+        } = session_manager
+            .start_new_session(
+                Implementation {
+                    description: None,
+                    name: "test-client".to_string(),
+                    title: None,
+                    version: "1.0.0".to_string(),
+                },
+                prompts_list_changed,
+                MCP_PROTOCOL_VERSION.to_string(),
+            )
+            .await
+            .map_err(|err| anyhow!("{err}"))?;
+
+        Ok(notification_rx)
+    }
+
+    async fn set_prompts(
+        prompt_controller_collection_holder: &PromptControllerCollectionHolder,
+        names: &[&str],
+    ) -> Result<()> {
+        let mut prompt_controller_collection = PromptControllerCollection::default();
+
+        for name in names {
+            prompt_controller_collection.prompt_controllers.insert(
+                name.to_string(),
+                Arc::new(PromptDocumentController::mock(
+                    name,
+                    "+++\ndescription = \"Test\"\ntitle = \"Test\"\n\n[arguments]\n+++\n\n**user**: Hello\n",
+                )?),
+            );
+        }
+
+        prompt_controller_collection_holder
+            .set(Some(Arc::new(prompt_controller_collection)))
+            .await;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rebuilds_notify_subscribed_sessions_of_changed_names() -> Result<()> {
+        let session_manager: SessionManager = Default::default();
+        let mut notification_rx = start_session(&session_manager, true).await?;
+        let mut unsubscribed_notification_rx = start_session(&session_manager, false).await?;
+        let prompt_controller_collection_holder: PromptControllerCollectionHolder =
+            Default::default();
+
+        set_prompts(&prompt_controller_collection_holder, &["greet"]).await?;
+
+        let ctrlc_notifier = CancellationToken::new();
+        let on_prompts_rebuilt: Arc<Notify> = Default::default();
+        let notifier = Arc::new(PromptsListChangedNotifier {
+            ctrlc_notifier: ctrlc_notifier.clone(),
+            debounce: Duration::ZERO,
+            on_prompts_rebuilt: on_prompts_rebuilt.clone(),
+            prompt_controller_collection_holder: prompt_controller_collection_holder.clone(),
+            session_manager,
+        });
+        let notifier_handle = tokio::spawn({
+            let notifier = notifier.clone();
+
+            async move { notifier.run().await }
+        });
+
+        // The notifier only runs while the test waits for a notification
+        for _ in 0..3 {
+            on_prompts_rebuilt.notify_one();
+        }
+
+        let first_notification = notification_rx.recv().await;
+
+        set_prompts(&prompt_controller_collection_holder, &["greet", "review"]).await?;
+        on_prompts_rebuilt.notify_one();
+
+        let second_notification = notification_rx.recv().await;
+
+        ctrlc_notifier.cancel();
+        notifier_handle.await??;
+
+        for notification in [first_notification, second_notification] {
+            assert!(matches!(
+                notification,
+                Some(ServerToClientNotification::PromptsListChanged(_))
+            ));
+        }
+
+        assert!(notification_rx.try_recv().is_err());
+        assert!(unsubscribed_notification_rx.try_recv().is_err());
+
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
+use std::collections::BTreeSet;
 use std::fs::create_dir_all;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::time::Duration;
 
 use anyhow::Result;
@@ -9,6 +11,7 @@ use log::error;
 use log::info;
 use notify::EventKind;
 use notify::RecommendedWatcher;
+use notify::event::ModifyKind;
 use notify_debouncer_full::DebounceEventResult;
 use notify_debouncer_full::Debouncer;
 use notify_debouncer_full::RecommendedCache;
@@ -16,7 +19,10 @@ use notify_debouncer_full::new_debouncer;
 use notify_debouncer_full::notify::RecursiveMode;
 use tokio::sync::Notify;
 
+use crate::cmd::watch::changed_prompt_files::ChangedPromptFiles;
+
 pub struct WatchProjectHandle {
+    pub changed_prompt_files: Arc<Mutex<ChangedPromptFiles>>,
     pub debouncer: Debouncer<RecommendedWatcher, RecommendedCache>,
     pub on_author_file_changed: Arc<Notify>,
     pub on_content_file_changed: Arc<Notify>,
@@ -43,13 +49,23 @@ fn is_temp_file(path: &Path) -> bool {
     path_string.ends_with("~") || path_string.ends_with(".swp") || path_string.ends_with(".tmp")
 }
 
-pub fn watch_project_files(source_directory: PathBuf) -> Result<WatchProjectHandle> {
+/// Prompts can also live outside of the prompts directory, so the
+/// directories that prompt discovery includes are watched as well
+pub fn watch_project_files(
+    source_directory: PathBuf,
+    prompt_directories: BTreeSet<PathBuf>,
+) -> Result<WatchProjectHandle> {
     let authors_directory = source_directory.join("authors");
     let content_directory = source_directory.join("content");
     let esbuild_metafile_path = source_directory.join("esbuild-meta.json");
-    let prompts_directory = source_directory.join("prompts");
+    let prompt_collections_directory = source_directory.join("prompt_collections");
+    let prompts_directories: Vec<PathBuf> = prompt_directories
+        .iter()
+        .map(|prompt_directory| source_directory.join(prompt_directory))
+        .collect();
     let shortcodes_directory = source_directory.join("shortcodes");
 
+    let changed_prompt_files: Arc<Mutex<ChangedPromptFiles>> = Default::default();
     let on_author_file_changed = Arc::new(Notify::new());
     let on_content_file_changed = Arc::new(Notify::new());
     let on_esbuild_metafile_changed = Arc::new(Notify::new());
@@ -57,14 +73,17 @@ pub fn watch_project_files(source_directory: PathBuf) -> Result<WatchProjectHand
     let on_shortcode_file_changed = Arc::new(Notify::new());
 
     let authors_directory_clone = authors_directory.clone();
+    let changed_prompt_files_clone = changed_prompt_files.clone();
     let content_directory_clone = content_directory.clone();
     let on_shortcode_file_changed_clone = on_shortcode_file_changed.clone();
     let on_author_file_changed_clone = on_author_file_changed.clone();
     let on_content_file_changed_clone = on_content_file_changed.clone();
     let on_esbuild_metafile_changed_clone = on_esbuild_metafile_changed.clone();
     let on_prompt_file_changed_clone = on_prompt_file_changed.clone();
-    let prompts_directory_clone = prompts_directory.clone();
+    let prompt_collections_directory_clone = prompt_collections_directory.clone();
+    let prompts_directories_clone = prompts_directories.clone();
     let shortcodes_directory_clone = shortcodes_directory.clone();
+    let source_directory_canonical = source_directory.canonicalize()?;
 
     let mut debouncer = new_debouncer(
         Duration::from_millis(100),
@@ -89,14 +108,47 @@ pub fn watch_project_files(source_directory: PathBuf) -> Result<WatchProjectHand
                                     return;
                                 }
 
-                                if is_inside_directory(&prompts_directory_clone, path) {
+                                let is_prompt_document =
+                                    prompts_directories_clone.iter().any(|prompts_directory| {
+                                        is_inside_directory(prompts_directory, path)
+                                    });
+                                let is_prompt_file = is_prompt_document
+                                    || is_inside_directory(
+                                        &prompt_collections_directory_clone,
+                                        path,
+                                    );
+
+                                if is_prompt_file {
                                     info!("Prompt file change detected: {:?}", path.display());
 
+                                    let edited_relative_path = match event.kind {
+                                        EventKind::Modify(
+                                            ModifyKind::Any | ModifyKind::Data(_),
+                                        ) if is_prompt_document => {
+                                            path.canonicalize().ok().and_then(|path| {
+                                                path.strip_prefix(&source_directory_canonical)
+                                                    .ok()
+                                                    .map(Path::to_path_buf)
+                                            })
+                                        }
+                                        _ => None,
+                                    };
+                                    let mut changed_prompt_files = changed_prompt_files_clone
+                                        .lock()
+                                        .expect("Changed prompt files lock is poisoned");
+
+                                    match edited_relative_path {
+                                        Some(relative_path) => {
+                                            changed_prompt_files.mark_edited(relative_path)
+                                        }
+                                        None => changed_prompt_files.mark_needs_full_rebuild(),
+                                    }
+
+                                    drop(changed_prompt_files);
                                     on_prompt_file_changed_clone.notify_waiters();
-
-                                    return;
                                 }
 
+                                // Prompts can be included from the content directory
                                 if is_inside_directory(&content_directory_clone, path) {
                                     info!("Content file change detected: {:?}", path.display());
 
@@ -121,7 +173,9 @@ pub fn watch_project_files(source_directory: PathBuf) -> Result<WatchProjectHand
                                     return;
                                 }
 
-                                info!("Ignoring file change: {:?}", path.display());
+                                if !is_prompt_file {
+                                    info!("Ignoring file change: {:?}", path.display());
+                                }
                             }
 
                             return;
@@ -137,18 +191,29 @@ pub fn watch_project_files(source_directory: PathBuf) -> Result<WatchProjectHand
     create_dir_all(&authors_directory)?;
     debouncer.watch(authors_directory, RecursiveMode::Recursive)?;
 
+    for prompts_directory in &prompts_directories {
+        // Prompts included from the content directory are watched with it
+        if !prompts_directory.starts_with(&content_directory) {
+            create_dir_all(prompts_directory)?;
+            debouncer.watch(prompts_directory, RecursiveMode::Recursive)?;
+        }
+    }
+
     create_dir_all(&content_directory)?;
     debouncer.watch(content_directory, RecursiveMode::Recursive)?;
 
-    create_dir_all(&prompts_directory)?;
-    debouncer.watch(prompts_directory, RecursiveMode::Recursive)?;
+    create_dir_all(&prompt_collections_directory)?;
+    debouncer.watch(prompt_collections_directory, RecursiveMode::Recursive)?;
 
     create_dir_all(&shortcodes_directory)?;
     debouncer.watch(shortcodes_directory, RecursiveMode::Recursive)?;
 
-    debouncer.watch(source_directory.clone(), RecursiveMode::NonRecursive)?;
+    if !prompts_directories.contains(&source_directory) {
+        debouncer.watch(source_directory.clone(), RecursiveMode::NonRecursive)?;
+    }
 
     Ok(WatchProjectHandle {
+        changed_prompt_files,
         debouncer,
         on_author_file_changed,
         on_content_file_changed,
@@ -0,0 +1,80 @@
+use anyhow::Context as _;
+use anyhow::Result;
+use rhai_components::component_syntax::component_prop::ComponentProp;
+use serde::Deserialize;
+
+const SCHEMA_LINE_PREFIX: &str = "//!";
+
+/// Declared in TOML, in the `//!` comment lines that open a component file
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentSchema {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub props: Vec<ComponentProp>,
+}
+
+impl ComponentSchema {
+    pub fn from_rhai_source(source: &str) -> Result<Self> {
+        let schema_source = source
+            .lines()
+            .map_while(|line| line.trim_start().strip_prefix(SCHEMA_LINE_PREFIX))
+            .map(|line| line.strip_prefix(' ').unwrap_or(line))
+            .collect::<Vec<&str>>()
+            .join("\n");
+
+        toml::from_str(&schema_source).context("Unable to parse the component schema")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_props_are_parsed_from_leading_comments() -> Result<()> {
+        let component_schema = ComponentSchema::from_rhai_source(indoc! {r#"
+            //! description = "Highlighted side note"
+            //!
+            //! [[props]]
+            //! name = "title"
+            //! required = true
+            //! type = "string"
+            //!
+            //! [[props]]
+            //! default = "info"
+            //! name = "variant"
+            //! type = "string"
+
+            //! name = "ignored"
+            fn template(context, props, content) {
+                props.title
+            }
+        "#})?;
+
+        assert_eq!(
+            component_schema.description.as_deref(),
+            Some("Highlighted side note")
+        );
+        assert_eq!(component_schema.props.len(), 2);
+        assert!(component_schema.props[0].required);
+        assert!(!component_schema.props[1].required);
+        assert_eq!(component_schema.props[1].default.as_deref(), Some("info"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_component_without_schema_has_no_props() -> Result<()> {
+        let component_schema =
+            ComponentSchema::from_rhai_source("fn template(context, props, content) {}")?;
+
+        assert!(component_schema.description.is_none());
+        assert!(component_schema.props.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use anyhow::Result;
+use anyhow::anyhow;
+use rhai::Array;
+use rhai::Dynamic;
+use rhai::FLOAT;
+use rhai::Map;
+
+/// Checks an override against the declared value of a prop, down into arrays
+/// and maps. Integers are accepted, and converted, where floats are declared.
+/// Array items are checked against the first declared item, and map overrides
+/// are merged over the declared map.
+pub fn conform_prop_override(path: &str, declared: &Dynamic, value: Dynamic) -> Result<Dynamic> {
+    if declared.is_float()
+        && let Ok(int) = value.as_int()
+    {
+        return Ok(Dynamic::from_float(int as FLOAT));
+    }
+
+    if declared.type_name() != value.type_name() {
+        return Err(anyhow!(
+            "Prop '{path}' expects {}, got {}",
+            declared.type_name(),
+            value.type_name()
+        ));
+    }
+
+    if let Some(declared_items) = declared.read_lock::<Array>() {
+        let Some(declared_item) = declared_items.first() else {
+            return Ok(value);
+        };
+        let Some(items) = value.try_cast::<Array>() else {
+            return Err(anyhow!("Prop '{path}' expects an array"));
+        };
+
+        return Ok(items
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| {
+                conform_prop_override(&format!("{path}[{index}]"), declared_item, item)
+            })
+            .collect::<Result<Array>>()?
+            .into());
+    }
+
+    if let Some(declared_entries) = declared.read_lock::<Map>() {
+        let Some(entries) = value.try_cast::<Map>() else {
+            return Err(anyhow!("Prop '{path}' expects a map"));
+        };
+        let mut merged_entries = declared_entries.clone();
+
+        for (key, entry) in entries {
+            let Some(declared_entry) = declared_entries.get(&key) else {
+                return Err(anyhow!(
+                    "Prop '{path}.{key}' is not declared in the front matter"
+                ));
+            };
+
+            merged_entries.insert(
+                key.clone(),
+                conform_prop_override(&format!("{path}.{key}"), declared_entry, entry)?,
+            );
+        }
+
+        return Ok(merged_entries.into());
+    }
+
+    Ok(value)
+}
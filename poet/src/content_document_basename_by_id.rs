@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use anyhow::anyhow;
+
+use crate::content_document_basename::ContentDocumentBasename;
+
+#[derive(Clone, Default)]
+pub struct ContentDocumentBasenameById {
+    basenames: HashMap<String, ContentDocumentBasename>,
+}
+
+impl ContentDocumentBasenameById {
+    pub fn get(&self, id: &str) -> Option<&ContentDocumentBasename> {
+        self.basenames.get(id)
+    }
+
+    pub fn register(&mut self, id: &str, basename: ContentDocumentBasename) -> Result<()> {
+        if let Some(existing_basename) = self.basenames.get(id) {
+            return Err(anyhow!(
+                "Duplicate document id: #{id} in '{basename}' (already used by '{existing_basename}')"
+            ));
+        }
+
+        self.basenames.insert(id.to_string(), basename);
+
+        Ok(())
+    }
+}
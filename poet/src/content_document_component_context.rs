@@ -108,6 +108,10 @@ impl ContentDocumentComponentContext {
         Ok(self.content_document_linker.link_to(path)?)
     }
 
+    fn rhai_link_to_id(&mut self, id: &str) -> Result<String, Box<EvalAltResult>> {
+        Ok(self.content_document_linker.link_to_id(id)?)
+    }
+
     fn rhai_primary_collection(
         &mut self,
     ) -> Result<ContentDocumentCollectionRanked, Box<EvalAltResult>> {
@@ -166,6 +170,7 @@ impl CustomType for ContentDocumentComponentContext {
             .with_fn("belongs_to", Self::rhai_belongs_to)
             .with_fn("collection", Self::rhai_collection)
             .with_fn("is_current_page", Self::rhai_is_current_page)
-            .with_fn("link_to", Self::rhai_link_to);
+            .with_fn("link_to", Self::rhai_link_to)
+            .with_fn("link_to_id", Self::rhai_link_to_id);
     }
 }
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use markdown::mdast::Node;
+use markdown::mdast::Root;
+use markdown::mdast::Toml;
+use markdown::mdast::Yaml;
+
+use crate::content_document_basename::ContentDocumentBasename;
+
+/// Document contents without their front matter, loaded once per build and
+/// shared by every controller that embeds them. Watch mode rebuilds the
+/// project on changes, so a new collection replaces the stale one.
+#[derive(Clone, Default)]
+pub struct ContentDocumentContentByBasename {
+    contents: HashMap<ContentDocumentBasename, Arc<String>>,
+}
+
+impl ContentDocumentContentByBasename {
+    pub fn get(&self, basename: &ContentDocumentBasename) -> Option<Arc<String>> {
+        self.contents.get(basename).cloned()
+    }
+
+    pub fn register(&mut self, basename: ContentDocumentBasename, mdast: &Node, contents: &str) {
+        let front_matter_end = match mdast {
+            Node::Root(Root { children, .. }) => children.iter().find_map(|child| match child {
+                Node::Toml(Toml {
+                    position: Some(position),
+                    ..
+                })
+                | Node::Yaml(Yaml {
+                    position: Some(position),
+                    ..
+                }) => Some(position.end.offset),
+                _ => None,
+            }),
+            _ => None,
+        };
+
+        self.contents.insert(
+            basename,
+            Arc::new(
+                contents[front_matter_end.unwrap_or(0)..]
+                    .trim_start()
+                    .to_string(),
+            ),
+        );
+    }
+}
@@ -4,7 +4,6 @@ use serde::Serialize;
 use crate::content_document_basename::ContentDocumentBasename;
 
 #[derive(Clone, Debug, Deserialize, Hash, Serialize)]
-#[serde(deny_unknown_fields)]
 pub struct CollectionPlacement {
     #[serde(default)]
     pub after: Option<ContentDocumentBasename>,
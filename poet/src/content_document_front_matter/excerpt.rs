@@ -0,0 +1,25 @@
+use rhai::Map;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Reusable snippet attached to a document or a prompt, which components can
+/// pull in by its type
+#[derive(Clone, Debug, Deserialize, Hash, Serialize)]
+pub struct Excerpt {
+    pub content: String,
+    #[serde(rename = "type")]
+    pub excerpt_type: String,
+}
+
+impl Excerpt {
+    /// Exposed to templates as a map, so the type is under `type` like in
+    /// the front matter
+    pub fn to_rhai_map(&self) -> Map {
+        let mut map = Map::new();
+
+        map.insert("content".into(), self.content.clone().into());
+        map.insert("type".into(), self.excerpt_type.clone().into());
+
+        map
+    }
+}
@@ -1,29 +1,25 @@
 pub mod collection_placement;
 pub mod collection_placement_list;
+pub mod excerpt;
 
 use chrono::DateTime;
 use chrono::Utc;
+use rhai::Array;
 use rhai::CustomType;
+use rhai::Dynamic;
 use rhai::Map;
 use rhai::TypeBuilder;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::content_document_front_matter::collection_placement_list::CollectionPlacementList;
+use crate::content_document_front_matter::excerpt::Excerpt;
 
 fn default_render() -> bool {
     true
 }
 
-// #[derive(Debug, Deserialize, Serialize)]
-// pub struct Excerpt {
-//     #[serde(rename = "type")]
-//     pub excerpt_type: String,
-//     pub content: String,
-// }
-
 #[derive(Clone, Debug, Deserialize, Hash, Serialize)]
-#[serde(deny_unknown_fields)]
 pub struct ContentDocumentFrontMatter {
     #[serde(default)]
     pub authors: Vec<String>,
@@ -31,11 +27,15 @@ pub struct ContentDocumentFrontMatter {
     #[serde(default)]
     pub id: Option<String>,
     pub layout: String,
-    // pub references: Vec<String>,
+    /// Documents this one depends on, by path or `#id`. Resolved to document
+    /// basenames at build time.
+    #[serde(default)]
+    pub references: Vec<String>,
     // pub truth_source_for: Vec<String>,
     #[serde(default, rename = "collection")]
     pub collections: CollectionPlacementList,
-    // pub excerpts: Vec<Excerpt>,
+    #[serde(default)]
+    pub excerpts: Vec<Excerpt>,
     #[serde(default, with = "crate::flexible_datetime")]
     pub last_updated_at: Option<DateTime<Utc>>,
     pub primary_collection: Option<String>,
@@ -52,12 +52,14 @@ impl ContentDocumentFrontMatter {
         Self {
             authors: vec![],
             description: "".to_string(),
+            excerpts: Default::default(),
             id: None,
             last_updated_at: None,
             layout: "SomeLayout".to_string(),
             collections: Default::default(),
             primary_collection: None,
             props: Default::default(),
+            references: Default::default(),
             render: true,
             title: name.to_string(),
         }
@@ -69,10 +71,21 @@ impl ContentDocumentFrontMatter {
         self.description.clone()
     }
 
+    fn rhai_excerpts(&mut self) -> Array {
+        self.excerpts
+            .iter()
+            .map(|excerpt| Dynamic::from_map(excerpt.to_rhai_map()))
+            .collect()
+    }
+
     fn rhai_props(&mut self) -> Map {
         self.props.clone()
     }
 
+    fn rhai_references(&mut self) -> Array {
+        self.references.iter().cloned().map(Dynamic::from).collect()
+    }
+
     fn rhai_render(&mut self) -> bool {
         self.render
     }
@@ -87,7 +100,9 @@ impl CustomType for ContentDocumentFrontMatter {
         builder
             .with_name("ContentDocumentFrontMatter")
             .with_get("description", Self::rhai_description)
+            .with_get("excerpts", Self::rhai_excerpts)
             .with_get("props", Self::rhai_props)
+            .with_get("references", Self::rhai_references)
             .with_get("render", Self::rhai_render)
             .with_get("title", Self::rhai_title);
     }
@@ -2,34 +2,49 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::content_document_basename::ContentDocumentBasename;
+use crate::content_document_basename_by_id::ContentDocumentBasenameById;
+use crate::content_document_content_by_basename::ContentDocumentContentByBasename;
 use crate::content_document_reference::ContentDocumentReference;
 
 #[derive(Clone, Default)]
 pub struct ContentDocumentLinker {
-    pub content_document_basename_by_id: Arc<HashMap<String, ContentDocumentBasename>>,
+    pub content_document_basename_by_id: Arc<ContentDocumentBasenameById>,
     pub content_document_by_basename:
         Arc<HashMap<ContentDocumentBasename, ContentDocumentReference>>,
+    pub content_document_content_by_basename: Arc<ContentDocumentContentByBasename>,
 }
 
 impl ContentDocumentLinker {
     pub fn link_to(&self, path: &str) -> Result<String, String> {
         let basename = self.resolve_id(path)?;
 
-        if let Some(reference) = self.content_document_by_basename.get(&basename) {
-            if !reference.front_matter.render {
-                return Err(format!(
-                    "Document cannot be linked to, because rendering of it is disabled: {basename}"
-                ));
-            }
+        self.link_to_basename(&basename)
+    }
 
-            match reference.canonical_link() {
-                Ok(canonical_link) => Ok(canonical_link),
-                Err(err) => Err(format!(
-                    "Unable to generate canonical link for {basename}: {err}"
-                )),
-            }
+    /// Contents of the linked document, without its front matter
+    pub fn linked_content(&self, path: &str) -> Result<Arc<String>, String> {
+        let basename = self.resolve_id(path)?;
+
+        self.content_document_content_by_basename
+            .get(&basename)
+            .ok_or_else(|| format!("Document does not exist: {basename}"))
+    }
+
+    pub fn link_to_id(&self, id: &str) -> Result<String, String> {
+        match self.content_document_basename_by_id.get(id) {
+            Some(basename) => self.link_to_basename(basename),
+            None => Err(format!("Document with id does not exist: #{id}")),
+        }
+    }
+
+    /// Basename of an existing document, by path or `#id`
+    pub fn resolve_reference(&self, path: &str) -> Result<ContentDocumentBasename, String> {
+        let basename = self.resolve_id(path)?;
+
+        if self.content_document_by_basename.contains_key(&basename) {
+            Ok(basename)
         } else {
-            Err(format!("Document does not exist: {path}"))
+            Err(format!("Document does not exist: {basename}"))
         }
     }
 
@@ -50,4 +65,126 @@ impl ContentDocumentLinker {
             Ok(path.to_string().into())
         }
     }
+
+    fn link_to_basename(&self, basename: &ContentDocumentBasename) -> Result<String, String> {
+        if let Some(reference) = self.content_document_by_basename.get(basename) {
+            if !reference.front_matter.render {
+                return Err(format!(
+                    "Document cannot be linked to, because rendering of it is disabled: {basename}"
+                ));
+            }
+
+            match reference.canonical_link() {
+                Ok(canonical_link) => Ok(canonical_link),
+                Err(err) => Err(format!(
+                    "Unable to generate canonical link for {basename}: {err}"
+                )),
+            }
+        } else {
+            Err(format!("Document does not exist: {basename}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use anyhow::anyhow;
+
+    use super::*;
+    use crate::content_document_front_matter::ContentDocumentFrontMatter;
+    use crate::string_to_mdast::string_to_mdast;
+
+    fn mock_reference(basename: &str, id: &str) -> ContentDocumentReference {
+        ContentDocumentReference {
+            basename_path: basename.into(),
+            front_matter: ContentDocumentFrontMatter {
+                id: Some(id.to_string()),
+                ..ContentDocumentFrontMatter::mock(basename)
+            },
+            generated_page_base_path: "/".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_link_to_id() -> Result<()> {
+        let reference = mock_reference("guides/api", "api-guide");
+        let mut content_document_basename_by_id = ContentDocumentBasenameById::default();
+
+        content_document_basename_by_id.register("api-guide", reference.basename())?;
+
+        let content_document_linker = ContentDocumentLinker {
+            content_document_basename_by_id: Arc::new(content_document_basename_by_id),
+            content_document_by_basename: Arc::new(HashMap::from([(
+                reference.basename(),
+                reference,
+            )])),
+            content_document_content_by_basename: Default::default(),
+        };
+
+        assert_eq!(
+            content_document_linker.link_to_id("api-guide"),
+            Ok("/guides/api/".to_string())
+        );
+        assert!(content_document_linker.link_to_id("missing").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_duplicate_id_is_rejected() -> Result<()> {
+        let mut content_document_basename_by_id = ContentDocumentBasenameById::default();
+
+        content_document_basename_by_id.register(
+            "api-guide",
+            mock_reference("guides/api", "api-guide").basename(),
+        )?;
+
+        assert!(
+            content_document_basename_by_id
+                .register(
+                    "api-guide",
+                    mock_reference("guides/other", "api-guide").basename(),
+                )
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_linked_content_is_shared_between_prompts() -> Result<()> {
+        let reference = mock_reference("guides/api", "api-guide");
+        let mut content_document_basename_by_id = ContentDocumentBasenameById::default();
+        let mut content_document_content_by_basename = ContentDocumentContentByBasename::default();
+        let contents = "+++\ntitle = \"API\"\n+++\n\n# API guide\n";
+
+        content_document_basename_by_id.register("api-guide", reference.basename())?;
+        content_document_content_by_basename.register(
+            reference.basename(),
+            &string_to_mdast(contents)?,
+            contents,
+        );
+
+        let content_document_linker = ContentDocumentLinker {
+            content_document_basename_by_id: Arc::new(content_document_basename_by_id),
+            content_document_by_basename: Default::default(),
+            content_document_content_by_basename: Arc::new(content_document_content_by_basename),
+        };
+        let first_prompt_linker = content_document_linker.clone();
+        let second_prompt_linker = content_document_linker.clone();
+
+        let first_content = first_prompt_linker
+            .linked_content("#api-guide")
+            .map_err(|err| anyhow!(err))?;
+        let second_content = second_prompt_linker
+            .linked_content("guides/api")
+            .map_err(|err| anyhow!(err))?;
+
+        assert_eq!(*first_content, "# API guide\n");
+        assert!(Arc::ptr_eq(&first_content, &second_content));
+        assert!(first_prompt_linker.linked_content("missing").is_err());
+
+        Ok(())
+    }
 }
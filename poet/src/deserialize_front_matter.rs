@@ -0,0 +1,41 @@
+use std::error::Error;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use log::warn;
+use serde::Deserialize;
+use serde::Deserializer;
+
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+
+/// Deserializes TOML or YAML front matter in a single pass, collecting the
+/// fields the type does not know along the way
+pub fn deserialize_front_matter<'de, TDeserializer, TFrontMatter>(
+    deserializer: TDeserializer,
+    unknown_fields_policy: FrontMatterUnknownFieldsPolicy,
+) -> Result<TFrontMatter>
+where
+    TDeserializer: Deserializer<'de>,
+    TDeserializer::Error: Error + Send + Sync + 'static,
+    TFrontMatter: Deserialize<'de>,
+{
+    let mut unknown_fields: Vec<String> = Vec::new();
+    let front_matter = serde_ignored::deserialize(deserializer, |path| {
+        unknown_fields.push(path.to_string());
+    })?;
+
+    match unknown_fields_policy {
+        FrontMatterUnknownFieldsPolicy::Deny if !unknown_fields.is_empty() => Err(anyhow!(
+            "Unknown front matter fields: {}",
+            unknown_fields.join(", ")
+        )),
+        FrontMatterUnknownFieldsPolicy::Deny => Ok(front_matter),
+        FrontMatterUnknownFieldsPolicy::Warn => {
+            for unknown_field in unknown_fields {
+                warn!("Ignoring unknown front matter field '{unknown_field}'");
+            }
+
+            Ok(front_matter)
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 use std::fmt;
 
+#[derive(Debug)]
 pub struct DocumentError {
     pub basename: String,
     pub err: anyhow::Error,
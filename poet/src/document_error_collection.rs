@@ -2,10 +2,12 @@ use std::fmt;
 
 use dashmap::DashMap;
 use itertools::Itertools as _;
+use serde_json::Value;
+use serde_json::json;
 
 use crate::document_error::DocumentError;
 
-#[derive(Default)]
+#[derive(Debug, Default)]
 pub struct DocumentErrorCollection {
     errors: DashMap<String, Vec<DocumentError>>,
 }
@@ -21,6 +23,28 @@ impl DocumentErrorCollection {
             .or_default()
             .push(DocumentError { basename, err });
     }
+
+    /// One `{ name, message }` object per error, ordered by document name
+    pub fn to_json(&self) -> Value {
+        Value::Array(
+            self.errors
+                .iter()
+                .sorted_by(|a, b| Ord::cmp(&a.key(), &b.key()))
+                .flat_map(|errors| {
+                    errors
+                        .value()
+                        .iter()
+                        .map(|DocumentError { basename, err }| {
+                            json!({
+                                "message": format!("{err:#}"),
+                                "name": basename,
+                            })
+                        })
+                        .collect::<Vec<Value>>()
+                })
+                .collect(),
+        )
+    }
 }
 
 impl fmt::Display for DocumentErrorCollection {
@@ -44,3 +68,37 @@ impl fmt::Display for DocumentErrorCollection {
         Ok(())
     }
 }
+
+impl std::error::Error for DocumentErrorCollection {}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn test_errors_serialize_to_json_array() {
+        let error_collection: DocumentErrorCollection = Default::default();
+
+        error_collection.register_error("summarize".to_string(), anyhow!("Missing front matter"));
+        error_collection.register_error(
+            "guides/review".to_string(),
+            anyhow!("Unknown component").context("Failed to render"),
+        );
+
+        assert_eq!(
+            error_collection.to_json(),
+            json!([
+                {
+                    "message": "Failed to render: Unknown component",
+                    "name": "guides/review",
+                },
+                {
+                    "message": "Missing front matter",
+                    "name": "summarize",
+                },
+            ])
+        );
+    }
+}
@@ -0,0 +1,11 @@
+use clap::ValueEnum;
+
+/// Decides how document errors that stop a command are reported
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum DocumentErrorFormat {
+    /// Readable list of errors grouped by document
+    #[default]
+    Human,
+    /// Array of `{ name, message }` objects, for CI tooling
+    Json,
+}
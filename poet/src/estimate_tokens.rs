@@ -0,0 +1,30 @@
+use crate::token_counter::TokenCounter;
+
+/// Token count for budgeting. Empty text never reaches the counter, since
+/// some tokenizers count special tokens even then.
+pub fn estimate_tokens(token_counter: &dyn TokenCounter, text: &str) -> usize {
+    if text.is_empty() {
+        return 0;
+    }
+
+    token_counter.count_tokens(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TokenCounterFixed(usize);
+
+    impl TokenCounter for TokenCounterFixed {
+        fn count_tokens(&self, _text: &str) -> usize {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_configured_counter_is_used() {
+        assert_eq!(estimate_tokens(&TokenCounterFixed(7), "abcd"), 7);
+        assert_eq!(estimate_tokens(&TokenCounterFixed(7), ""), 0);
+    }
+}
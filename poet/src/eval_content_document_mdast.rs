@@ -408,12 +408,9 @@ pub fn eval_content_document_mdast(
         Node::ThematicBreak(ThematicBreak { .. }) => {
             result.push_str("<hr>");
         }
-        Node::Toml(_) => {
+        Node::Toml(_) | Node::Yaml(_) => {
             // ignore frontmatter during this pass
         }
-        Node::Yaml(node) => {
-            warn!("YAML front-matter is not supported, use TOML instead: {node:?}");
-        }
     }
 
     Ok(result)
@@ -1,9 +1,6 @@
 use anyhow::Result;
 use anyhow::anyhow;
 use markdown::mdast::AttributeContent;
-use markdown::mdast::AttributeValue;
-use markdown::mdast::AttributeValueExpression;
-use markdown::mdast::MdxJsxAttribute;
 use markdown::mdast::Node;
 use rhai::CustomType;
 use rhai::Dynamic;
@@ -11,6 +8,8 @@ use rhai_components::component_syntax::tag_name::TagName;
 use rhai_components::escape_html_attribute::escape_html_attribute;
 use rhai_components::rhai_template_renderer::RhaiTemplateRenderer;
 
+use crate::eval_mdx_element_props::eval_mdx_element_props;
+
 pub fn eval_mdx_element<TComponentContext>(
     attributes: &[AttributeContent],
     children: &[Node],
@@ -27,41 +26,10 @@ where
     let tag_name = TagName {
         name: name
             .clone()
-            .ok_or_else(|| anyhow!("MdxJsxFlowElement without a name"))?
-            .into(),
+            .ok_or_else(|| anyhow!("MdxJsxFlowElement without a name"))?,
     };
 
-    let props = {
-        let mut props = rhai::Map::new();
-
-        for attribute in attributes {
-            match attribute {
-                AttributeContent::Expression(_) => {
-                    return Err(anyhow!(
-                        "Attribute expressions in Markdown are not supported"
-                    ));
-                }
-                AttributeContent::Property(MdxJsxAttribute { name, value }) => {
-                    props.insert(
-                        name.into(),
-                        match value {
-                            Some(value) => match value {
-                                AttributeValue::Literal(literal) => literal.into(),
-                                AttributeValue::Expression(AttributeValueExpression {
-                                    value,
-                                    ..
-                                }) => rhai_template_renderer
-                                    .render_expression(component_context.clone(), value)?,
-                            },
-                            None => true.into(),
-                        },
-                    );
-                }
-            }
-        }
-
-        props
-    };
+    let props = eval_mdx_element_props(attributes, component_context, rhai_template_renderer)?;
 
     if tag_name.is_void_element() && !children.is_empty() {
         return Err(anyhow!("Void element cannot have children"));
@@ -0,0 +1,48 @@
+use anyhow::Result;
+use anyhow::anyhow;
+use markdown::mdast::AttributeContent;
+use markdown::mdast::AttributeValue;
+use markdown::mdast::AttributeValueExpression;
+use markdown::mdast::MdxJsxAttribute;
+use rhai::CustomType;
+use rhai::Map;
+use rhai_components::rhai_template_renderer::RhaiTemplateRenderer;
+
+/// Evaluates element attributes into the props a component receives
+pub fn eval_mdx_element_props<TComponentContext>(
+    attributes: &[AttributeContent],
+    component_context: &TComponentContext,
+    rhai_template_renderer: &RhaiTemplateRenderer,
+) -> Result<Map>
+where
+    TComponentContext: CustomType,
+{
+    let mut props = Map::new();
+
+    for attribute in attributes {
+        match attribute {
+            AttributeContent::Expression(_) => {
+                return Err(anyhow!(
+                    "Attribute expressions in Markdown are not supported"
+                ));
+            }
+            AttributeContent::Property(MdxJsxAttribute { name, value }) => {
+                props.insert(
+                    name.into(),
+                    match value {
+                        Some(value) => match value {
+                            AttributeValue::Literal(literal) => literal.into(),
+                            AttributeValue::Expression(AttributeValueExpression {
+                                value, ..
+                            }) => rhai_template_renderer
+                                .render_expression(component_context.clone(), value)?,
+                        },
+                        None => true.into(),
+                    },
+                );
+            }
+        }
+    }
+
+    Ok(props)
+}
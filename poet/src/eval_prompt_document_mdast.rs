@@ -1,6 +1,11 @@
+use anyhow::Context as _;
 use anyhow::Result;
 use anyhow::anyhow;
+use log::trace;
 use log::warn;
+use markdown::mdast::AttributeContent;
+use markdown::mdast::AttributeValue;
+use markdown::mdast::AttributeValueExpression;
 use markdown::mdast::Blockquote;
 use markdown::mdast::Code;
 use markdown::mdast::Delete;
@@ -13,6 +18,7 @@ use markdown::mdast::Link;
 use markdown::mdast::List;
 use markdown::mdast::ListItem;
 use markdown::mdast::MdxFlowExpression;
+use markdown::mdast::MdxJsxAttribute;
 use markdown::mdast::MdxJsxFlowElement;
 use markdown::mdast::MdxJsxTextElement;
 use markdown::mdast::MdxTextExpression;
@@ -25,33 +31,244 @@ use markdown::mdast::TableCell;
 use markdown::mdast::TableRow;
 use markdown::mdast::Text;
 use markdown::mdast::ThematicBreak;
+use rhai::Dynamic;
+use rhai::Map;
+use rhai::serde::from_dynamic;
+use rhai_components::component_syntax::tag_name::TagName;
 use rhai_components::escape_html::escape_html;
 use rhai_components::escape_html_attribute::escape_html_attribute;
+use rhai_components::rhai_template_renderer::RhaiTemplateRenderer;
+use serde_json::Value;
 
+use crate::estimate_tokens::estimate_tokens;
 use crate::eval_mdx_element::eval_mdx_element;
+use crate::eval_mdx_element_props::eval_mdx_element_props;
 use crate::eval_prompt_document_mdast_params::EvalPromptDocumentMdastParams;
+use crate::fence_code_block::fence_code_block;
 use crate::is_external_link::is_external_link;
+use crate::mcp::content_block::ContentBlock;
+use crate::mcp::content_block::image_content::ImageContent;
+use crate::mcp::content_block::resource_link::ResourceLink;
 use crate::prompt_document_component_context::PromptDocumentComponentContext;
+use crate::prompt_image_policy::PromptImagePolicy;
+use crate::prompt_render_cancelled::PromptRenderCancelled;
+use crate::split_base64_data_uri::split_base64_data_uri;
+
+/// Replaces the children of a `<Budget>` that would not fit
+const BUDGET_EXCEEDED_NOTE: &str = "[Omitted to stay within the token budget]";
+
+/// Shortcodes are looked up first, so a component named like a built-in
+/// tag takes its place
+fn is_builtin_tag(
+    name: &Option<String>,
+    builtin_name: &str,
+    rhai_template_renderer: &RhaiTemplateRenderer,
+) -> bool {
+    name.as_deref() == Some(builtin_name) && !rhai_template_renderer.has_template(builtin_name)
+}
 
 fn into_blockquote(input: String) -> String {
     input
+        .trim()
         .lines()
-        .map(|line| format!("> {line}"))
+        .map(|line| {
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {line}")
+            }
+        })
         .collect::<Vec<_>>()
         .join("\n")
 }
 
-fn trim_chunk(chunk: String) -> Result<String> {
-    if chunk.is_empty() {
-        return Ok(chunk);
+fn inline_image(url: &str) -> Result<ImageContent> {
+    let (mime_type, data) = split_base64_data_uri(url).ok_or_else(|| {
+            anyhow!(
+                "Only base64 data URIs can be inlined as image content, use the resource link image policy for: '{url}'"
+            )
+        })?;
+
+    Ok(ImageContent {
+        data: data.to_string(),
+        mime_type: mime_type.to_string(),
+    })
+}
+
+fn link_image(
+    alt: &str,
+    url: &str,
+    title: &Option<String>,
+    prompt_document_component_context: &PromptDocumentComponentContext,
+) -> Result<ResourceLink> {
+    let uri = if is_external_link(url) {
+        url.to_string()
+    } else {
+        match prompt_document_component_context.asset_manager.file(url) {
+            Ok(src) => src,
+            Err(err) => return Err(anyhow!(err)),
+        }
+    };
+
+    Ok(ResourceLink {
+        description: None,
+        mime_type: mime_guess::from_path(&uri)
+            .first()
+            .map(|mime| mime.to_string()),
+        name: if alt.is_empty() {
+            url.to_string()
+        } else {
+            alt.to_string()
+        },
+        title: title.clone(),
+        uri,
+    })
+}
+
+/// Source text of the nodes exactly as it was written in the document
+fn raw_source<'eval>(children: &[Node], source: &'eval str) -> Result<&'eval str> {
+    let (Some(first_child), Some(last_child)) = (children.first(), children.last()) else {
+        return Ok("");
+    };
+
+    match (first_child.position(), last_child.position()) {
+        (Some(start), Some(end)) => source
+            .get(start.start.offset..end.end.offset)
+            .ok_or_else(|| anyhow!("Raw content is out of the document bounds")),
+        _ => Err(anyhow!("Unable to determine the position of raw content")),
     }
+}
 
-    Ok(chunk
-        .trim()
-        .strip_prefix(':')
-        .ok_or_else(|| anyhow!("Unable to strip chunk prefix from '{chunk}'"))?
-        .trim_start()
-        .to_string())
+/// Components return either text, or a map describing a content block that
+/// is embedded into the message
+fn component_output_to_chunk(
+    name: &str,
+    output: Dynamic,
+    prompt_document_component_context: &mut PromptDocumentComponentContext,
+) -> Result<String> {
+    let Some(content) = output.read_lock::<Map>() else {
+        return Ok(output.to_string());
+    };
+
+    let content_type = content
+        .get("type")
+        .ok_or_else(|| anyhow!("Component '{name}' returned content without a type"))?
+        .to_string();
+
+    drop(content);
+
+    if !ContentBlock::TYPE_NAMES.contains(&content_type.as_str()) {
+        return Err(anyhow!(
+            "Component '{name}' returned content of an unknown type '{content_type}', expected one of: {}",
+            ContentBlock::TYPE_NAMES.join(", ")
+        ));
+    }
+
+    let content_block: ContentBlock = from_dynamic(&output)
+        .with_context(|| format!("Component '{name}' returned invalid '{content_type}' content"))?;
+
+    if let ContentBlock::AudioContent(audio_content) = &content_block {
+        audio_content
+            .assert_audio_mime_type()
+            .with_context(|| format!("Component '{name}' returned invalid audio content"))?;
+    }
+
+    Ok(prompt_document_component_context.embed_content_block(content_block))
+}
+
+/// Reads the `key` and `value` attributes of a `<ResultMeta />` component
+fn result_meta_entry(
+    attributes: &[AttributeContent],
+    prompt_document_component_context: &PromptDocumentComponentContext,
+    rhai_template_renderer: &RhaiTemplateRenderer,
+) -> Result<(String, Value)> {
+    let mut key: Option<String> = None;
+    let mut value: Option<Value> = None;
+
+    for attribute in attributes {
+        let AttributeContent::Property(MdxJsxAttribute {
+            name,
+            value: attribute_value,
+        }) = attribute
+        else {
+            return Err(anyhow!("ResultMeta does not support attribute expressions"));
+        };
+
+        let evaluated_value = match attribute_value {
+            Some(AttributeValue::Literal(literal)) => Value::String(literal.clone()),
+            Some(AttributeValue::Expression(AttributeValueExpression {
+                value: expression,
+                ..
+            })) => from_dynamic(
+                &rhai_template_renderer
+                    .render_expression(prompt_document_component_context.clone(), expression)?,
+            )?,
+            None => Value::Bool(true),
+        };
+
+        match name.as_str() {
+            "key" => {
+                key = Some(
+                    evaluated_value
+                        .as_str()
+                        .ok_or_else(|| anyhow!("ResultMeta key has to be a string"))?
+                        .to_string(),
+                );
+            }
+            "value" => value = Some(evaluated_value),
+            _ => return Err(anyhow!("ResultMeta has an unknown attribute: '{name}'")),
+        }
+    }
+
+    Ok((
+        key.ok_or_else(|| anyhow!("ResultMeta requires a key attribute"))?,
+        value.ok_or_else(|| anyhow!("ResultMeta requires a value attribute"))?,
+    ))
+}
+
+/// Reads the `max_tokens` attribute of a `<Budget>` component
+fn budget_max_tokens(
+    attributes: &[AttributeContent],
+    prompt_document_component_context: &PromptDocumentComponentContext,
+    rhai_template_renderer: &RhaiTemplateRenderer,
+) -> Result<usize> {
+    let props = eval_mdx_element_props(
+        attributes,
+        prompt_document_component_context,
+        rhai_template_renderer,
+    )?;
+    let max_tokens = props
+        .get("max_tokens")
+        .ok_or_else(|| anyhow!("Budget requires a max_tokens attribute"))?;
+
+    match max_tokens.as_int() {
+        Ok(max_tokens) => Ok(usize::try_from(max_tokens)?),
+        Err(type_name) => max_tokens.to_string().parse().with_context(|| {
+            format!(
+                "Budget max_tokens has to be a non-negative integer, got '{max_tokens}' of type {type_name}"
+            )
+        }),
+    }
+}
+
+/// Removes the colon that follows a role name (`**user**: message`), so only
+/// the message itself is left
+fn strip_role_marker(paragraph: &str) -> &str {
+    let trimmed_paragraph = paragraph.trim_start();
+
+    match trimmed_paragraph.strip_prefix(':') {
+        Some(message) => message.trim_start(),
+        None => trimmed_paragraph,
+    }
+}
+
+fn is_target_message_complete(
+    target_message_index: Option<usize>,
+    prompt_document_component_context: &PromptDocumentComponentContext,
+) -> bool {
+    target_message_index.is_some_and(|target_message_index| {
+        prompt_document_component_context.prompt_messages_count() > target_message_index
+    })
 }
 
 pub fn eval_prompt_document_children(
@@ -63,6 +280,13 @@ pub fn eval_prompt_document_children(
     let mut is_first_child = true;
 
     for child in children {
+        if is_target_message_complete(
+            params.target_message_index,
+            prompt_document_component_context,
+        ) {
+            break;
+        }
+
         content.push_str(&eval_prompt_document_mdast(
             params.child(child, is_first_child),
             prompt_document_component_context,
@@ -83,26 +307,44 @@ pub fn eval_prompt_document_mdast(
         is_first_child,
         is_in_top_paragraph,
         rhai_template_renderer,
+        source,
+        target_message_index,
     }: EvalPromptDocumentMdastParams,
     prompt_document_component_context: &mut PromptDocumentComponentContext,
 ) -> Result<String> {
+    if prompt_document_component_context
+        .cancellation_token
+        .is_cancelled()
+    {
+        return Err(PromptRenderCancelled {
+            prompt_name: prompt_document_component_context.prompt_name.clone(),
+        }
+        .into());
+    }
+
+    prompt_document_component_context.assert_within_render_deadline()?;
+
     let mut result = String::new();
 
     match mdast {
         Node::Blockquote(Blockquote { children, .. }) => {
+            result.push('\n');
             result.push_str(&into_blockquote(eval_prompt_document_children(
                 children,
                 params.regular_element(),
                 prompt_document_component_context,
             )?));
+            result.push('\n');
         }
         Node::Break(_) => {
             result.push_str("  \n");
         }
         Node::Code(Code { lang, value, .. }) => {
-            result.push_str(&format!("```{}\n", lang.clone().unwrap_or("".to_string())));
-            result.push_str(&escape_html(value));
-            result.push_str("\n```");
+            result.push_str(&fence_code_block(
+                &escape_html(value),
+                lang.as_deref().unwrap_or_default(),
+                '`',
+            ));
         }
         Node::Definition(node) => {
             warn!("Definitions are not supported: {node:?}");
@@ -144,6 +386,52 @@ pub fn eval_prompt_document_mdast(
         Node::Html(Html { value, .. }) => {
             result.push_str(value);
         }
+        Node::Image(Image {
+            alt, url, title, ..
+        }) if prompt_document_component_context.image_policy == PromptImagePolicy::Inline => {
+            let content_block = if url.starts_with("data:") {
+                ContentBlock::ImageContent(inline_image(url)?)
+            } else if is_external_link(url) {
+                if !prompt_document_component_context.allow_remote_images {
+                    return Err(anyhow!(
+                        "Remote image '{url}' cannot be inlined, allow remote images to pass it through as a resource link"
+                    ));
+                }
+
+                ContentBlock::ResourceLink(link_image(
+                    alt,
+                    url,
+                    title,
+                    prompt_document_component_context,
+                )?)
+            } else {
+                match prompt_document_component_context.inline_images.get(url) {
+                    Some(image_content) => ContentBlock::ImageContent(image_content.clone()),
+                    // Images that were not read while building, like the ones
+                    // that appear after a rebuild, are linked instead
+                    None => ContentBlock::ResourceLink(link_image(
+                        alt,
+                        url,
+                        title,
+                        prompt_document_component_context,
+                    )?),
+                }
+            };
+
+            result.push_str(&prompt_document_component_context.embed_content_block(content_block));
+        }
+        Node::Image(Image {
+            alt, url, title, ..
+        }) if prompt_document_component_context.image_policy == PromptImagePolicy::ResourceLink => {
+            let content_block = ContentBlock::ResourceLink(link_image(
+                alt,
+                url,
+                title,
+                prompt_document_component_context,
+            )?);
+
+            result.push_str(&prompt_document_component_context.embed_content_block(content_block));
+        }
         Node::Image(Image {
             alt, url, title, ..
         }) => {
@@ -246,6 +534,144 @@ pub fn eval_prompt_document_mdast(
                     .to_string(),
             );
         }
+        Node::MdxJsxFlowElement(MdxJsxFlowElement { name, .. })
+        | Node::MdxJsxTextElement(MdxJsxTextElement { name, .. })
+            if is_builtin_tag(name, "Break", rhai_template_renderer) =>
+        {
+            prompt_document_component_context.break_message()?;
+        }
+        Node::MdxJsxFlowElement(MdxJsxFlowElement {
+            attributes, name, ..
+        })
+        | Node::MdxJsxTextElement(MdxJsxTextElement {
+            attributes, name, ..
+        }) if is_builtin_tag(name, "ResultMeta", rhai_template_renderer) => {
+            let (key, value) = result_meta_entry(
+                attributes,
+                prompt_document_component_context,
+                rhai_template_renderer,
+            )?;
+
+            prompt_document_component_context
+                .result_meta
+                .insert(key, value);
+        }
+        Node::MdxJsxFlowElement(MdxJsxFlowElement {
+            attributes,
+            children,
+            name,
+            ..
+        })
+        | Node::MdxJsxTextElement(MdxJsxTextElement {
+            attributes,
+            children,
+            name,
+            ..
+        }) if is_builtin_tag(name, "Budget", rhai_template_renderer) => {
+            let max_tokens = budget_max_tokens(
+                attributes,
+                prompt_document_component_context,
+                rhai_template_renderer,
+            )?;
+            let checkpoint = prompt_document_component_context.checkpoint();
+            let evaluated_children = eval_prompt_document_children(
+                children,
+                params.regular_element(),
+                prompt_document_component_context,
+            )?;
+            let running_tokens = prompt_document_component_context.estimate_rendered_tokens()
+                + estimate_tokens(
+                    prompt_document_component_context.token_counter.as_ref(),
+                    &evaluated_children,
+                );
+
+            if running_tokens <= max_tokens {
+                result.push_str(&evaluated_children);
+            } else {
+                prompt_document_component_context.restore_checkpoint(checkpoint);
+                result.push_str(BUDGET_EXCEEDED_NOTE);
+            }
+        }
+        Node::MdxJsxFlowElement(MdxJsxFlowElement { children, name, .. })
+        | Node::MdxJsxTextElement(MdxJsxTextElement { children, name, .. })
+            if is_builtin_tag(name, "Comment", rhai_template_renderer) =>
+        {
+            // Children are evaluated only so that broken references surface,
+            // then role switches, messages and result meta are undone
+            let checkpoint = prompt_document_component_context.checkpoint();
+
+            eval_prompt_document_children(
+                children,
+                params.regular_element(),
+                prompt_document_component_context,
+            )?;
+            prompt_document_component_context.restore_checkpoint(checkpoint);
+        }
+        Node::MdxJsxFlowElement(MdxJsxFlowElement {
+            attributes, name, ..
+        })
+        | Node::MdxJsxTextElement(MdxJsxTextElement {
+            attributes, name, ..
+        }) if is_builtin_tag(name, "Fetch", rhai_template_renderer) => {
+            let url = eval_mdx_element_props(
+                attributes,
+                prompt_document_component_context,
+                rhai_template_renderer,
+            )?
+            .get("url")
+            .ok_or_else(|| anyhow!("Fetch requires a url attribute"))?
+            .to_string();
+            let fetched_content = prompt_document_component_context
+                .fetched_contents
+                .get(&url)
+                .ok_or_else(|| anyhow!("Content of '{url}' was not fetched before rendering"))?;
+
+            result.push_str(fetched_content);
+        }
+        Node::MdxJsxFlowElement(MdxJsxFlowElement { children, name, .. })
+        | Node::MdxJsxTextElement(MdxJsxTextElement { children, name, .. })
+            if is_builtin_tag(name, "Raw", rhai_template_renderer) =>
+        {
+            result.push_str(raw_source(children, source)?);
+        }
+        Node::MdxJsxFlowElement(MdxJsxFlowElement {
+            attributes,
+            children,
+            name: Some(name),
+            ..
+        })
+        | Node::MdxJsxTextElement(MdxJsxTextElement {
+            attributes,
+            children,
+            name: Some(name),
+            ..
+        }) if TagName { name: name.clone() }.is_component() => {
+            let evaluated_children = eval_prompt_document_children(
+                children,
+                params.regular_element(),
+                prompt_document_component_context,
+            )?;
+            let props = eval_mdx_element_props(
+                attributes,
+                prompt_document_component_context,
+                rhai_template_renderer,
+            )?;
+
+            trace!("Calling component '{name}' with props: {props:?}");
+
+            let output = rhai_template_renderer.render_dynamic(
+                name,
+                prompt_document_component_context.clone(),
+                Dynamic::from_map(props),
+                Dynamic::from(evaluated_children),
+            )?;
+
+            result.push_str(&component_output_to_chunk(
+                name,
+                output,
+                prompt_document_component_context,
+            )?);
+        }
         Node::MdxJsxFlowElement(MdxJsxFlowElement {
             attributes,
             children,
@@ -274,12 +700,21 @@ pub fn eval_prompt_document_mdast(
             )?);
         }
         Node::Paragraph(Paragraph { children, .. }) => {
-            result.push('\n');
-            result.push_str(&eval_prompt_document_children(
+            let paragraph = eval_prompt_document_children(
                 children,
                 params.paragraph(),
                 prompt_document_component_context,
-            )?);
+            )?;
+
+            result.push('\n');
+
+            // A leading strong element in a top paragraph switches the role
+            if is_directly_in_root && matches!(children.first(), Some(Node::Strong(_))) {
+                result.push_str(strip_role_marker(&paragraph));
+            } else {
+                result.push_str(&paragraph);
+            }
+
             result.push('\n');
         }
         Node::Root(Root { children, .. }) => {
@@ -289,7 +724,15 @@ pub fn eval_prompt_document_mdast(
                 prompt_document_component_context,
             )?);
 
-            prompt_document_component_context.flush()?;
+            if !is_target_message_complete(target_message_index, prompt_document_component_context)
+            {
+                prompt_document_component_context.flush()?;
+            }
+
+            if let Some(target_message_index) = target_message_index {
+                prompt_document_component_context
+                    .truncate_prompt_messages(target_message_index + 1);
+            }
         }
         Node::Strong(Strong { children, .. }) => {
             let potential_role_name: &str = &eval_prompt_document_children(
@@ -336,16 +779,13 @@ pub fn eval_prompt_document_mdast(
         Node::ThematicBreak(ThematicBreak { .. }) => {
             result.push_str("---");
         }
-        Node::Toml(_) => {
+        Node::Toml(_) | Node::Yaml(_) => {
             // ignore frontmatter during this pass
         }
-        Node::Yaml(node) => {
-            warn!("YAML front-matter is not supported, use TOML instead: {node:?}");
-        }
     }
 
     if is_directly_in_root {
-        prompt_document_component_context.append_to_message(trim_chunk(result.clone())?)?;
+        prompt_document_component_context.append_block_to_message(result.trim().to_string())?;
     }
 
     Ok(result)
@@ -364,24 +804,20 @@ mod test {
     }
 
     #[test]
-    fn test_chunk_trim() -> Result<()> {
+    fn test_nested_blockquotes() {
         assert_eq!(
-            trim_chunk(
-                r#"
-                : foo bar
-            "#
-                .to_string()
-            )?,
-            "foo bar".to_string(),
+            into_blockquote("\nfoo\nbar\n\n> baz\n".to_string()),
+            "> foo\n> bar\n>\n> > baz".to_string()
         );
-
-        Ok(())
     }
 
     #[test]
-    fn test_chunk_trim_empty() -> Result<()> {
-        assert_eq!(trim_chunk("".to_string())?, "".to_string(),);
+    fn test_strip_role_marker() {
+        assert_eq!(strip_role_marker(": foo bar"), "foo bar");
+    }
 
-        Ok(())
+    #[test]
+    fn test_strip_role_marker_without_colon() {
+        assert_eq!(strip_role_marker(" foo bar"), "foo bar");
     }
 }
@@ -8,6 +8,10 @@ pub struct EvalPromptDocumentMdastParams<'eval> {
     pub is_first_child: bool,
     pub is_in_top_paragraph: bool,
     pub rhai_template_renderer: &'eval RhaiTemplateRenderer,
+    /// Document contents the mdast was parsed from
+    pub source: &'eval str,
+    /// Rendering stops once the message with this index is complete
+    pub target_message_index: Option<usize>,
 }
 
 impl<'eval> EvalPromptDocumentMdastParams<'eval> {
@@ -18,6 +22,8 @@ impl<'eval> EvalPromptDocumentMdastParams<'eval> {
             is_first_child,
             is_in_top_paragraph: self.is_in_top_paragraph,
             rhai_template_renderer: self.rhai_template_renderer,
+            source: self.source,
+            target_message_index: self.target_message_index,
         }
     }
 
@@ -28,6 +34,8 @@ impl<'eval> EvalPromptDocumentMdastParams<'eval> {
             is_first_child: self.is_first_child,
             is_in_top_paragraph: false,
             rhai_template_renderer: self.rhai_template_renderer,
+            source: self.source,
+            target_message_index: self.target_message_index,
         }
     }
 
@@ -38,6 +46,8 @@ impl<'eval> EvalPromptDocumentMdastParams<'eval> {
             is_first_child: self.is_first_child,
             is_in_top_paragraph: self.is_directly_in_root,
             rhai_template_renderer: self.rhai_template_renderer,
+            source: self.source,
+            target_message_index: self.target_message_index,
         }
     }
 
@@ -48,6 +58,8 @@ impl<'eval> EvalPromptDocumentMdastParams<'eval> {
             is_first_child: false,
             is_in_top_paragraph: false,
             rhai_template_renderer: self.rhai_template_renderer,
+            source: self.source,
+            target_message_index: self.target_message_index,
         }
     }
 }
@@ -0,0 +1,36 @@
+/// Wraps content in a fenced code block, with a fence longer than any run of
+/// the fence character inside the content, so the content cannot close the
+/// block early
+pub fn fence_code_block(content: &str, info_string: &str, fence_character: char) -> String {
+    let longest_fence_character_run = content
+        .split(|character| character != fence_character)
+        .map(|run| run.chars().count())
+        .max()
+        .unwrap_or(0);
+    let fence = fence_character
+        .to_string()
+        .repeat(longest_fence_character_run.max(2) + 1);
+
+    format!("{fence}{info_string}\n{content}\n{fence}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fence_outlasts_fence_characters_in_content() {
+        assert_eq!(
+            fence_code_block("let a = 1;", "rust", '`'),
+            "```rust\nlet a = 1;\n```"
+        );
+        assert_eq!(
+            fence_code_block("Use ```js fences``` here", "markdown", '`'),
+            "````markdown\nUse ```js fences``` here\n````"
+        );
+        assert_eq!(
+            fence_code_block("~~~~\n```", "", '~'),
+            "~~~~~\n~~~~\n```\n~~~~~"
+        );
+    }
+}
@@ -63,6 +63,7 @@ impl TryFrom<FileEntryStub> for FileEntry {
                 ("authors", "toml") => FileEntryKind::Author,
                 ("content", "md") => FileEntryKind::Content,
                 ("prompts", "md") => FileEntryKind::Prompt,
+                ("prompt_collections", "toml") => FileEntryKind::PromptCollection,
                 ("shortcodes", "rhai") => FileEntryKind::Shortcode,
                 _ => FileEntryKind::Other,
             },
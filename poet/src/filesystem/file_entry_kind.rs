@@ -4,6 +4,7 @@ pub enum FileEntryKind {
     Content,
     Other,
     Prompt,
+    PromptCollection,
     Shortcode,
 }
 
@@ -20,6 +21,10 @@ impl FileEntryKind {
         *self == Self::Prompt
     }
 
+    pub fn is_prompt_collection(&self) -> bool {
+        *self == Self::PromptCollection
+    }
+
     pub fn is_shortcode(&self) -> bool {
         *self == Self::Shortcode
     }
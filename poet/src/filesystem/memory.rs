@@ -51,6 +51,17 @@ impl Filesystem for Memory {
         }
     }
 
+    async fn read_file_bytes(&self, relative_path: &Path) -> Result<Vec<u8>> {
+        let path_str = relative_path
+            .to_str()
+            .ok_or_else(|| anyhow!("Unable to stringify path"))?;
+
+        match self.files.get(path_str) {
+            Some(contents) => Ok(contents.value().as_bytes().to_vec()),
+            None => Err(anyhow!("File not found: '{path_str}'")),
+        }
+    }
+
     async fn set_file_contents(&self, path: &Path, contents: &str) -> Result<()> {
         self.set_file_contents_sync(path, contents)
     }
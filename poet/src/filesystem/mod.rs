@@ -21,6 +21,9 @@ pub trait Filesystem: Send + Sync {
 
     async fn read_file_contents(&self, path: &Path) -> Result<ReadFileContentsResult>;
 
+    /// Reads the file without decoding it, for binary files like images
+    async fn read_file_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+
     async fn set_file_contents(&self, path: &Path, contents: &str) -> Result<()>;
 
     fn set_file_contents_sync(&self, path: &Path, contents: &str) -> Result<()>;
@@ -196,6 +199,8 @@ mod tests {
                 "Storage",
                 storage::Storage {
                     base_directory: base_directory.path().to_path_buf(),
+                    max_file_bytes: storage::DEFAULT_MAX_FILE_BYTES,
+                    source_encodings: Default::default(),
                 },
             )
             .await?;
@@ -1,12 +1,15 @@
 pub mod create_parent_directories;
+pub mod source_encodings;
 
 use std::path::Path;
 use std::path::PathBuf;
 
 use anyhow::Context as _;
 use anyhow::Result;
+use anyhow::anyhow;
 use async_trait::async_trait;
 use log::debug;
+use log::warn;
 use tokio::fs;
 
 use super::Filesystem;
@@ -14,9 +17,34 @@ use super::file_entry::FileEntry;
 use super::read_file_contents_result::ReadFileContentsResult;
 use crate::filesystem::file_entry_stub::FileEntryStub;
 use crate::filesystem::storage::create_parent_directories::create_parent_directories;
+use crate::filesystem::storage::source_encodings::SourceEncodings;
+
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
 
 pub struct Storage {
     pub base_directory: PathBuf,
+    /// Project files larger than this are skipped with a warning
+    pub max_file_bytes: u64,
+    pub source_encodings: SourceEncodings,
+}
+
+impl Storage {
+    async fn read_to_utf8_string(&self, path: &Path) -> Result<String> {
+        let bytes = fs::read(path)
+            .await
+            .context(format!("Failed to read file: {}", path.display()))?;
+        let (contents, encoding, had_errors) = self.source_encodings.for_path(path).decode(&bytes);
+
+        if had_errors {
+            return Err(anyhow!(
+                "File '{}' is not valid {} and cannot be transcoded to UTF-8",
+                path.display(),
+                encoding.name()
+            ));
+        }
+
+        Ok(contents.into_owned())
+    }
 }
 
 #[async_trait]
@@ -25,6 +53,7 @@ impl Filesystem for Storage {
         let mut to_visit: Vec<PathBuf> = vec![
             self.base_directory.join("authors"),
             self.base_directory.join("content"),
+            self.base_directory.join("prompt_collections"),
             self.base_directory.join("prompts"),
             self.base_directory.join("shortcodes"),
         ];
@@ -48,12 +77,20 @@ impl Filesystem for Storage {
 
                     if let Some(extension) = path.extension() {
                         match extension.to_str() {
+                            Some("md") | Some("rhai") | Some("toml")
+                                if metadata.len() > self.max_file_bytes =>
+                            {
+                                warn!(
+                                    "Skipping file '{}': it is {} bytes, which exceeds the limit of {} bytes",
+                                    relative_path.display(),
+                                    metadata.len(),
+                                    self.max_file_bytes
+                                );
+                            }
                             Some("md") | Some("rhai") | Some("toml") => {
                                 files.push(
                                     FileEntryStub {
-                                        contents: fs::read_to_string(&path).await.context(
-                                            format!("Failed to read file: {}", path.display()),
-                                        )?,
+                                        contents: self.read_to_utf8_string(&path).await?,
                                         relative_path,
                                     }
                                     .try_into()?,
@@ -81,11 +118,31 @@ impl Filesystem for Storage {
             return Ok(ReadFileContentsResult::Directory);
         }
 
-        let contents = fs::read_to_string(&full_path).await?;
+        let contents = self.read_to_utf8_string(&full_path).await?;
 
         Ok(ReadFileContentsResult::Found { contents })
     }
 
+    async fn read_file_bytes(&self, relative_path: &Path) -> Result<Vec<u8>> {
+        let full_path = self.base_directory.join(relative_path);
+        let metadata = fs::metadata(&full_path)
+            .await
+            .context(format!("Failed to read file: {}", full_path.display()))?;
+
+        if metadata.len() > self.max_file_bytes {
+            return Err(anyhow!(
+                "File '{}' is {} bytes, which exceeds the limit of {} bytes",
+                relative_path.display(),
+                metadata.len(),
+                self.max_file_bytes
+            ));
+        }
+
+        fs::read(&full_path)
+            .await
+            .context(format!("Failed to read file: {}", full_path.display()))
+    }
+
     async fn set_file_contents(&self, path: &Path, contents: &str) -> Result<()> {
         let full_path = self.base_directory.join(path);
 
@@ -102,3 +159,81 @@ impl Filesystem for Storage {
         unreachable!("This should not be used with storage filesystem")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::WINDOWS_1252;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_oversized_file_is_skipped() -> Result<()> {
+        let base_directory = tempdir()?;
+        let storage = Storage {
+            base_directory: base_directory.path().to_path_buf(),
+            max_file_bytes: 8,
+            source_encodings: Default::default(),
+        };
+
+        storage
+            .set_file_contents(Path::new("prompts/small.md"), "tiny")
+            .await?;
+        storage
+            .set_file_contents(Path::new("prompts/large.md"), "far too large")
+            .await?;
+
+        let files = storage.read_project_files().await?;
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].relative_path, PathBuf::from("prompts/small.md"));
+
+        Ok(())
+    }
+
+    async fn write_latin1_prompt(base_directory: &Path) -> Result<()> {
+        let path = base_directory.join("prompts/cafe.md");
+
+        create_parent_directories(&path).await?;
+        fs::write(&path, b"Caf\xe9 cr\xe8me").await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_latin1_file_is_transcoded() -> Result<()> {
+        let base_directory = tempdir()?;
+
+        write_latin1_prompt(base_directory.path()).await?;
+
+        let storage = Storage {
+            base_directory: base_directory.path().to_path_buf(),
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            source_encodings: SourceEncodings {
+                by_extension: [("md".to_string(), WINDOWS_1252)].into(),
+                ..Default::default()
+            },
+        };
+
+        let files = storage.read_project_files().await?;
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].contents, "Caf\u{e9} cr\u{e8}me");
+
+        let strict_storage = Storage {
+            source_encodings: Default::default(),
+            ..storage
+        };
+
+        let Err(err) = strict_storage.read_project_files().await else {
+            return Err(anyhow!("Expected the Latin-1 file to be rejected as UTF-8"));
+        };
+
+        assert!(
+            err.to_string()
+                .contains("is not valid UTF-8 and cannot be transcoded to UTF-8")
+        );
+
+        Ok(())
+    }
+}
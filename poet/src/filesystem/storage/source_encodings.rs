@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use encoding_rs::Encoding;
+use encoding_rs::UTF_8;
+
+/// Encodings project files are transcoded from into UTF-8, unless they start
+/// with a byte order mark of another encoding
+#[derive(Clone)]
+pub struct SourceEncodings {
+    /// Keyed by file extension, without the leading dot
+    pub by_extension: HashMap<String, &'static Encoding>,
+    pub default: &'static Encoding,
+}
+
+impl SourceEncodings {
+    pub fn for_path(&self, path: &Path) -> &'static Encoding {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| self.by_extension.get(extension))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+impl Default for SourceEncodings {
+    fn default() -> Self {
+        Self {
+            by_extension: Default::default(),
+            default: UTF_8,
+        }
+    }
+}
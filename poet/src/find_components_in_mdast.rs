@@ -0,0 +1,36 @@
+use std::collections::BTreeSet;
+
+use markdown::mdast::MdxJsxFlowElement;
+use markdown::mdast::MdxJsxTextElement;
+use markdown::mdast::Node;
+use rhai_components::component_syntax::tag_name::TagName;
+
+fn collect_in_node(mdast: &Node, components: &mut BTreeSet<String>) {
+    match mdast {
+        Node::MdxJsxFlowElement(MdxJsxFlowElement {
+            name: Some(name), ..
+        })
+        | Node::MdxJsxTextElement(MdxJsxTextElement {
+            name: Some(name), ..
+        }) if TagName { name: name.clone() }.is_component() => {
+            components.insert(name.clone());
+        }
+        _ => {}
+    }
+
+    if let Some(children) = mdast.children() {
+        for child in children {
+            collect_in_node(child, components);
+        }
+    }
+}
+
+/// Names of the components used anywhere in the document, including the
+/// built-in ones and those nested inside other components
+pub fn find_components_in_mdast(mdast: &Node) -> BTreeSet<String> {
+    let mut components = BTreeSet::new();
+
+    collect_in_node(mdast, &mut components);
+
+    components
+}
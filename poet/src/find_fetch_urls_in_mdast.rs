@@ -0,0 +1,53 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use markdown::mdast::AttributeContent;
+use markdown::mdast::AttributeValue;
+use markdown::mdast::MdxJsxAttribute;
+use markdown::mdast::MdxJsxFlowElement;
+use markdown::mdast::MdxJsxTextElement;
+use markdown::mdast::Node;
+
+fn collect_in_node(mdast: &Node, fetch_urls: &mut BTreeSet<String>) -> Result<()> {
+    match mdast {
+        Node::MdxJsxFlowElement(MdxJsxFlowElement {
+            attributes, name, ..
+        })
+        | Node::MdxJsxTextElement(MdxJsxTextElement {
+            attributes, name, ..
+        }) if name.as_deref() == Some("Fetch") => {
+            let url = attributes
+                .iter()
+                .find_map(|attribute| match attribute {
+                    AttributeContent::Property(MdxJsxAttribute {
+                        name,
+                        value: Some(AttributeValue::Literal(url)),
+                    }) if name == "url" => Some(url.clone()),
+                    _ => None,
+                })
+                .ok_or_else(|| anyhow!("Fetch requires a literal url attribute"))?;
+
+            fetch_urls.insert(url);
+        }
+        _ => {}
+    }
+
+    if let Some(children) = mdast.children() {
+        for child in children {
+            collect_in_node(child, fetch_urls)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetches happen before the document is evaluated, so their URLs have to be
+/// known without evaluating any expressions
+pub fn find_fetch_urls_in_mdast(mdast: &Node) -> Result<BTreeSet<String>> {
+    let mut fetch_urls = BTreeSet::new();
+
+    collect_in_node(mdast, &mut fetch_urls)?;
+
+    Ok(fetch_urls)
+}
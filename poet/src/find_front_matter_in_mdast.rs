@@ -2,22 +2,37 @@ use anyhow::Result;
 use markdown::mdast::Node;
 use markdown::mdast::Root;
 use markdown::mdast::Toml;
+use markdown::mdast::Yaml;
 use serde::de::DeserializeOwned;
 
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+use crate::parse_front_matter::parse_front_matter;
+use crate::parse_yaml_front_matter::parse_yaml_front_matter;
+
 pub fn find_front_matter_in_mdast<TFrontMatter: DeserializeOwned>(
     mdast: &Node,
+    unknown_fields_policy: FrontMatterUnknownFieldsPolicy,
 ) -> Result<Option<TFrontMatter>> {
     match mdast {
         Node::Root(Root { children, .. }) => {
             for child in children {
-                if let Some(front_matter) = find_front_matter_in_mdast::<TFrontMatter>(child)? {
+                if let Some(front_matter) =
+                    find_front_matter_in_mdast::<TFrontMatter>(child, unknown_fields_policy)?
+                {
                     return Ok(Some(front_matter));
                 }
             }
 
             Ok(None)
         }
-        Node::Toml(Toml { value, .. }) => Ok(Some(toml::from_str::<TFrontMatter>(value)?)),
+        Node::Toml(Toml { value, .. }) => Ok(Some(parse_front_matter::<TFrontMatter>(
+            value,
+            unknown_fields_policy,
+        )?)),
+        Node::Yaml(Yaml { value, .. }) => Ok(Some(parse_yaml_front_matter::<TFrontMatter>(
+            value,
+            unknown_fields_policy,
+        )?)),
         _ => Ok(None),
     }
 }
@@ -0,0 +1,31 @@
+use std::collections::BTreeSet;
+
+use markdown::mdast::Image;
+use markdown::mdast::Node;
+
+use crate::is_external_link::is_external_link;
+
+fn collect_in_node(mdast: &Node, image_urls: &mut BTreeSet<String>) {
+    if let Node::Image(Image { url, .. }) = mdast
+        && !url.starts_with("data:")
+        && !is_external_link(url)
+    {
+        image_urls.insert(url.clone());
+    }
+
+    if let Some(children) = mdast.children() {
+        for child in children {
+            collect_in_node(child, image_urls);
+        }
+    }
+}
+
+/// Images have to be read from the project files before the document is
+/// evaluated, because evaluation cannot wait on the filesystem
+pub fn find_local_image_urls_in_mdast(mdast: &Node) -> BTreeSet<String> {
+    let mut image_urls = BTreeSet::new();
+
+    collect_in_node(mdast, &mut image_urls);
+
+    image_urls
+}
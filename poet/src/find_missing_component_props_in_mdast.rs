@@ -0,0 +1,68 @@
+use markdown::mdast::AttributeContent;
+use markdown::mdast::MdxJsxAttribute;
+use markdown::mdast::MdxJsxFlowElement;
+use markdown::mdast::MdxJsxTextElement;
+use markdown::mdast::Node;
+use rhai_components::component_syntax::component_prop::ComponentProp;
+use rhai_components::component_syntax::component_reference::ComponentReference;
+use rhai_components::rhai_template_renderer::RhaiTemplateRenderer;
+
+fn collect_in_node(
+    mdast: &Node,
+    rhai_template_renderer: &RhaiTemplateRenderer,
+    missing_props: &mut Vec<(String, String)>,
+) {
+    if let Node::MdxJsxFlowElement(MdxJsxFlowElement {
+        attributes,
+        name: Some(name),
+        ..
+    })
+    | Node::MdxJsxTextElement(MdxJsxTextElement {
+        attributes,
+        name: Some(name),
+        ..
+    }) = mdast
+        && let Some(ComponentReference { props, .. }) =
+            rhai_template_renderer.get_component_reference(name)
+    {
+        for ComponentProp {
+            name: prop_name,
+            required,
+            ..
+        } in props
+        {
+            // Literal and expression values both count, the latter is
+            // assumed to produce something at render time
+            let is_set = attributes.iter().any(|attribute| match attribute {
+                AttributeContent::Expression(_) => true,
+                AttributeContent::Property(MdxJsxAttribute {
+                    name: attribute_name,
+                    ..
+                }) => *attribute_name == prop_name,
+            });
+
+            if required && !is_set {
+                missing_props.push((name.clone(), prop_name));
+            }
+        }
+    }
+
+    if let Some(children) = mdast.children() {
+        for child in children {
+            collect_in_node(child, rhai_template_renderer, missing_props);
+        }
+    }
+}
+
+/// Pairs of component and prop name, for every component usage that does
+/// not set one of the props its schema requires
+pub fn find_missing_component_props_in_mdast(
+    mdast: &Node,
+    rhai_template_renderer: &RhaiTemplateRenderer,
+) -> Vec<(String, String)> {
+    let mut missing_props = Vec::new();
+
+    collect_in_node(mdast, rhai_template_renderer, &mut missing_props);
+
+    missing_props
+}
@@ -0,0 +1,145 @@
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+
+use markdown::mdast::AttributeContent;
+use markdown::mdast::AttributeValue;
+use markdown::mdast::AttributeValueExpression;
+use markdown::mdast::MdxFlowExpression;
+use markdown::mdast::MdxJsxAttribute;
+use markdown::mdast::MdxJsxFlowElement;
+use markdown::mdast::MdxJsxTextElement;
+use markdown::mdast::MdxTextExpression;
+use markdown::mdast::Node;
+
+fn is_identifier_character(character: char) -> bool {
+    character.is_alphanumeric() || character == '_'
+}
+
+fn collect_unknown_references(
+    expression: &str,
+    context_path: &str,
+    known_fields: &HashSet<String>,
+    unknown_references: &mut BTreeSet<String>,
+) {
+    let prefix = format!("{context_path}.");
+
+    for (offset, _) in expression.match_indices(&prefix) {
+        let is_part_of_longer_path = expression[..offset]
+            .chars()
+            .next_back()
+            .is_some_and(|character| is_identifier_character(character) || character == '.');
+
+        if is_part_of_longer_path {
+            continue;
+        }
+
+        let field: String = expression[offset + prefix.len()..]
+            .chars()
+            .take_while(|character| is_identifier_character(*character))
+            .collect();
+
+        if !field.is_empty() && !known_fields.contains(&field) {
+            unknown_references.insert(format!("{prefix}{field}"));
+        }
+    }
+}
+
+fn collect_in_node(
+    mdast: &Node,
+    context_path: &str,
+    known_fields: &HashSet<String>,
+    unknown_references: &mut BTreeSet<String>,
+) {
+    match mdast {
+        Node::MdxFlowExpression(MdxFlowExpression { value, .. })
+        | Node::MdxTextExpression(MdxTextExpression { value, .. }) => {
+            collect_unknown_references(value, context_path, known_fields, unknown_references);
+        }
+        Node::MdxJsxFlowElement(MdxJsxFlowElement { attributes, .. })
+        | Node::MdxJsxTextElement(MdxJsxTextElement { attributes, .. }) => {
+            for attribute in attributes {
+                if let AttributeContent::Property(MdxJsxAttribute {
+                    value: Some(AttributeValue::Expression(AttributeValueExpression { value, .. })),
+                    ..
+                }) = attribute
+                {
+                    collect_unknown_references(
+                        value,
+                        context_path,
+                        known_fields,
+                        unknown_references,
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(children) = mdast.children() {
+        for child in children {
+            collect_in_node(child, context_path, known_fields, unknown_references);
+        }
+    }
+}
+
+/// Best-effort static check for expressions that reach into a context map
+/// (like `context.arguments.name`) for a field that is not declared. Dynamic
+/// indexing (`context.arguments[name]`) cannot be checked, so it is skipped.
+pub fn find_unknown_context_references_in_mdast(
+    mdast: &Node,
+    context_path: &str,
+    known_fields: &HashSet<String>,
+) -> BTreeSet<String> {
+    let mut unknown_references = BTreeSet::new();
+
+    collect_in_node(mdast, context_path, known_fields, &mut unknown_references);
+
+    unknown_references
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use indoc::indoc;
+
+    use super::*;
+    use crate::string_to_mdast::string_to_mdast;
+
+    #[test]
+    fn test_reference_to_missing_prop_is_flagged() -> Result<()> {
+        let mdast = string_to_mdast(indoc! {r#"
+            +++
+            description = "Props"
+            layout = "LayoutMinimal"
+            title = "Props"
+
+            [props]
+            color = "red"
+            +++
+
+            Color: {context.front_matter.props.color}
+
+            Size: {context.front_matter.props.size}
+
+            <Badge label={context.front_matter.props.labal} />
+
+            Dynamic: {context.front_matter.props["whatever"]}
+        "#})?;
+
+        let unknown_references = find_unknown_context_references_in_mdast(
+            &mdast,
+            "context.front_matter.props",
+            &HashSet::from(["color".to_string()]),
+        );
+
+        assert_eq!(
+            unknown_references.into_iter().collect::<Vec<_>>(),
+            vec![
+                "context.front_matter.props.labal",
+                "context.front_matter.props.size",
+            ]
+        );
+
+        Ok(())
+    }
+}
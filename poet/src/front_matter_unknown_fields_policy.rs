@@ -0,0 +1,12 @@
+use clap::ValueEnum;
+
+/// Decides what happens with front matter fields poet does not know, for
+/// example ones added by other tooling during a migration
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum FrontMatterUnknownFieldsPolicy {
+    /// Fail parsing, which catches typos
+    #[default]
+    Deny,
+    /// Log a warning and ignore the field
+    Warn,
+}
@@ -2,6 +2,7 @@ pub mod anyhow_error_aggregate;
 pub mod app_dir_desktop_entry;
 pub mod assert_valid_desktop_entry_string;
 pub mod asset_manager;
+pub mod asset_path_profiles;
 pub mod asset_path_renderer;
 pub mod author;
 pub mod author_basename;
@@ -16,11 +17,15 @@ pub mod build_prompt_document_controller_params;
 pub mod build_timer;
 pub mod cmd;
 pub mod compile_shortcodes;
+pub mod component_schema;
+pub mod conform_prop_override;
 pub mod content_document;
 pub mod content_document_basename;
+pub mod content_document_basename_by_id;
 pub mod content_document_collection;
 pub mod content_document_collection_ranked;
 pub mod content_document_component_context;
+pub mod content_document_content_by_basename;
 pub mod content_document_front_matter;
 pub mod content_document_hierarchy;
 pub mod content_document_in_collection;
@@ -29,35 +34,76 @@ pub mod content_document_reference;
 pub mod content_document_source;
 pub mod content_document_tree_node;
 pub mod copy_esbuild_metafile_assets_to;
+pub mod deserialize_front_matter;
 pub mod document_error;
 pub mod document_error_collection;
+pub mod document_error_format;
 pub mod esbuild_metafile_holder;
+pub mod estimate_tokens;
 pub mod eval_content_document_mdast;
 pub mod eval_mdx_element;
+pub mod eval_mdx_element_props;
 pub mod eval_prompt_document_mdast;
 pub mod eval_prompt_document_mdast_params;
 pub mod external_asset;
+pub mod fence_code_block;
 pub mod filesystem;
 pub mod filesystem_http_route_index;
 pub mod filesystem_http_route_index_holder;
+pub mod find_components_in_mdast;
+pub mod find_fetch_urls_in_mdast;
 pub mod find_front_matter_in_mdast;
+pub mod find_local_image_urls_in_mdast;
+pub mod find_missing_component_props_in_mdast;
 pub mod find_table_of_contents_in_mdast;
 pub mod find_text_content_in_mdast;
+pub mod find_unknown_context_references_in_mdast;
 pub mod flexible_datetime;
+pub mod front_matter_unknown_fields_policy;
 pub mod generate_sitemap;
 pub mod holder;
 pub mod is_external_link;
 pub mod is_valid_desktop_entry_string;
+pub mod lint_project;
 pub mod mcp;
 pub mod mcp_resource_provider_content_documents;
 pub mod mdast_children_to_heading_id;
+pub mod mdast_is_static;
 pub mod mdast_to_tantivy_document;
+pub mod mdast_uses_assets;
+pub mod normalize_smart_punctuation;
+pub mod parse_front_matter;
 pub mod parse_markdown_metadata_line;
+pub mod parse_yaml_front_matter;
+pub mod prompt_builtin_components;
+pub mod prompt_collection_config;
 pub mod prompt_controller_collection_holder;
+pub mod prompt_discovery;
 pub mod prompt_document_component_context;
+pub mod prompt_document_component_context_checkpoint;
 pub mod prompt_document_controller;
 pub mod prompt_document_front_matter;
+pub mod prompt_document_role;
+pub mod prompt_fetch_policy;
+pub mod prompt_fetcher;
+pub mod prompt_fetcher_http;
+pub mod prompt_image_policy;
+pub mod prompt_line_ending;
+pub mod prompt_manifest;
+pub mod prompt_manifest_operation;
+pub mod prompt_messages_to_html;
+pub mod prompt_provenance;
+pub mod prompt_random;
+pub mod prompt_render_cancelled;
+pub mod prompt_render_limits;
+pub mod prompt_render_limits_policy;
+pub mod prompt_render_pass;
+pub mod prompt_render_pipeline;
+pub mod prompt_request_metadata;
+pub mod prompt_system_role_lowering;
+pub mod prompt_uncategorized_policy;
 pub mod read_esbuild_metafile_or_default;
+pub mod resolve_local_image_path;
 pub mod rhai_helpers;
 pub mod rhai_template_renderer_factory;
 pub mod rhai_template_renderer_holder;
@@ -69,5 +115,9 @@ pub mod search_index_reader;
 pub mod search_index_reader_holder;
 pub mod search_index_schema;
 pub mod search_tool;
+pub mod split_base64_data_uri;
 pub mod string_to_mdast;
 pub mod table_of_contents;
+pub mod take_title_heading_from_mdast;
+pub mod token_counter;
+pub mod token_counter_heuristic;
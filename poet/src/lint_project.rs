@@ -0,0 +1,136 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use serde::de::DeserializeOwned;
+
+use crate::author_data::AuthorData;
+use crate::content_document_basename::ContentDocumentBasename;
+use crate::content_document_basename_by_id::ContentDocumentBasenameById;
+use crate::content_document_front_matter::ContentDocumentFrontMatter;
+use crate::document_error_collection::DocumentErrorCollection;
+use crate::filesystem::Filesystem as _;
+use crate::filesystem::storage::Storage;
+use crate::find_front_matter_in_mdast::find_front_matter_in_mdast;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+use crate::parse_front_matter::parse_front_matter;
+use crate::prompt_collection_config::PromptCollectionConfig;
+use crate::prompt_document_front_matter::PromptDocumentFrontMatter;
+use crate::string_to_mdast::string_to_mdast;
+
+/// Reads front matter the same way the build does, but leaves the rest of
+/// the document unevaluated
+fn lint_front_matter<TFrontMatter: DeserializeOwned>(
+    contents: &str,
+    unknown_fields_policy: FrontMatterUnknownFieldsPolicy,
+) -> Result<TFrontMatter> {
+    find_front_matter_in_mdast::<TFrontMatter>(&string_to_mdast(contents)?, unknown_fields_policy)?
+        .ok_or_else(|| anyhow!("No front matter found"))
+}
+
+/// Checks the structure of project files without rendering anything, so
+/// obvious mistakes are caught before a full build
+pub async fn lint_project(
+    source_filesystem: Arc<Storage>,
+    unknown_fields_policy: FrontMatterUnknownFieldsPolicy,
+) -> Result<DocumentErrorCollection> {
+    let error_collection: DocumentErrorCollection = Default::default();
+    let mut content_document_basename_by_id = ContentDocumentBasenameById::default();
+
+    for file in source_filesystem.read_project_files().await? {
+        let relative_path = file.relative_path.display().to_string();
+
+        if file.kind.is_author() {
+            if let Err(err) = toml::from_str::<AuthorData>(&file.contents) {
+                error_collection
+                    .register_error(relative_path, anyhow!("Failed to parse author file: {err}"));
+            }
+        } else if file.kind.is_content() {
+            match lint_front_matter::<ContentDocumentFrontMatter>(
+                &file.contents,
+                unknown_fields_policy,
+            ) {
+                Ok(ContentDocumentFrontMatter { id: Some(id), .. }) => {
+                    let basename: ContentDocumentBasename = file
+                        .get_stem_path_relative_to(&PathBuf::from("content"))
+                        .into();
+
+                    if let Err(err) = content_document_basename_by_id.register(&id, basename) {
+                        error_collection.register_error(relative_path, err);
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => error_collection.register_error(relative_path, err),
+            }
+        } else if file.kind.is_prompt() {
+            if let Err(err) = lint_front_matter::<PromptDocumentFrontMatter>(
+                &file.contents,
+                unknown_fields_policy,
+            ) {
+                error_collection.register_error(relative_path, err);
+            }
+        } else if file.kind.is_prompt_collection()
+            && let Err(err) =
+                parse_front_matter::<PromptCollectionConfig>(&file.contents, unknown_fields_policy)
+        {
+            error_collection.register_error(
+                relative_path,
+                anyhow!("Failed to parse prompt collection file: {err}"),
+            );
+        }
+    }
+
+    Ok(error_collection)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use indoc::indoc;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::filesystem::storage::DEFAULT_MAX_FILE_BYTES;
+
+    #[tokio::test]
+    async fn test_duplicate_id_is_reported() -> Result<()> {
+        let base_directory = tempdir()?;
+        let storage = Storage {
+            base_directory: base_directory.path().to_path_buf(),
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            source_encodings: Default::default(),
+        };
+
+        for basename in ["first", "second"] {
+            storage
+                .set_file_contents(
+                    Path::new(&format!("content/{basename}.md")),
+                    indoc! {r#"
+                    +++
+                    description = "Duplicate"
+                    id = "shared"
+                    layout = "LayoutMissing"
+                    title = "Duplicate"
+                    +++
+
+                    <ComponentThatDoesNotExist />
+                    "#},
+                )
+                .await?;
+        }
+
+        let error_collection = lint_project(Arc::new(storage), Default::default()).await?;
+
+        assert!(!error_collection.is_empty());
+        assert!(
+            error_collection
+                .to_string()
+                .contains("Duplicate document id: #shared")
+        );
+        assert!(!error_collection.to_string().contains("LayoutMissing"));
+
+        Ok(())
+    }
+}
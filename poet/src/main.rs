@@ -1,21 +1,33 @@
-use anyhow::Result;
+use std::process::ExitCode;
+
 use clap::Parser;
 use clap::Subcommand;
 use poet::cmd::handler::Handler;
+use poet::cmd::lint::Lint;
 use poet::cmd::make::app_dir::AppDir;
+use poet::cmd::make::prompt_manifest_file::PromptManifestFile;
 use poet::cmd::make::static_pages::StaticPages;
 use poet::cmd::serve::Serve;
 use poet::cmd::watch::Watch;
+use poet::document_error_collection::DocumentErrorCollection;
+use poet::document_error_format::DocumentErrorFormat;
 
 #[derive(Parser)]
 #[command(arg_required_else_help(true), version, about, long_about = None)]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Document errors in JSON are printed alone on stdout, so CI tooling can
+    /// parse them
+    #[arg(long, global = true, value_enum, default_value_t)]
+    error_format: DocumentErrorFormat,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Checks the front matter of project files without building them
+    Lint(Lint),
     /// Produce various output formats based on your content files
     Make {
         #[command(subcommand)]
@@ -31,14 +43,18 @@ enum Commands {
 enum Make {
     /// Generates AppDir (packageable with AppImageKit)
     AppDir(AppDir),
+    /// Describes every prompt as an operation, for tooling that does not speak MCP
+    PromptManifest(PromptManifestFile),
     /// Generates static pages
     StaticPages(StaticPages),
 }
 
-fn get_handler() -> Option<Box<dyn Handler>> {
-    match Cli::parse().command {
+fn get_handler(command: Option<Commands>) -> Option<Box<dyn Handler>> {
+    match command {
+        Some(Commands::Lint(handler)) => Some(Box::new(handler)),
         Some(Commands::Make { command }) => match command {
             Make::AppDir(handler) => Some(Box::new(handler)),
+            Make::PromptManifest(handler) => Some(Box::new(handler)),
             Make::StaticPages(handler) => Some(Box::new(handler)),
         },
         Some(Commands::Serve(handler)) => Some(Box::new(handler)),
@@ -48,14 +64,28 @@ fn get_handler() -> Option<Box<dyn Handler>> {
 }
 
 #[actix_web::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
         .filter_module("tantivy", log::LevelFilter::Warn)
         .init();
 
-    if let Some(handler) = get_handler() {
-        handler.handle().await
-    } else {
-        Ok(())
+    let Cli {
+        command,
+        error_format,
+    } = Cli::parse();
+    let Some(handler) = get_handler(command) else {
+        return ExitCode::SUCCESS;
+    };
+    let Err(err) = handler.handle().await else {
+        return ExitCode::SUCCESS;
+    };
+
+    match (error_format, err.downcast_ref::<DocumentErrorCollection>()) {
+        (DocumentErrorFormat::Json, Some(error_collection)) => {
+            println!("{}", error_collection.to_json());
+        }
+        _ => eprintln!("Error: {err:?}"),
     }
+
+    ExitCode::FAILURE
 }
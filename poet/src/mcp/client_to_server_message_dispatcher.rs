@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use anyhow::anyhow;
+
+use crate::mcp::handler::Handler as _;
+use crate::mcp::handler::cancelled_handler::CancelledHandler;
+use crate::mcp::handler::completion_complete_handler::CompletionCompleteHandler;
+use crate::mcp::handler::initialized_handler::InitializedHandler;
+use crate::mcp::handler::logging_set_level_handler::LoggingSetLevelHandler;
+use crate::mcp::handler::ping_handler::PingHandler;
+use crate::mcp::handler::prompts_get_handler::PromptsGetHandler;
+use crate::mcp::handler::prompts_list_handler::PromptsListHandler;
+use crate::mcp::handler::resources_list_handler::ResourcesListHandler;
+use crate::mcp::handler::resources_read_handler::ResourcesReadHandler;
+use crate::mcp::handler::resources_subscribe_handler::ResourcesSubscribeHandler;
+use crate::mcp::handler::resources_templates_list_handler::ResourcesTemplatesListHandler;
+use crate::mcp::handler::resources_unsubscribe_handler::ResourcesUnsubscribeHandler;
+use crate::mcp::handler::tools_call_handler::ToolsCallHandler;
+use crate::mcp::handler::tools_list_handler::ToolsListHandler;
+use crate::mcp::jsonrpc::client_to_server_message::ClientToServerMessage;
+use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
+use crate::mcp::resource_list_aggregate::ResourceListAggregate;
+use crate::mcp::session::Session;
+use crate::mcp::session_manager::SessionManager;
+use crate::mcp::tool_registry::ToolRegistry;
+use crate::prompt_controller_collection_holder::PromptControllerCollectionHolder;
+
+/// Routes the messages of an initialized session to their handlers, the
+/// same way for every transport
+#[derive(Clone)]
+pub struct ClientToServerMessageDispatcher {
+    pub prompt_controller_collection_holder: PromptControllerCollectionHolder,
+    pub resource_list_aggregate: Arc<ResourceListAggregate>,
+    pub session_manager: SessionManager,
+    pub tool_registry: Arc<ToolRegistry>,
+}
+
+impl ClientToServerMessageDispatcher {
+    pub async fn dispatch(
+        self,
+        client_to_server_message: ClientToServerMessage,
+        session: Session,
+    ) -> Result<Option<ServerToClientResponse>> {
+        match client_to_server_message {
+            ClientToServerMessage::Cancelled(request) => {
+                CancelledHandler {}.handle(request, session).await
+            }
+            ClientToServerMessage::CompletionComplete(request) => {
+                CompletionCompleteHandler {
+                    prompt_controller_collection_holder: self.prompt_controller_collection_holder,
+                }
+                .handle(request, session)
+                .await
+            }
+            ClientToServerMessage::Initialize(_) => {
+                Err(anyhow!("The session is already initialized"))
+            }
+            ClientToServerMessage::Initialized(request) => {
+                InitializedHandler {}.handle(request, session).await
+            }
+            ClientToServerMessage::LoggingSetLevel(request) => {
+                LoggingSetLevelHandler {
+                    session_manager: self.session_manager,
+                }
+                .handle(request, session)
+                .await
+            }
+            ClientToServerMessage::Ping(request) => PingHandler {}.handle(request, ()).await,
+            ClientToServerMessage::PromptsGet(request) => {
+                PromptsGetHandler {
+                    prompt_controller_collection_holder: self.prompt_controller_collection_holder,
+                }
+                .handle(request, session)
+                .await
+            }
+            ClientToServerMessage::PromptsList(request) => {
+                PromptsListHandler {
+                    prompt_controller_collection_holder: self.prompt_controller_collection_holder,
+                }
+                .handle(request, session)
+                .await
+            }
+            ClientToServerMessage::ResourcesList(request) => {
+                ResourcesListHandler {
+                    resource_list_aggregate: self.resource_list_aggregate,
+                }
+                .handle(request, session)
+                .await
+            }
+            ClientToServerMessage::ResourcesRead(request) => {
+                ResourcesReadHandler {
+                    resource_list_aggregate: self.resource_list_aggregate,
+                }
+                .handle(request, session)
+                .await
+            }
+            ClientToServerMessage::ResourcesSubscribe(request) => {
+                ResourcesSubscribeHandler {
+                    resource_list_aggregate: self.resource_list_aggregate,
+                }
+                .handle(request, session)
+                .await
+            }
+            ClientToServerMessage::ResourcesTemplatesList(request) => {
+                ResourcesTemplatesListHandler {
+                    resource_list_aggregate: self.resource_list_aggregate,
+                }
+                .handle(request, session)
+                .await
+            }
+            ClientToServerMessage::ResourcesUnsubscribe(request) => {
+                ResourcesUnsubscribeHandler {}
+                    .handle(request, session)
+                    .await
+            }
+            ClientToServerMessage::ToolsCall(request) => {
+                ToolsCallHandler {
+                    tool_registry: self.tool_registry,
+                }
+                .handle(request, session)
+                .await
+            }
+            ClientToServerMessage::ToolsList(request) => {
+                ToolsListHandler {
+                    tool_registry: self.tool_registry,
+                }
+                .handle(request, session)
+                .await
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use anyhow::anyhow;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::split_base64_data_uri::split_base64_data_uri;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AudioContent {
+    pub data: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+}
+
+impl AudioContent {
+    pub fn from_base64_data_uri(src: &str) -> Result<Self> {
+        let (mime_type, data) = split_base64_data_uri(src)
+            .ok_or_else(|| anyhow!("Audio src has to be a base64 data URI, got: '{src}'"))?;
+        let audio_content = Self {
+            data: data.to_string(),
+            mime_type: mime_type.to_string(),
+        };
+
+        audio_content.assert_audio_mime_type()?;
+
+        Ok(audio_content)
+    }
+
+    pub fn assert_audio_mime_type(&self) -> Result<()> {
+        if !self.mime_type.starts_with("audio/") {
+            return Err(anyhow!(
+                "Audio content has the mime type '{}', expected an 'audio/*' type",
+                self.mime_type
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -5,5 +5,5 @@ use crate::mcp::resource_content::ResourceContent;
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct EmbeddedResource {
-    resource: ResourceContent,
+    pub resource: ResourceContent,
 }
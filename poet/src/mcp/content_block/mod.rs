@@ -1,25 +1,45 @@
+pub mod audio_content;
 pub mod embedded_resource;
+pub mod image_content;
 pub mod resource_link;
 pub mod text_content;
 
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::mcp::content_block::audio_content::AudioContent;
 use crate::mcp::content_block::embedded_resource::EmbeddedResource;
+use crate::mcp::content_block::image_content::ImageContent;
 use crate::mcp::content_block::resource_link::ResourceLink;
 use crate::mcp::content_block::text_content::TextContent;
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(tag = "type")]
 pub enum ContentBlock {
+    #[serde(rename = "audio")]
+    AudioContent(AudioContent),
     #[serde(rename = "resource")]
     EmbeddedResource(EmbeddedResource),
+    #[serde(rename = "image")]
+    ImageContent(ImageContent),
     #[serde(rename = "resource_link")]
     ResourceLink(ResourceLink),
     #[serde(rename = "text")]
     TextContent(TextContent),
 }
 
+impl ContentBlock {
+    /// Values of the `type` field that identify each kind of content block
+    pub const TYPE_NAMES: [&str; 5] = ["audio", "image", "resource", "resource_link", "text"];
+
+    pub fn is_empty_text(&self) -> bool {
+        match self {
+            ContentBlock::TextContent(TextContent { text }) => text.trim().is_empty(),
+            _ => false,
+        }
+    }
+}
+
 impl From<&str> for ContentBlock {
     fn from(value: &str) -> Self {
         ContentBlock::TextContent(value.into())
@@ -0,0 +1,36 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use log::debug;
+
+use crate::mcp::handler::Handler;
+use crate::mcp::jsonrpc::notification::cancelled::Cancelled;
+use crate::mcp::jsonrpc::notification::cancelled::CancelledParams;
+use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
+use crate::mcp::session::Session;
+
+pub struct CancelledHandler {}
+
+#[async_trait]
+impl Handler for CancelledHandler {
+    type Request = Cancelled;
+    type Session = Session;
+
+    async fn handle(
+        self,
+        Cancelled {
+            params: CancelledParams { reason, request_id },
+            ..
+        }: Self::Request,
+        session: Self::Session,
+    ) -> Result<Option<ServerToClientResponse>> {
+        // The request might have finished already, which is not an error
+        if session.cancel_request(&request_id) {
+            debug!(
+                "Cancelled request {request_id:?}: {}",
+                reason.as_deref().unwrap_or("no reason given")
+            );
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::holder::Holder as _;
+use crate::mcp::handler::Handler;
+use crate::mcp::jsonrpc::JSONRPC_VERSION;
+use crate::mcp::jsonrpc::request::completion_complete::CompletionComplete as CompletionCompleteRequest;
+use crate::mcp::jsonrpc::request::completion_complete::CompletionCompleteArgument;
+use crate::mcp::jsonrpc::request::completion_complete::CompletionCompleteParams;
+use crate::mcp::jsonrpc::request::completion_complete::CompletionCompleteReference;
+use crate::mcp::jsonrpc::response::error::Error;
+use crate::mcp::jsonrpc::response::success::Success;
+use crate::mcp::jsonrpc::response::success::completion_complete::CompletionComplete as CompletionCompleteResponse;
+use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
+use crate::mcp::session::Session;
+use crate::prompt_controller_collection_holder::PromptControllerCollectionHolder;
+
+pub struct CompletionCompleteHandler {
+    pub prompt_controller_collection_holder: PromptControllerCollectionHolder,
+}
+
+#[async_trait]
+impl Handler for CompletionCompleteHandler {
+    type Request = CompletionCompleteRequest;
+    type Session = Session;
+
+    async fn handle(
+        self,
+        CompletionCompleteRequest {
+            id,
+            params:
+                CompletionCompleteParams {
+                    argument: CompletionCompleteArgument { name, value },
+                    reference,
+                    ..
+                },
+            ..
+        }: Self::Request,
+        _: Self::Session,
+    ) -> Result<Option<ServerToClientResponse>> {
+        let response = match reference {
+            CompletionCompleteReference::Prompt {
+                name: prompt_name,
+            } => match self.prompt_controller_collection_holder.get().await {
+                Some(prompt_controller_collection) => {
+                    match prompt_controller_collection.get(&prompt_name) {
+                        Some(prompt_controller) => ServerToClientResponse::CompletionComplete(Success {
+                            id,
+                            jsonrpc: JSONRPC_VERSION.to_string(),
+                            result: CompletionCompleteResponse {
+                                completion: prompt_controller.complete(&name, &value),
+                            },
+                        }),
+                        None => ServerToClientResponse::Error(Error::invalid_prompt_name(
+                            id,
+                            prompt_name,
+                        )),
+                    }
+                }
+                None => ServerToClientResponse::Error(Error::request_internal(
+                    id,
+                    "Prompt controller collection is not ready. The server is not ready yet or is still starting.".to_string(),
+                )),
+            },
+            CompletionCompleteReference::Resource { .. } => {
+                ServerToClientResponse::CompletionComplete(Success {
+                    id,
+                    jsonrpc: JSONRPC_VERSION.to_string(),
+                    result: CompletionCompleteResponse {
+                        completion: Default::default(),
+                    },
+                })
+            }
+        };
+
+        Ok(Some(response))
+    }
+}
@@ -0,0 +1,92 @@
+use anyhow::Result;
+use log::warn;
+
+use crate::mcp::MCP_SUPPORTED_PROTOCOL_VERSIONS;
+use crate::mcp::jsonrpc::JSONRPC_VERSION;
+use crate::mcp::jsonrpc::empty_object::EmptyObject;
+use crate::mcp::jsonrpc::implementation::Implementation;
+use crate::mcp::jsonrpc::request::initialize::Initialize;
+use crate::mcp::jsonrpc::response::error::Error;
+use crate::mcp::jsonrpc::response::success::Success;
+use crate::mcp::jsonrpc::response::success::initialize_result::InitializeResult;
+use crate::mcp::jsonrpc::response::success::initialize_result::ServerCapabilities;
+use crate::mcp::jsonrpc::response::success::initialize_result::ServerCapabilityPrompts;
+use crate::mcp::jsonrpc::response::success::initialize_result::ServerCapabilityResources;
+use crate::mcp::jsonrpc::response::success::initialize_result::ServerCapabilityTools;
+use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
+use crate::mcp::session_manager::SessionManager;
+use crate::mcp::session_with_notifications_receiver::SessionWithNotificationsReceiver;
+
+/// Unlike the other handlers, starts the session that the transport keeps
+/// for the connection. The transport delivers the notifications of the
+/// session.
+#[derive(Clone)]
+pub struct InitializeHandler {
+    /// Only watch mode rebuilds prompts while the server is running
+    pub prompts_list_changed: bool,
+    pub server_info: Implementation,
+    pub session_manager: SessionManager,
+}
+
+impl InitializeHandler {
+    /// No session is started if the protocol version is not supported
+    pub async fn handle(
+        self,
+        Initialize { id, params, .. }: Initialize,
+    ) -> Result<(
+        ServerToClientResponse,
+        Option<SessionWithNotificationsReceiver>,
+    )> {
+        if !MCP_SUPPORTED_PROTOCOL_VERSIONS.contains(&params.protocol_version.as_str()) {
+            return Ok((
+                ServerToClientResponse::Error(Error::unsupported_protocol_version(
+                    id,
+                    params.protocol_version,
+                )),
+                None,
+            ));
+        }
+
+        if !params.capabilities.extra.is_empty() {
+            warn!(
+                "Unknown fields in client capabilities: {:#?}",
+                params.capabilities.extra
+            );
+        }
+
+        let session_with_notifications_receiver = self
+            .session_manager
+            .start_new_session(
+                params.client_info,
+                self.prompts_list_changed,
+                params.protocol_version.clone(),
+            )
+            .await?;
+
+        Ok((
+            ServerToClientResponse::InitializeResult(Success {
+                id,
+                jsonrpc: JSONRPC_VERSION.to_string(),
+                result: InitializeResult {
+                    capabilities: ServerCapabilities {
+                        completions: Some(EmptyObject {}),
+                        experimental: None,
+                        logging: Some(EmptyObject {}),
+                        prompts: Some(ServerCapabilityPrompts {
+                            list_changed: self.prompts_list_changed,
+                        }),
+                        resources: Some(ServerCapabilityResources {
+                            list_changed: true,
+                            subscribe: true,
+                        }),
+                        tools: Some(ServerCapabilityTools { list_changed: true }),
+                    },
+                    instructions: None,
+                    protocol_version: params.protocol_version,
+                    server_info: self.server_info,
+                },
+            }),
+            Some(session_with_notifications_receiver),
+        ))
+    }
+}
@@ -1,14 +1,11 @@
-use actix_web::HttpResponse;
-use actix_web::Result;
-use actix_web::body::BoxBody;
-use actix_web::error::ErrorInternalServerError;
+use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::mcp::MCP_HEADER_SESSION;
+use crate::mcp::handler::Handler;
 use crate::mcp::jsonrpc::notification::initialized::Initialized;
 use crate::mcp::jsonrpc::notification::message::MessageParams;
+use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
 use crate::mcp::log_level::LogLevel;
-use crate::mcp::mcp_http_service::respond_to_post::handler::Handler;
 use crate::mcp::session::Session;
 
 pub struct InitializedHandler {}
@@ -22,17 +19,14 @@ impl Handler for InitializedHandler {
         self,
         _: Self::Request,
         session: Self::Session,
-    ) -> Result<HttpResponse<BoxBody>> {
+    ) -> Result<Option<ServerToClientResponse>> {
         session
             .log_message(MessageParams {
                 data: "Initialization handshake is successfully completed".to_string(),
                 level: LogLevel::Debug,
             })
-            .await
-            .map_err(ErrorInternalServerError)?;
+            .await?;
 
-        Ok(HttpResponse::Accepted()
-            .insert_header((MCP_HEADER_SESSION, session.id()))
-            .finish())
+        Ok(None)
     }
 }
@@ -1,11 +1,10 @@
-use actix_web::HttpResponse;
-use actix_web::Result;
-use actix_web::body::BoxBody;
+use anyhow::Result;
 use async_trait::async_trait;
 
+use crate::mcp::handler::Handler;
 use crate::mcp::jsonrpc::request::logging_set_level::LoggingSetLevel;
 use crate::mcp::jsonrpc::request::logging_set_level::LoggingSetLevelParams;
-use crate::mcp::mcp_http_service::respond_to_post::handler::Handler;
+use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
 use crate::mcp::session::Session;
 use crate::mcp::session_manager::SessionManager;
 
@@ -26,11 +25,11 @@ impl Handler for LoggingSetLevelHandler {
             ..
         }: Self::Request,
         session: Self::Session,
-    ) -> Result<HttpResponse<BoxBody>> {
+    ) -> Result<Option<ServerToClientResponse>> {
         self.session_manager
-            .update_session(session.clone().with_log_level(level))
+            .update_session(session.with_log_level(level))
             .await?;
 
-        self.empty_response(id, session)
+        self.empty_response(id)
     }
 }
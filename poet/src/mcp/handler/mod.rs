@@ -1,3 +1,5 @@
+pub mod cancelled_handler;
+pub mod completion_complete_handler;
 pub mod initialize_handler;
 pub mod initialized_handler;
 pub mod logging_set_level_handler;
@@ -12,37 +14,33 @@ pub mod resources_unsubscribe_handler;
 pub mod tools_call_handler;
 pub mod tools_list_handler;
 
-use actix_web::HttpResponse;
-use actix_web::Result;
-use actix_web::body::BoxBody;
+use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::mcp::MCP_HEADER_SESSION;
 use crate::mcp::jsonrpc::JSONRPC_VERSION;
 use crate::mcp::jsonrpc::id::Id;
 use crate::mcp::jsonrpc::response::success::Success;
 use crate::mcp::jsonrpc::response::success::empty_response::EmptyResponse;
 use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
-use crate::mcp::session::Session;
 
+/// Handles a single message regardless of the transport it came through
 #[async_trait]
 pub trait Handler: Sized {
     type Request;
     type Session;
 
+    /// Notifications, and requests the client cancelled, get no response
     async fn handle(
         self,
         request: Self::Request,
         session: Self::Session,
-    ) -> Result<HttpResponse<BoxBody>>;
+    ) -> Result<Option<ServerToClientResponse>>;
 
-    fn empty_response(self, id: Id, session: Session) -> Result<HttpResponse<BoxBody>> {
-        Ok(HttpResponse::Ok()
-            .insert_header((MCP_HEADER_SESSION, session.id()))
-            .json(ServerToClientResponse::EmptyResponse(Success {
-                id,
-                jsonrpc: JSONRPC_VERSION.to_string(),
-                result: EmptyResponse {},
-            })))
+    fn empty_response(self, id: Id) -> Result<Option<ServerToClientResponse>> {
+        Ok(Some(ServerToClientResponse::EmptyResponse(Success {
+            id,
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            result: EmptyResponse {},
+        })))
     }
 }
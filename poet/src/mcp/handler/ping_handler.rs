@@ -0,0 +1,22 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::mcp::handler::Handler;
+use crate::mcp::jsonrpc::request::ping::Ping;
+use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
+
+pub struct PingHandler {}
+
+#[async_trait]
+impl Handler for PingHandler {
+    type Request = Ping;
+    type Session = ();
+
+    async fn handle(
+        self,
+        Ping { id, .. }: Self::Request,
+        _: Self::Session,
+    ) -> Result<Option<ServerToClientResponse>> {
+        self.empty_response(id)
+    }
+}
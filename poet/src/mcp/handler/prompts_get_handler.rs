@@ -0,0 +1,92 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::holder::Holder as _;
+use crate::mcp::handler::Handler;
+use crate::mcp::jsonrpc::JSONRPC_VERSION;
+use crate::mcp::jsonrpc::notification::message::MessageParams;
+use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
+use crate::mcp::jsonrpc::response::error::Error;
+use crate::mcp::jsonrpc::response::success::Success;
+use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
+use crate::mcp::log_level::LogLevel;
+use crate::mcp::session::Session;
+use crate::prompt_controller_collection_holder::PromptControllerCollectionHolder;
+use crate::prompt_render_cancelled::PromptRenderCancelled;
+
+pub struct PromptsGetHandler {
+    pub prompt_controller_collection_holder: PromptControllerCollectionHolder,
+}
+
+#[async_trait]
+impl Handler for PromptsGetHandler {
+    type Request = PromptsGet;
+    type Session = Session;
+
+    async fn handle(
+        self,
+        mut request: Self::Request,
+        session: Self::Session,
+    ) -> Result<Option<ServerToClientResponse>> {
+        let response = match self
+            .prompt_controller_collection_holder
+            .get()
+            .await {
+            Some(prompt_controller_collection) => {
+                match prompt_controller_collection
+                    .get(&request.params.name)
+                    .or_else(|| prompt_controller_collection.get_fallback())
+                {
+                    Some(prompt_controller) => {
+                        request.cancellation_token = session.start_request(request.id.clone());
+                        request.client_info = Some(session.client_info());
+
+                        let id = request.id.clone();
+                        let name = request.params.name.clone();
+                        let result = prompt_controller.respond_to(request).await;
+
+                        session.finish_request(&id);
+
+                        let result = match result {
+                            Ok(result) => result,
+                            // The client does not wait for the response anymore
+                            Err(err) if err.chain().any(|cause| cause.is::<PromptRenderCancelled>()) => {
+                                return Ok(None);
+                            }
+                            Err(err) => return Err(err),
+                        };
+
+                        if result.is_assistant_prefill() && session.mark_prefill_warned(&name) {
+                            session
+                                .log_message(MessageParams {
+                                    data: format!("Prompt '{name}' ends with a partial assistant message meant to be continued (prefill). Clients without prefill support should not treat it as a complete turn."),
+                                    level: LogLevel::Warning,
+                                })
+                                .await?;
+                        }
+
+                        ServerToClientResponse::PromptsGet(Success {
+                            id,
+                            jsonrpc: JSONRPC_VERSION.to_string(),
+                            result,
+                        })
+                    }
+                    None => {
+                        ServerToClientResponse::Error(Error::invalid_prompt_name(
+                            request.id,
+                            request.params.name
+                        ))
+                    }
+                }
+            }
+            None => {
+                ServerToClientResponse::Error(Error::request_internal(
+                    request.id,
+                    "Prompt controller collection is not ready. The server is not ready yet or is still starting.".to_string(),
+                ))
+            }
+        };
+
+        Ok(Some(response))
+    }
+}
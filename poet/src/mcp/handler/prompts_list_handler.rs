@@ -1,18 +1,15 @@
-use actix_web::HttpResponse;
-use actix_web::Result;
-use actix_web::body::BoxBody;
+use anyhow::Result;
 use async_trait::async_trait;
 
 use crate::holder::Holder as _;
-use crate::mcp::MCP_HEADER_SESSION;
+use crate::mcp::handler::Handler;
 use crate::mcp::jsonrpc::JSONRPC_VERSION;
 use crate::mcp::jsonrpc::request::prompts_list::PromptsList as PromptsListRequest;
 use crate::mcp::jsonrpc::request::prompts_list::PromptsListParams;
 use crate::mcp::jsonrpc::response::error::Error;
 use crate::mcp::jsonrpc::response::success::Success;
-use crate::mcp::jsonrpc::response::success::prompts_list::PromptsList as PromptsListResponse;
 use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
-use crate::mcp::mcp_http_service::respond_to_post::handler::Handler;
+use crate::mcp::list_resources_cursor::ListResourcesCursor;
 use crate::mcp::session::Session;
 use crate::prompt_controller_collection_holder::PromptControllerCollectionHolder;
 
@@ -32,8 +29,18 @@ impl Handler for PromptsListHandler {
             params: PromptsListParams { cursor, .. },
             ..
         }: Self::Request,
-        session: Self::Session,
-    ) -> Result<HttpResponse<BoxBody>> {
+        _: Self::Session,
+    ) -> Result<Option<ServerToClientResponse>> {
+        let list_cursor: ListResourcesCursor = cursor.unwrap_or_default();
+
+        if list_cursor.per_page < 1 {
+            return Ok(Some(ServerToClientResponse::Error(Error::invalid_params(
+                id,
+                "per_page must be greater than 0".to_string(),
+            ))));
+        }
+
+        let page_size = list_cursor.per_page;
         let response = match self
             .prompt_controller_collection_holder
             .get()
@@ -43,9 +50,7 @@ impl Handler for PromptsListHandler {
                 ServerToClientResponse::PromptsList(Success {
                     id,
                     jsonrpc: JSONRPC_VERSION.to_string(),
-                    result: PromptsListResponse {
-                        prompts: prompt_controller_collection.list_mcp_prompts(cursor.unwrap_or_default()),
-                    },
+                    result: prompt_controller_collection.list_prompts(Some(list_cursor), page_size),
                 })
             }
             None => {
@@ -56,8 +61,6 @@ impl Handler for PromptsListHandler {
             }
         };
 
-        Ok(HttpResponse::Ok()
-            .insert_header((MCP_HEADER_SESSION, session.id()))
-            .json(response))
+        Ok(Some(response))
     }
 }
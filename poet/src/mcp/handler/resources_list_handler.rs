@@ -1,12 +1,9 @@
 use std::sync::Arc;
 
-use actix_web::HttpResponse;
-use actix_web::Result;
-use actix_web::body::BoxBody;
-use actix_web::error::ErrorInternalServerError;
+use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::mcp::MCP_HEADER_SESSION;
+use crate::mcp::handler::Handler;
 use crate::mcp::jsonrpc::JSONRPC_VERSION;
 use crate::mcp::jsonrpc::request::resources_list::ResourcesList as ResourcesListRequest;
 use crate::mcp::jsonrpc::request::resources_list::ResourcesListParams;
@@ -15,7 +12,6 @@ use crate::mcp::jsonrpc::response::success::Success;
 use crate::mcp::jsonrpc::response::success::resources_list::ResourcesList as ResourcesListResponse;
 use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
 use crate::mcp::list_resources_cursor::ListResourcesCursor;
-use crate::mcp::mcp_http_service::respond_to_post::handler::Handler;
 use crate::mcp::resource_list_aggregate::ResourceListAggregate;
 use crate::mcp::session::Session;
 
@@ -35,18 +31,15 @@ impl Handler for ResourcesListHandler {
             params: ResourcesListParams { cursor, .. },
             ..
         }: Self::Request,
-        session: Self::Session,
-    ) -> Result<HttpResponse<BoxBody>> {
-        let list_cursor = match cursor {
-            Some(list_cursor) => list_cursor,
-            None => ListResourcesCursor::default(),
-        };
+        _: Self::Session,
+    ) -> Result<Option<ServerToClientResponse>> {
+        let list_cursor: ListResourcesCursor = cursor.unwrap_or_default();
 
         if list_cursor.per_page < 1 {
-            return Ok(HttpResponse::BadRequest().json(Error::invalid_params(
+            return Ok(Some(ServerToClientResponse::Error(Error::invalid_params(
                 id,
                 "per_page must be greater than 0".to_string(),
-            )));
+            ))));
         }
 
         let total = self.resource_list_aggregate.total();
@@ -68,13 +61,10 @@ impl Handler for ResourcesListHandler {
                 resources: self
                     .resource_list_aggregate
                     .list_resources(list_cursor)
-                    .await
-                    .map_err(ErrorInternalServerError)?,
+                    .await?,
             },
         });
 
-        Ok(HttpResponse::Ok()
-            .insert_header((MCP_HEADER_SESSION, session.id()))
-            .json(response))
+        Ok(Some(response))
     }
 }
@@ -1,13 +1,10 @@
 use std::sync::Arc;
 
-use actix_web::HttpResponse;
-use actix_web::Result;
-use actix_web::body::BoxBody;
-use actix_web::error::ErrorInternalServerError;
+use anyhow::Result;
 use async_trait::async_trait;
 use log::warn;
 
-use crate::mcp::MCP_HEADER_SESSION;
+use crate::mcp::handler::Handler;
 use crate::mcp::jsonrpc::JSONRPC_VERSION;
 use crate::mcp::jsonrpc::request::resources_read::ResourcesRead as ResourcesReadRequest;
 use crate::mcp::jsonrpc::request::resources_read::ResourcesReadParams;
@@ -15,7 +12,6 @@ use crate::mcp::jsonrpc::response::error::Error;
 use crate::mcp::jsonrpc::response::success::Success;
 use crate::mcp::jsonrpc::response::success::resources_read::ResourcesRead as ResourcesReadResponse;
 use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
-use crate::mcp::mcp_http_service::respond_to_post::handler::Handler;
 use crate::mcp::resource_content_parts::ResourceContentParts;
 use crate::mcp::resource_list_aggregate::ResourceListAggregate;
 use crate::mcp::session::Session;
@@ -36,13 +32,12 @@ impl Handler for ResourcesReadHandler {
             params: ResourcesReadParams { uri, .. },
             ..
         }: Self::Request,
-        session: Self::Session,
-    ) -> Result<HttpResponse<BoxBody>> {
+        _: Self::Session,
+    ) -> Result<Option<ServerToClientResponse>> {
         let response = match self
             .resource_list_aggregate
             .read_resource_contents(&uri)
-            .await
-            .map_err(ErrorInternalServerError)?
+            .await?
         {
             Some(ResourceContentParts {
                 parts: contents, ..
@@ -61,8 +56,6 @@ impl Handler for ResourcesReadHandler {
             }
         };
 
-        Ok(HttpResponse::Ok()
-            .insert_header((MCP_HEADER_SESSION, session.id()))
-            .json(response))
+        Ok(Some(response))
     }
 }
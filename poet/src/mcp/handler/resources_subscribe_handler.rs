@@ -1,14 +1,11 @@
 use std::sync::Arc;
 
-use actix_web::HttpResponse;
-use actix_web::Result;
-use actix_web::body::BoxBody;
-use actix_web::error::ErrorInternalServerError;
 use actix_web::rt;
+use anyhow::Result;
 use async_trait::async_trait;
 use log::error;
 
-use crate::mcp::MCP_HEADER_SESSION;
+use crate::mcp::handler::Handler;
 use crate::mcp::jsonrpc::JSONRPC_VERSION;
 use crate::mcp::jsonrpc::notification::resources_updated::ResourcesUpdated;
 use crate::mcp::jsonrpc::notification::resources_updated::ResourcesUpdatedParams;
@@ -17,7 +14,6 @@ use crate::mcp::jsonrpc::request::resources_subscribe::ResourcesSubscribeParams;
 use crate::mcp::jsonrpc::response::error::Error;
 use crate::mcp::jsonrpc::server_to_client_notification::ServerToClientNotification;
 use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
-use crate::mcp::mcp_http_service::respond_to_post::handler::Handler;
 use crate::mcp::resource_content_parts::ResourceContentParts;
 use crate::mcp::resource_list_aggregate::ResourceListAggregate;
 use crate::mcp::session::Session;
@@ -40,18 +36,14 @@ impl Handler for ResourcesSubscribeHandler {
             ..
         }: Self::Request,
         session: Self::Session,
-    ) -> Result<HttpResponse<BoxBody>> {
-        let cancellation_token = session
-            .subscribe_to_resource(&uri)
-            .await
-            .map_err(ErrorInternalServerError)?;
+    ) -> Result<Option<ServerToClientResponse>> {
+        let cancellation_token = session.subscribe_to_resource(&uri).await?;
         let cancellation_token_clone = cancellation_token.clone();
 
         match self
             .resource_list_aggregate
             .subscribe(cancellation_token, &uri)
-            .await
-            .map_err(ErrorInternalServerError)?
+            .await?
         {
             Some(mut resource_content_parts_rx) => {
                 let session_clone = session.clone();
@@ -91,13 +83,11 @@ impl Handler for ResourcesSubscribeHandler {
                     resource_content_parts_rx.close();
                 });
 
-                self.empty_response(id, session)
+                self.empty_response(id)
             }
-            None => Ok(HttpResponse::NotFound()
-                .insert_header((MCP_HEADER_SESSION, session.id()))
-                .json(ServerToClientResponse::Error(Error::resource_not_found(
-                    id, uri,
-                )))),
+            None => Ok(Some(ServerToClientResponse::Error(
+                Error::resource_not_found(id, uri),
+            ))),
         }
     }
 }
@@ -1,12 +1,10 @@
-use actix_web::HttpResponse;
-use actix_web::Result;
-use actix_web::body::BoxBody;
-use actix_web::error::ErrorInternalServerError;
+use anyhow::Result;
 use async_trait::async_trait;
 
+use crate::mcp::handler::Handler;
 use crate::mcp::jsonrpc::request::resources_unsubscribe::ResourcesUnsubscribe;
 use crate::mcp::jsonrpc::request::resources_unsubscribe::ResourcesUnsubscribeParams;
-use crate::mcp::mcp_http_service::respond_to_post::handler::Handler;
+use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
 use crate::mcp::session::Session;
 
 pub struct ResourcesUnsubscribeHandler {}
@@ -24,14 +22,11 @@ impl Handler for ResourcesUnsubscribeHandler {
             ..
         }: Self::Request,
         session: Self::Session,
-    ) -> Result<HttpResponse<BoxBody>> {
-        if let Some(cancallation_token) = session
-            .subscribe_token(&uri)
-            .map_err(ErrorInternalServerError)?
-        {
+    ) -> Result<Option<ServerToClientResponse>> {
+        if let Some(cancallation_token) = session.subscribe_token(&uri)? {
             cancallation_token.cancel();
         }
 
-        self.empty_response(id, session)
+        self.empty_response(id)
     }
 }
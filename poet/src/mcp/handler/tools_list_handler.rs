@@ -1,18 +1,15 @@
 use std::sync::Arc;
 
-use actix_web::HttpResponse;
-use actix_web::Result;
-use actix_web::body::BoxBody;
+use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::mcp::MCP_HEADER_SESSION;
+use crate::mcp::handler::Handler;
 use crate::mcp::jsonrpc::JSONRPC_VERSION;
 use crate::mcp::jsonrpc::request::tools_list::ToolsList as ToolsListRequest;
 use crate::mcp::jsonrpc::request::tools_list::ToolsListParams;
 use crate::mcp::jsonrpc::response::success::Success;
 use crate::mcp::jsonrpc::response::success::tools_list::ToolsList as ToolsListResponse;
 use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
-use crate::mcp::mcp_http_service::respond_to_post::handler::Handler;
 use crate::mcp::session::Session;
 use crate::mcp::tool_registry::ToolRegistry;
 
@@ -32,8 +29,8 @@ impl Handler for ToolsListHandler {
             params: ToolsListParams { cursor, .. },
             ..
         }: Self::Request,
-        session: Self::Session,
-    ) -> Result<HttpResponse<BoxBody>> {
+        _: Self::Session,
+    ) -> Result<Option<ServerToClientResponse>> {
         let response = ServerToClientResponse::ToolsList(Success {
             id,
             jsonrpc: JSONRPC_VERSION.to_string(),
@@ -44,8 +41,6 @@ impl Handler for ToolsListHandler {
             },
         });
 
-        Ok(HttpResponse::Ok()
-            .insert_header((MCP_HEADER_SESSION, session.id()))
-            .json(response))
+        Ok(Some(response))
     }
 }
@@ -1,7 +1,9 @@
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::mcp::jsonrpc::notification::cancelled::Cancelled;
 use crate::mcp::jsonrpc::notification::initialized::Initialized;
+use crate::mcp::jsonrpc::request::completion_complete::CompletionComplete;
 use crate::mcp::jsonrpc::request::initialize::Initialize;
 use crate::mcp::jsonrpc::request::logging_set_level::LoggingSetLevel;
 use crate::mcp::jsonrpc::request::ping::Ping;
@@ -18,6 +20,10 @@ use crate::mcp::jsonrpc::request::tools_list::ToolsList;
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "method")]
 pub enum ClientToServerMessage {
+    #[serde(rename = "notifications/cancelled")]
+    Cancelled(Cancelled),
+    #[serde(rename = "completion/complete")]
+    CompletionComplete(CompletionComplete),
     #[serde(rename = "initialize")]
     Initialize(Initialize),
     #[serde(rename = "notifications/initialized")]
@@ -0,0 +1,20 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::mcp::jsonrpc::id::Id;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CancelledParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(rename = "requestId")]
+    pub request_id: Id,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Cancelled {
+    pub jsonrpc: String,
+    pub params: CancelledParams,
+}
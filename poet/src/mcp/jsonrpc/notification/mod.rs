@@ -1,4 +1,6 @@
+pub mod cancelled;
 pub mod initialized;
 pub mod message;
+pub mod prompts_list_changed;
 pub mod resources_list_changed;
 pub mod resources_updated;
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::mcp::jsonrpc::id::Id;
+use crate::mcp::jsonrpc::meta::Meta;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompletionCompleteArgument {
+    pub name: String,
+    /// Partial value typed so far
+    pub value: String,
+}
+
+/// Arguments the client already resolved, which may narrow down the
+/// completions of the next one
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompletionCompleteContext {
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum CompletionCompleteReference {
+    #[serde(rename = "ref/prompt")]
+    Prompt { name: String },
+    #[serde(rename = "ref/resource")]
+    Resource { uri: String },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompletionCompleteParams {
+    pub argument: CompletionCompleteArgument,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<CompletionCompleteContext>,
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+    #[serde(rename = "ref")]
+    pub reference: CompletionCompleteReference,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompletionComplete {
+    pub id: Id,
+    pub jsonrpc: String,
+    pub params: CompletionCompleteParams,
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use anyhow::anyhow;
+
+    use super::*;
+    use crate::mcp::jsonrpc::client_to_server_message::ClientToServerMessage;
+
+    #[test]
+    fn test_deserialize_prompt_reference() -> Result<()> {
+        let message: ClientToServerMessage = serde_json::from_str(
+            r#"{
+                "id": 1,
+                "jsonrpc": "2.0",
+                "method": "completion/complete",
+                "params": {
+                    "argument": { "name": "tone", "value": "fr" },
+                    "ref": { "type": "ref/prompt", "name": "greet" }
+                }
+            }"#,
+        )?;
+
+        let ClientToServerMessage::CompletionComplete(CompletionComplete {
+            params:
+                CompletionCompleteParams {
+                    argument: CompletionCompleteArgument { name, value },
+                    reference: CompletionCompleteReference::Prompt { name: prompt_name },
+                    ..
+                },
+            ..
+        }) = message
+        else {
+            return Err(anyhow!("Expected a prompt completion request"));
+        };
+
+        assert_eq!(prompt_name, "greet");
+        assert_eq!(name, "tone");
+        assert_eq!(value, "fr");
+
+        Ok(())
+    }
+}
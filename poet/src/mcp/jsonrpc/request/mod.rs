@@ -1,3 +1,4 @@
+pub mod completion_complete;
 pub mod initialize;
 pub mod logging_set_level;
 pub mod ping;
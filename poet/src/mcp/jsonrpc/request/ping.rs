@@ -4,7 +4,7 @@ use serde::Serialize;
 use crate::mcp::jsonrpc::id::Id;
 use crate::mcp::jsonrpc::meta::Meta;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct PingParams {
     #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
@@ -16,5 +16,7 @@ pub struct PingParams {
 pub struct Ping {
     pub id: Id,
     pub jsonrpc: String,
+    /// Clients usually send pings without params
+    #[serde(default)]
     pub params: PingParams,
 }
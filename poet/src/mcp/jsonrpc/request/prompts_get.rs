@@ -1,24 +1,83 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
 
+use crate::mcp::jsonrpc::JSONRPC_VERSION;
 use crate::mcp::jsonrpc::id::Id;
+use crate::mcp::jsonrpc::implementation::Implementation;
 use crate::mcp::jsonrpc::meta::Meta;
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct PromptsGetParams {
+    #[serde(default)]
     pub arguments: HashMap<String, String>,
     #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
     pub meta: Option<Meta>,
     pub name: String,
+    /// Replaces front matter props for this request only, see
+    /// `PromptDocumentFrontMatter::override_props`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub props: Option<BTreeMap<String, Value>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct PromptsGet {
+    /// Cancelled when the client gives up on the request
+    #[serde(skip)]
+    pub cancellation_token: CancellationToken,
+    /// Client that sent the request, known from the session it belongs to
+    #[serde(skip)]
+    pub client_info: Option<Implementation>,
     pub id: Id,
     pub jsonrpc: String,
     pub params: PromptsGetParams,
 }
+
+impl PromptsGet {
+    /// Request made by the server itself rather than a client, so nobody
+    /// can cancel it
+    pub fn internal(id: Id, name: &str, arguments: HashMap<String, String>) -> Self {
+        Self {
+            cancellation_token: Default::default(),
+            client_info: None,
+            id,
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            params: PromptsGetParams {
+                arguments,
+                meta: None,
+                name: name.to_string(),
+                props: None,
+            },
+        }
+    }
+
+    #[cfg(test)]
+    pub fn mock(name: &str, arguments: HashMap<String, String>) -> Self {
+        Self::internal(1.into(), name, arguments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+
+    #[test]
+    fn test_deserialize_without_arguments() -> Result<()> {
+        let prompts_get: PromptsGet = serde_json::from_str(
+            r#"{"id":1,"jsonrpc":"2.0","params":{"name":"help-me-finish-task"}}"#,
+        )?;
+
+        assert!(prompts_get.params.arguments.is_empty());
+        assert_eq!(prompts_get.params.name, "help-me-finish-task");
+
+        Ok(())
+    }
+}
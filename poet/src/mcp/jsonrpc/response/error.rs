@@ -1,16 +1,17 @@
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::mcp::MCP_SUPPORTED_PROTOCOL_VERSIONS;
 use crate::mcp::jsonrpc::JSONRPC_VERSION;
 use crate::mcp::jsonrpc::id::Id;
 
 const ERROR_INTERNAL_ERROR: i32 = -32603;
 const ERROR_INVALID_PARAMS: i32 = -32602;
 const ERROR_INVALID_REQUEST: i32 = -32600;
+const ERROR_METHOD_NOT_FOUND: i32 = -32601;
 const ERROR_PARSE_ERROR: i32 = -32700;
 const ERROR_RESOURCE_NOT_FOUND: i32 = -32002;
 
-// pub const ERROR_METHOD_NOT_FOUND: i32 = -32601;
 // pub const ERROR_SERVER_ERROR_RANGE_MIN: i32 = -32099;
 // pub const ERROR_SERVER_ERROR_RANGE_MAX: i32 = -32000;
 
@@ -26,97 +27,119 @@ pub struct ToolNotFound {
     tool_name: String,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct UnsupportedProtocolVersion {
+    requested: String,
+    supported: Vec<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields, untagged)]
-pub enum Error {
-    GenericMessage {
-        code: i32,
-        jsonrpc: String,
-        message: String,
-    },
-    RequestInternal {
-        code: i32,
-        id: Id,
-        jsonrpc: String,
-        message: String,
-    },
-    ResourceNotFound {
-        code: i32,
-        data: ResourceNotFound,
-        id: Id,
-        jsonrpc: String,
-        message: String,
-    },
-    ToolNotFound {
-        code: i32,
-        data: ToolNotFound,
-        id: Id,
-        jsonrpc: String,
-        message: String,
-    },
+pub enum ErrorData {
+    ResourceNotFound(ResourceNotFound),
+    ToolNotFound(ToolNotFound),
+    UnsupportedProtocolVersion(UnsupportedProtocolVersion),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ErrorObject {
+    pub code: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<ErrorData>,
+    pub message: String,
+}
+
+/// The id is null when the request could not be read far enough to tell
+/// which one failed
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Error {
+    pub error: ErrorObject,
+    pub id: Option<Id>,
+    pub jsonrpc: String,
 }
 
 impl Error {
-    pub fn invalid_prompt_name(id: Id, name: String) -> Self {
-        Self::RequestInternal {
-            code: ERROR_INVALID_PARAMS,
+    fn new(code: i32, data: Option<ErrorData>, id: Option<Id>, message: String) -> Self {
+        Self {
+            error: ErrorObject {
+                code,
+                data,
+                message,
+            },
             id,
             jsonrpc: JSONRPC_VERSION.to_string(),
-            message: format!("Invalid prompt name: {name}"),
         }
     }
 
+    pub fn invalid_prompt_name(id: Id, name: String) -> Self {
+        Self::new(
+            ERROR_INVALID_PARAMS,
+            None,
+            Some(id),
+            format!("Invalid prompt name: {name}"),
+        )
+    }
+
     pub fn invalid_params(id: Id, message: String) -> Self {
-        Self::RequestInternal {
-            code: ERROR_INVALID_PARAMS,
-            id,
-            jsonrpc: JSONRPC_VERSION.to_string(),
-            message,
-        }
+        Self::new(ERROR_INVALID_PARAMS, None, Some(id), message)
     }
 
     pub fn invalid_request(message: String) -> Self {
-        Self::GenericMessage {
-            code: ERROR_INVALID_REQUEST,
-            jsonrpc: JSONRPC_VERSION.to_string(),
-            message,
-        }
+        Self::new(ERROR_INVALID_REQUEST, None, None, message)
+    }
+
+    pub fn method_not_found(id: Id, method: &str) -> Self {
+        Self::new(
+            ERROR_METHOD_NOT_FOUND,
+            None,
+            Some(id),
+            format!("Method not found: {method}"),
+        )
     }
 
     pub fn parse(message: String) -> Self {
-        Self::GenericMessage {
-            code: ERROR_PARSE_ERROR,
-            jsonrpc: JSONRPC_VERSION.to_string(),
-            message,
-        }
+        Self::new(ERROR_PARSE_ERROR, None, None, message)
     }
 
     pub fn request_internal(id: Id, message: String) -> Self {
-        Self::RequestInternal {
-            code: ERROR_INTERNAL_ERROR,
-            id,
-            jsonrpc: JSONRPC_VERSION.to_string(),
-            message,
-        }
+        Self::new(ERROR_INTERNAL_ERROR, None, Some(id), message)
     }
 
     pub fn resource_not_found(id: Id, uri: String) -> Self {
-        Self::ResourceNotFound {
-            code: ERROR_RESOURCE_NOT_FOUND,
-            data: ResourceNotFound { uri },
-            id,
-            jsonrpc: JSONRPC_VERSION.to_string(),
-            message: "Resource not found".to_string(),
-        }
+        Self::new(
+            ERROR_RESOURCE_NOT_FOUND,
+            Some(ErrorData::ResourceNotFound(ResourceNotFound { uri })),
+            Some(id),
+            "Resource not found".to_string(),
+        )
     }
 
     pub fn tool_not_found(id: Id, tool_name: String) -> Self {
-        Self::ToolNotFound {
-            code: ERROR_RESOURCE_NOT_FOUND,
-            data: ToolNotFound { tool_name },
-            id,
-            jsonrpc: JSONRPC_VERSION.to_string(),
-            message: "Tool not found".to_string(),
-        }
+        Self::new(
+            ERROR_RESOURCE_NOT_FOUND,
+            Some(ErrorData::ToolNotFound(ToolNotFound { tool_name })),
+            Some(id),
+            "Tool not found".to_string(),
+        )
+    }
+
+    pub fn unsupported_protocol_version(id: Id, requested: String) -> Self {
+        Self::new(
+            ERROR_INVALID_PARAMS,
+            Some(ErrorData::UnsupportedProtocolVersion(
+                UnsupportedProtocolVersion {
+                    requested,
+                    supported: MCP_SUPPORTED_PROTOCOL_VERSIONS
+                        .iter()
+                        .map(|version| version.to_string())
+                        .collect(),
+                },
+            )),
+            Some(id),
+            "Unsupported protocol version".to_string(),
+        )
     }
 }
@@ -0,0 +1,36 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The most values a single completion response may contain
+pub const COMPLETION_VALUES_LIMIT: usize = 100;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Completion {
+    #[serde(rename = "hasMore")]
+    pub has_more: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    pub values: Vec<String>,
+}
+
+/// Keeps the first values up to the limit, and reports how many matched
+impl From<Vec<String>> for Completion {
+    fn from(mut values: Vec<String>) -> Self {
+        let total = values.len();
+
+        values.truncate(COMPLETION_VALUES_LIMIT);
+
+        Self {
+            has_more: total > COMPLETION_VALUES_LIMIT,
+            total: Some(total),
+            values,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompletionComplete {
+    pub completion: Completion,
+}
@@ -1,3 +1,4 @@
+pub mod completion_complete;
 pub mod empty_response;
 pub mod initialize_result;
 pub mod prompts_get_result;
@@ -1,15 +1,114 @@
+use std::collections::BTreeMap;
+
 use serde::Deserialize;
 use serde::Serialize;
+use serde::Serializer;
+use serde::ser::Error as _;
+use serde_json::Map;
+use serde_json::Value;
 
-use crate::mcp::jsonrpc::meta::Meta;
 use crate::mcp::prompt_message::PromptMessage;
+use crate::prompt_provenance::PromptProvenance;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PromptsGetResultMeta {
+    /// Result-level hints from the front matter and `<ResultMeta />`
+    /// components, like a suggested model. Serialized next to the other
+    /// fields, which take precedence over hints with the same name.
+    #[serde(skip)]
+    pub annotations: BTreeMap<String, Value>,
+    #[serde(default, rename = "assistantPrefill")]
+    pub assistant_prefill: Option<bool>,
+    #[serde(default)]
+    pub checksum: Option<String>,
+    /// Only included when the server is configured to expose it, since it
+    /// reveals source paths
+    #[serde(default)]
+    pub provenance: Option<PromptProvenance>,
+}
+
+impl PromptsGetResultMeta {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Serialize for PromptsGetResultMeta {
+    fn serialize<TSerializer: Serializer>(
+        &self,
+        serializer: TSerializer,
+    ) -> Result<TSerializer::Ok, TSerializer::Error> {
+        let mut entries: Map<String, Value> = self
+            .annotations
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        if let Some(assistant_prefill) = self.assistant_prefill {
+            entries.insert("assistantPrefill".to_string(), assistant_prefill.into());
+        }
+
+        if let Some(checksum) = &self.checksum {
+            entries.insert("checksum".to_string(), checksum.clone().into());
+        }
+
+        if let Some(provenance) = &self.provenance {
+            entries.insert(
+                "provenance".to_string(),
+                serde_json::to_value(provenance).map_err(TSerializer::Error::custom)?,
+            );
+        }
+
+        entries.serialize(serializer)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct PromptsGetResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub messages: Vec<PromptMessage>,
     #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
-    pub meta: Option<Meta>,
+    pub meta: Option<PromptsGetResultMeta>,
+}
+
+impl PromptsGetResult {
+    pub fn checksum(&self) -> Option<&str> {
+        self.meta.as_ref().and_then(|meta| meta.checksum.as_deref())
+    }
+
+    pub fn is_assistant_prefill(&self) -> bool {
+        self.meta
+            .as_ref()
+            .and_then(|meta| meta.assistant_prefill)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+
+    #[test]
+    fn test_fields_take_precedence_over_annotations() -> Result<()> {
+        let meta = PromptsGetResultMeta {
+            annotations: BTreeMap::from([
+                ("checksum".to_string(), "annotated".into()),
+                ("model".to_string(), "fast".into()),
+            ]),
+            checksum: Some("computed".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            serde_json::to_string(&meta)?,
+            r#"{"checksum":"computed","model":"fast"}"#
+        );
+
+        Ok(())
+    }
 }
@@ -1,9 +1,12 @@
 use anyhow::Result;
 use anyhow::anyhow;
 use serde::Deserialize;
+use serde::Deserializer;
 use serde::Serialize;
+use serde::de::Error as _;
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+/// Always serialized in lowercase, as the MCP specification requires
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 pub enum Role {
     #[serde(rename = "assistant")]
     Assistant,
@@ -11,6 +14,21 @@ pub enum Role {
     User,
 }
 
+/// Lenient clients sometimes send capitalized roles, so any casing is accepted
+impl<'de> Deserialize<'de> for Role {
+    fn deserialize<TDeserializer: Deserializer<'de>>(
+        deserializer: TDeserializer,
+    ) -> Result<Self, TDeserializer::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        value
+            .to_lowercase()
+            .as_str()
+            .try_into()
+            .map_err(TDeserializer::Error::custom)
+    }
+}
+
 impl TryFrom<&str> for Role {
     type Error = anyhow::Error;
 
@@ -30,3 +48,22 @@ impl TryFrom<String> for Role {
         value.as_str().try_into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_is_deserialized_case_insensitively() -> Result<()> {
+        assert_eq!(serde_json::from_str::<Role>(r#""User""#)?, Role::User);
+        assert_eq!(
+            serde_json::from_str::<Role>(r#""ASSISTANT""#)?,
+            Role::Assistant
+        );
+        assert_eq!(serde_json::to_string(&Role::User)?, r#""user""#);
+        assert!(serde_json::from_str::<Role>(r#""system""#).is_err());
+        assert!(serde_json::from_str::<Role>(r#""moderator""#).is_err());
+
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::mcp::jsonrpc::notification::message::Message;
+use crate::mcp::jsonrpc::notification::prompts_list_changed::PromptsListChanged;
 use crate::mcp::jsonrpc::notification::resources_list_changed::ResourcesListChanged;
 use crate::mcp::jsonrpc::notification::resources_updated::ResourcesUpdated;
 
@@ -10,6 +11,8 @@ use crate::mcp::jsonrpc::notification::resources_updated::ResourcesUpdated;
 pub enum ServerToClientNotification {
     #[serde(rename = "notifications/message")]
     Message(Message),
+    #[serde(rename = "notifications/prompts/list_changed")]
+    PromptsListChanged(PromptsListChanged),
     #[serde(rename = "notifications/resources/list_changed")]
     ResourcesListChanged(ResourcesListChanged),
     #[serde(rename = "notifications/resources/updated")]
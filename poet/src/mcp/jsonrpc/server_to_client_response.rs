@@ -4,6 +4,7 @@ use serde_json::Value;
 
 use crate::mcp::jsonrpc::response::error::Error;
 use crate::mcp::jsonrpc::response::success::Success;
+use crate::mcp::jsonrpc::response::success::completion_complete::CompletionComplete;
 use crate::mcp::jsonrpc::response::success::empty_response::EmptyResponse;
 use crate::mcp::jsonrpc::response::success::initialize_result::InitializeResult;
 use crate::mcp::jsonrpc::response::success::prompts_get_result::PromptsGetResult;
@@ -17,6 +18,7 @@ use crate::mcp::jsonrpc::response::success::tools_list::ToolsList;
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields, untagged)]
 pub enum ServerToClientResponse {
+    CompletionComplete(Success<CompletionComplete>),
     EmptyResponse(Success<EmptyResponse>),
     Error(Error),
     InitializeResult(Success<InitializeResult>),
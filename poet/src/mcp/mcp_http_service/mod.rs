@@ -31,6 +31,7 @@ use crate::prompt_controller_collection_holder::PromptControllerCollectionHolder
 
 pub struct McpHttpService {
     pub prompt_controller_collection_holder: PromptControllerCollectionHolder,
+    pub prompts_list_changed: bool,
     pub resource_list_aggregate: Arc<ResourceListAggregate>,
     pub server_info: Implementation,
     pub session_manager: SessionManager,
@@ -46,6 +47,7 @@ impl Service<ServiceRequest> for McpHttpService {
 
     fn call(&self, mut req: ServiceRequest) -> Self::Future {
         let prompt_controller_collection_holder = self.prompt_controller_collection_holder.clone();
+        let prompts_list_changed = self.prompts_list_changed;
         let req_method = req.method().clone();
         let resource_list_aggregate = self.resource_list_aggregate.clone();
         let server_info = self.server_info.clone();
@@ -66,6 +68,7 @@ impl Service<ServiceRequest> for McpHttpService {
                 Method::POST => {
                     McpResponderHandler(RespondToPost {
                         prompt_controller_collection_holder,
+                        prompts_list_changed,
                         resource_list_aggregate,
                         server_info,
                         session_manager,
@@ -83,3 +86,416 @@ impl Service<ServiceRequest> for McpHttpService {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::future::poll_fn;
+    use std::pin::pin;
+
+    use actix_web::App;
+    use actix_web::body::MessageBody as _;
+    use actix_web::test::TestRequest;
+    use actix_web::test::call_service;
+    use actix_web::test::init_service;
+    use actix_web::test::read_body_json;
+    use anyhow::Result;
+    use anyhow::anyhow;
+    use async_trait::async_trait;
+    use indoc::indoc;
+    use serde_json::Value;
+    use serde_json::json;
+
+    use super::*;
+    use crate::holder::Holder as _;
+    use crate::mcp::MCP_HEADER_PROTOCOL_VERSION;
+    use crate::mcp::MCP_HEADER_SESSION;
+    use crate::mcp::MCP_PROTOCOL_VERSION;
+    use crate::mcp::MCP_SUPPORTED_PROTOCOL_VERSIONS;
+    use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
+    use crate::mcp::jsonrpc::response::success::prompts_get_result::PromptsGetResult;
+    use crate::mcp::mcp_http_service_factory::McpHttpServiceFactory;
+    use crate::mcp::prompt::Prompt;
+    use crate::mcp::prompt_controller::PromptController;
+    use crate::mcp::resource_provider::ResourceProvider;
+    use crate::prompt_document_controller::PromptDocumentController;
+    use crate::prompt_render_cancelled::PromptRenderCancelled;
+
+    const MCP_ACCEPT: &str = "application/json, text/event-stream";
+
+    async fn mcp_http_service_factory() -> Result<McpHttpServiceFactory> {
+        let prompt_controller: Arc<dyn PromptController> =
+            Arc::new(PromptDocumentController::mock(
+                "greeting",
+                indoc! {r#"
+                +++
+                description = "Greeting"
+                title = "Greeting"
+
+                [arguments]
+                +++
+
+                **user**: Hello over HTTP
+                "#},
+            )?);
+        let prompt_controller_collection_holder: PromptControllerCollectionHolder =
+            Default::default();
+        let resource_list_providers: Vec<Arc<dyn ResourceProvider>> = vec![];
+
+        prompt_controller_collection_holder
+            .set(Some(Arc::new(
+                [("greeting".to_string(), prompt_controller)]
+                    .into_iter()
+                    .collect(),
+            )))
+            .await;
+
+        Ok(McpHttpServiceFactory {
+            mount_path: "/mcp/streamable".to_string(),
+            prompt_controller_collection_holder,
+            prompts_list_changed: false,
+            resource_list_aggregate: Arc::new(resource_list_providers.into()),
+            server_info: Implementation {
+                description: None,
+                name: "poet".to_string(),
+                title: None,
+                version: "1.0.0".to_string(),
+            },
+            session_manager: Default::default(),
+            tool_registry: Default::default(),
+        })
+    }
+
+    #[actix_web::test]
+    async fn test_ping_before_initialize_returns_an_empty_result() -> Result<()> {
+        let app = init_service(App::new().service(mcp_http_service_factory().await?)).await;
+        let response = call_service(
+            &app,
+            TestRequest::post()
+                .uri("/mcp/streamable")
+                .insert_header((header::ACCEPT, MCP_ACCEPT))
+                .insert_header((MCP_HEADER_PROTOCOL_VERSION, MCP_PROTOCOL_VERSION))
+                .set_json(json!({ "id": "liveness", "jsonrpc": "2.0", "method": "ping" }))
+                .to_request(),
+        )
+        .await;
+
+        assert!(response.status().is_success());
+
+        let pong: Value = read_body_json(response).await;
+
+        assert_eq!(
+            pong,
+            json!({ "id": "liveness", "jsonrpc": "2.0", "result": {} })
+        );
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn test_prompts_get_over_http() -> Result<()> {
+        let app = init_service(App::new().service(mcp_http_service_factory().await?)).await;
+
+        let initialize_response = call_service(
+            &app,
+            TestRequest::post()
+                .uri("/mcp/streamable")
+                .insert_header((header::ACCEPT, MCP_ACCEPT))
+                .set_json(json!({
+                    "id": 1,
+                    "jsonrpc": "2.0",
+                    "method": "initialize",
+                    "params": {
+                        "capabilities": {},
+                        "clientInfo": { "name": "test-client", "version": "1.0.0" },
+                        "protocolVersion": MCP_PROTOCOL_VERSION,
+                    },
+                }))
+                .to_request(),
+        )
+        .await;
+
+        assert!(initialize_response.status().is_success());
+
+        let Some(session_id) = initialize_response.headers().get(MCP_HEADER_SESSION) else {
+            return Err(anyhow!("Initialize response has no session header"));
+        };
+
+        let prompts_get_response: Value = read_body_json(
+            call_service(
+                &app,
+                TestRequest::post()
+                    .uri("/mcp/streamable")
+                    .insert_header((header::ACCEPT, MCP_ACCEPT))
+                    .insert_header((MCP_HEADER_PROTOCOL_VERSION, MCP_PROTOCOL_VERSION))
+                    .insert_header((MCP_HEADER_SESSION, session_id.clone()))
+                    .set_json(json!({
+                        "id": 2,
+                        "jsonrpc": "2.0",
+                        "method": "prompts/get",
+                        "params": { "name": "greeting" },
+                    }))
+                    .to_request(),
+            )
+            .await,
+        )
+        .await;
+
+        assert_eq!(prompts_get_response["id"], 2);
+        assert_eq!(
+            prompts_get_response["result"]["messages"],
+            json!([{
+                "content": { "text": "Hello over HTTP", "type": "text" },
+                "role": "user",
+            }])
+        );
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn test_malformed_json_is_a_parse_error() -> Result<()> {
+        let app = init_service(App::new().service(mcp_http_service_factory().await?)).await;
+        let response = call_service(
+            &app,
+            TestRequest::post()
+                .uri("/mcp/streamable")
+                .insert_header((header::ACCEPT, MCP_ACCEPT))
+                .insert_header((header::CONTENT_TYPE, "application/json"))
+                .set_payload("{\"jsonrpc\": ")
+                .to_request(),
+        )
+        .await;
+
+        assert_eq!(response.status(), 400);
+
+        let error: Value = read_body_json(response).await;
+
+        assert_eq!(error["error"]["code"], -32700);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn test_initialize_negotiates_a_supported_version() -> Result<()> {
+        let mcp_http_service_factory = mcp_http_service_factory().await?;
+        let session_manager = mcp_http_service_factory.session_manager.clone();
+        let app = init_service(App::new().service(mcp_http_service_factory)).await;
+        let initialize_response = call_service(
+            &app,
+            TestRequest::post()
+                .uri("/mcp/streamable")
+                .insert_header((header::ACCEPT, MCP_ACCEPT))
+                .set_json(json!({
+                    "id": 1,
+                    "jsonrpc": "2.0",
+                    "method": "initialize",
+                    "params": {
+                        "capabilities": {},
+                        "clientInfo": { "name": "test-client", "version": "1.0.0" },
+                        "protocolVersion": "2025-06-18",
+                    },
+                }))
+                .to_request(),
+        )
+        .await;
+
+        let Some(session_id) = initialize_response
+            .headers()
+            .get(MCP_HEADER_SESSION)
+            .cloned()
+        else {
+            return Err(anyhow!("Initialize response has no session header"));
+        };
+        let Some(session) = session_manager
+            .session_storage
+            .read(session_id.to_str()?)
+            .await
+            .map_err(|err| anyhow!("{err}"))?
+        else {
+            return Err(anyhow!("Session was not stored"));
+        };
+
+        assert_eq!(session.protocol_version(), "2025-06-18");
+
+        let mut body = pin!(initialize_response.into_body());
+        let Some(Ok(first_event)) = poll_fn(|cx| body.as_mut().poll_next(cx)).await else {
+            return Err(anyhow!("Initialize response has no confirmation event"));
+        };
+        let Some(confirmation) = str::from_utf8(&first_event)?.strip_prefix("data: ") else {
+            return Err(anyhow!("Confirmation is not an SSE data event"));
+        };
+        let confirmation: Value = serde_json::from_str(confirmation.trim_end())?;
+
+        assert_eq!(confirmation["result"]["protocolVersion"], "2025-06-18");
+        assert_eq!(
+            confirmation["result"]["capabilities"]["prompts"]["listChanged"],
+            false
+        );
+
+        let prompts_list_response = call_service(
+            &app,
+            TestRequest::post()
+                .uri("/mcp/streamable")
+                .insert_header((header::ACCEPT, MCP_ACCEPT))
+                .insert_header((MCP_HEADER_PROTOCOL_VERSION, "2025-06-18"))
+                .insert_header((MCP_HEADER_SESSION, session_id))
+                .set_json(json!({
+                    "id": 2,
+                    "jsonrpc": "2.0",
+                    "method": "prompts/list",
+                    "params": {},
+                }))
+                .to_request(),
+        )
+        .await;
+
+        assert!(prompts_list_response.status().is_success());
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn test_initialize_rejects_an_unsupported_version() -> Result<()> {
+        let app = init_service(App::new().service(mcp_http_service_factory().await?)).await;
+        let response = call_service(
+            &app,
+            TestRequest::post()
+                .uri("/mcp/streamable")
+                .insert_header((header::ACCEPT, MCP_ACCEPT))
+                .set_json(json!({
+                    "id": 1,
+                    "jsonrpc": "2.0",
+                    "method": "initialize",
+                    "params": {
+                        "capabilities": {},
+                        "clientInfo": { "name": "test-client", "version": "1.0.0" },
+                        "protocolVersion": "2024-11-05",
+                    },
+                }))
+                .to_request(),
+        )
+        .await;
+
+        assert!(response.headers().get(MCP_HEADER_SESSION).is_none());
+
+        let error: Value = read_body_json(response).await;
+
+        assert_eq!(error["id"], 1);
+        assert_eq!(error["error"]["code"], -32602);
+        assert_eq!(error["error"]["data"]["requested"], "2024-11-05");
+        assert_eq!(
+            error["error"]["data"]["supported"],
+            json!(MCP_SUPPORTED_PROTOCOL_VERSIONS)
+        );
+
+        Ok(())
+    }
+
+    #[derive(Clone)]
+    struct CancellablePromptController;
+
+    #[async_trait]
+    impl PromptController for CancellablePromptController {
+        fn clone_box(&self) -> Box<dyn PromptController> {
+            Box::new(self.clone())
+        }
+
+        fn get_mcp_prompt(&self) -> Prompt {
+            Prompt {
+                arguments: Default::default(),
+                description: "Renders until it is cancelled".to_string(),
+                meta: None,
+                name: "cancellable".to_string(),
+                title: "Cancellable".to_string(),
+            }
+        }
+
+        async fn respond_to(&self, request: PromptsGet) -> Result<PromptsGetResult> {
+            request.cancellation_token.cancelled().await;
+
+            Err(PromptRenderCancelled {
+                prompt_name: request.params.name,
+            }
+            .into())
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_cancelled_prompts_get_has_no_result() -> Result<()> {
+        let mcp_http_service_factory = mcp_http_service_factory().await?;
+        let prompt_controller: Arc<dyn PromptController> = Arc::new(CancellablePromptController);
+
+        mcp_http_service_factory
+            .prompt_controller_collection_holder
+            .set(Some(Arc::new(
+                [("cancellable".to_string(), prompt_controller)]
+                    .into_iter()
+                    .collect(),
+            )))
+            .await;
+
+        let app = init_service(App::new().service(mcp_http_service_factory)).await;
+        let initialize_response = call_service(
+            &app,
+            TestRequest::post()
+                .uri("/mcp/streamable")
+                .insert_header((header::ACCEPT, MCP_ACCEPT))
+                .set_json(json!({
+                    "id": 1,
+                    "jsonrpc": "2.0",
+                    "method": "initialize",
+                    "params": {
+                        "capabilities": {},
+                        "clientInfo": { "name": "test-client", "version": "1.0.0" },
+                        "protocolVersion": MCP_PROTOCOL_VERSION,
+                    },
+                }))
+                .to_request(),
+        )
+        .await;
+
+        let Some(session_id) = initialize_response.headers().get(MCP_HEADER_SESSION) else {
+            return Err(anyhow!("Initialize response has no session header"));
+        };
+        let post = |message: Value| {
+            TestRequest::post()
+                .uri("/mcp/streamable")
+                .insert_header((header::ACCEPT, MCP_ACCEPT))
+                .insert_header((MCP_HEADER_PROTOCOL_VERSION, MCP_PROTOCOL_VERSION))
+                .insert_header((MCP_HEADER_SESSION, session_id.clone()))
+                .set_json(message)
+                .to_request()
+        };
+
+        let (prompts_get_response, cancelled_response) = tokio::join!(
+            call_service(
+                &app,
+                post(json!({
+                    "id": 2,
+                    "jsonrpc": "2.0",
+                    "method": "prompts/get",
+                    "params": { "name": "cancellable" },
+                })),
+            ),
+            call_service(
+                &app,
+                post(json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/cancelled",
+                    "params": { "reason": "No longer needed", "requestId": 2 },
+                })),
+            ),
+        );
+
+        assert_eq!(cancelled_response.status(), 202);
+        assert_eq!(prompts_get_response.status(), 202);
+        assert!(
+            prompts_get_response
+                .into_body()
+                .try_into_bytes()
+                .is_ok_and(|body| body.is_empty())
+        );
+
+        Ok(())
+    }
+}
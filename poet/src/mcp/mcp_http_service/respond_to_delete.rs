@@ -1,6 +1,7 @@
 use actix_web::HttpResponse;
 use actix_web::Result;
 use actix_web::body::BoxBody;
+use actix_web::error::ErrorInternalServerError;
 use async_trait::async_trait;
 use mime::Mime;
 
@@ -33,7 +34,10 @@ impl McpResponder for RespondToDelete {
 
         match session {
             Some(session) => {
-                session_manager.terminate_session(session).await?;
+                session_manager
+                    .terminate_session(session)
+                    .await
+                    .map_err(ErrorInternalServerError)?;
 
                 Ok(HttpResponse::Accepted().into())
             }
@@ -1,43 +1,44 @@
-mod handler;
-
 use std::sync::Arc;
+use std::time::Duration;
 
 use actix_web::FromRequest as _;
 use actix_web::HttpResponse;
 use actix_web::Result;
 use actix_web::body::BoxBody;
+use actix_web::error::ErrorInternalServerError;
+use actix_web::web::Bytes;
 use async_trait::async_trait;
+use futures_core::stream::Stream;
 use log::error;
 use mime::Mime;
+use tokio::sync::mpsc::Receiver;
+use tokio::time::interval;
 
+use crate::mcp::MCP_HEADER_SESSION;
 use crate::mcp::MCP_PROTOCOL_VERSION;
+use crate::mcp::client_to_server_message_dispatcher::ClientToServerMessageDispatcher;
+use crate::mcp::handler::Handler as _;
+use crate::mcp::handler::initialize_handler::InitializeHandler;
+use crate::mcp::handler::ping_handler::PingHandler;
 use crate::mcp::jsonrpc::client_to_server_message::ClientToServerMessage;
 use crate::mcp::jsonrpc::implementation::Implementation;
+use crate::mcp::jsonrpc::request::initialize::Initialize;
 use crate::mcp::jsonrpc::response::error::Error;
-use crate::mcp::mcp_http_service::respond_to_post::handler::Handler as _;
-use crate::mcp::mcp_http_service::respond_to_post::handler::initialize_handler::InitializeHandler;
-use crate::mcp::mcp_http_service::respond_to_post::handler::initialized_handler::InitializedHandler;
-use crate::mcp::mcp_http_service::respond_to_post::handler::logging_set_level_handler::LoggingSetLevelHandler;
-use crate::mcp::mcp_http_service::respond_to_post::handler::ping_handler::PingHandler;
-use crate::mcp::mcp_http_service::respond_to_post::handler::prompts_get_handler::PromptsGetHandler;
-use crate::mcp::mcp_http_service::respond_to_post::handler::prompts_list_handler::PromptsListHandler;
-use crate::mcp::mcp_http_service::respond_to_post::handler::resources_list_handler::ResourcesListHandler;
-use crate::mcp::mcp_http_service::respond_to_post::handler::resources_read_handler::ResourcesReadHandler;
-use crate::mcp::mcp_http_service::respond_to_post::handler::resources_subscribe_handler::ResourcesSubscribeHandler;
-use crate::mcp::mcp_http_service::respond_to_post::handler::resources_templates_list_handler::ResourcesTemplatesListHandler;
-use crate::mcp::mcp_http_service::respond_to_post::handler::resources_unsubscribe_handler::ResourcesUnsubscribeHandler;
-use crate::mcp::mcp_http_service::respond_to_post::handler::tools_call_handler::ToolsCallHandler;
-use crate::mcp::mcp_http_service::respond_to_post::handler::tools_list_handler::ToolsListHandler;
+use crate::mcp::jsonrpc::server_to_client_notification::ServerToClientNotification;
+use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
 use crate::mcp::mcp_responder::McpResponder;
 use crate::mcp::mcp_responder_context::McpResponderContext;
 use crate::mcp::resource_list_aggregate::ResourceListAggregate;
+use crate::mcp::session::Session;
 use crate::mcp::session_manager::SessionManager;
+use crate::mcp::session_with_notifications_receiver::SessionWithNotificationsReceiver;
 use crate::mcp::tool_registry::ToolRegistry;
 use crate::prompt_controller_collection_holder::PromptControllerCollectionHolder;
 
 #[derive(Clone)]
 pub struct RespondToPost {
     pub prompt_controller_collection_holder: PromptControllerCollectionHolder,
+    pub prompts_list_changed: bool,
     pub resource_list_aggregate: Arc<ResourceListAggregate>,
     pub server_info: Implementation,
     pub session_manager: SessionManager,
@@ -84,113 +85,142 @@ impl McpResponder for RespondToPost {
         match client_to_server_message {
             ClientToServerMessage::Initialize(_) => {}
             _ => {
-                self.assert_protocol_version_header(&req, MCP_PROTOCOL_VERSION)?;
+                let protocol_version = session
+                    .as_ref()
+                    .map(Session::protocol_version)
+                    .unwrap_or_else(|| MCP_PROTOCOL_VERSION.to_string());
+
+                self.assert_protocol_version_header(&req, &protocol_version)?;
             }
         }
 
-        match client_to_server_message {
+        let (response, session) = match client_to_server_message {
             ClientToServerMessage::Initialize(request) => {
                 self.assert_no_session(&session)?;
 
-                InitializeHandler {
-                    server_info: self.server_info,
-                    session_manager: self.session_manager,
-                }
-                .handle(request, ())
-                .await
-            }
-            ClientToServerMessage::Initialized(request) => {
-                let session = self.assert_session(&session)?;
-
-                InitializedHandler {}.handle(request, session).await
+                return self.initialize(request).await;
             }
-            ClientToServerMessage::LoggingSetLevel(request) => {
-                let session = self.assert_session(&session)?;
-
-                LoggingSetLevelHandler { session_manager }
-                    .handle(request, session)
+            ClientToServerMessage::Ping(request) => (
+                PingHandler {}
+                    .handle(request, ())
                     .await
-            }
-            ClientToServerMessage::Ping(request) => PingHandler {}.handle(request, ()).await,
-            ClientToServerMessage::PromptsGet(request) => {
+                    .map_err(ErrorInternalServerError)?,
+                session,
+            ),
+            client_to_server_message => {
                 let session = self.assert_session(&session)?;
 
-                PromptsGetHandler {
-                    prompt_controller_collection_holder: self.prompt_controller_collection_holder,
-                }
-                .handle(request, session)
-                .await
+                (
+                    ClientToServerMessageDispatcher {
+                        prompt_controller_collection_holder: self
+                            .prompt_controller_collection_holder,
+                        resource_list_aggregate: self.resource_list_aggregate,
+                        session_manager,
+                        tool_registry: self.tool_registry,
+                    }
+                    .dispatch(client_to_server_message, session.clone())
+                    .await
+                    .map_err(ErrorInternalServerError)?,
+                    Some(session),
+                )
             }
-            ClientToServerMessage::PromptsList(request) => {
-                let session = self.assert_session(&session)?;
+        };
 
-                PromptsListHandler {
-                    prompt_controller_collection_holder: self.prompt_controller_collection_holder,
-                }
-                .handle(request, session)
-                .await
-            }
-            ClientToServerMessage::ResourcesList(request) => {
-                let session = self.assert_session(&session)?;
+        let mut http_response = match response {
+            Some(_) => HttpResponse::Ok(),
+            None => HttpResponse::Accepted(),
+        };
 
-                ResourcesListHandler {
-                    resource_list_aggregate: self.resource_list_aggregate,
-                }
-                .handle(request, session)
-                .await
-            }
-            ClientToServerMessage::ResourcesRead(request) => {
-                let session = self.assert_session(&session)?;
+        if let Some(session) = session {
+            http_response.insert_header((MCP_HEADER_SESSION, session.id()));
+        }
 
-                ResourcesReadHandler {
-                    resource_list_aggregate: self.resource_list_aggregate,
-                }
-                .handle(request, session)
-                .await
-            }
-            ClientToServerMessage::ResourcesSubscribe(request) => {
-                let session = self.assert_session(&session)?;
+        Ok(match response {
+            Some(response) => http_response.json(response),
+            None => http_response.finish(),
+        })
+    }
+}
 
-                ResourcesSubscribeHandler {
-                    resource_list_aggregate: self.resource_list_aggregate,
-                }
-                .handle(request, session)
-                .await
-            }
-            ClientToServerMessage::ResourcesTemplatesList(request) => {
-                let session = self.assert_session(&session)?;
+impl RespondToPost {
+    async fn initialize(self, request: Initialize) -> Result<HttpResponse<BoxBody>> {
+        let session_manager = self.session_manager.clone();
+        let (confirmation, session_with_notifications_receiver) = InitializeHandler {
+            prompts_list_changed: self.prompts_list_changed,
+            server_info: self.server_info,
+            session_manager: self.session_manager,
+        }
+        .handle(request)
+        .await
+        .map_err(ErrorInternalServerError)?;
 
-                ResourcesTemplatesListHandler {
-                    resource_list_aggregate: self.resource_list_aggregate,
-                }
-                .handle(request, session)
-                .await
-            }
-            ClientToServerMessage::ResourcesUnsubscribe(request) => {
-                let session = self.assert_session(&session)?;
+        let Some(SessionWithNotificationsReceiver {
+            notification_rx,
+            session,
+        }) = session_with_notifications_receiver
+        else {
+            return Ok(HttpResponse::Ok().json(confirmation));
+        };
+
+        Ok(HttpResponse::Ok()
+            .content_type(mime::TEXT_EVENT_STREAM)
+            .insert_header((MCP_HEADER_SESSION, session.id()))
+            .streaming(notifications_stream(
+                confirmation,
+                notification_rx,
+                session,
+                session_manager,
+            )))
+    }
+}
 
-                ResourcesUnsubscribeHandler {}
-                    .handle(request, session)
-                    .await
+fn notifications_stream(
+    confirmation: ServerToClientResponse,
+    mut notification_rx: Receiver<ServerToClientNotification>,
+    session: Session,
+    session_manager: SessionManager,
+) -> impl Stream<Item = Result<Bytes>> {
+    async_stream::try_stream! {
+        match serde_json::to_string(&confirmation) {
+            Ok(confirmation_serialized) => {
+                yield Bytes::from(format!("data: {confirmation_serialized}\n\n"));
+            },
+            Err(err) => {
+                error!("Unable to serialize initialize confirmation: {err:#?}");
+
+                return;
             }
-            ClientToServerMessage::ToolsCall(request) => {
-                let session = self.assert_session(&session)?;
+        }
 
-                ToolsCallHandler {
-                    tool_registry: self.tool_registry,
+        let mut ticker = interval(Duration::from_secs(1));
+
+        loop {
+            tokio::select! {
+                resource_content_parts = notification_rx.recv() => {
+                    match resource_content_parts {
+                        Some(resource_content_parts) => {
+                            match serde_json::to_string(&resource_content_parts) {
+                                Ok(serialized) => yield Bytes::from(format!("data: {serialized}\n\n")),
+                                Err(err) => {
+                                    error!("{err}");
+
+                                    yield Bytes::from(": server-error\n\n");
+                                }
+                            }
+                        }
+                        None => break,
+                    }
                 }
-                .handle(request, session)
-                .await
-            }
-            ClientToServerMessage::ToolsList(request) => {
-                let session = self.assert_session(&session)?;
-
-                ToolsListHandler {
-                    tool_registry: self.tool_registry,
+                _ = ticker.tick() => {
+                    yield Bytes::from(": keep-alive\n\n");
                 }
-                .handle(request, session)
-                .await
             }
         }
+
+        notification_rx.close();
+
+        if let Err(err) = session_manager.terminate_session(session).await {
+            error!("Unable to terminate session: {err:#?}");
+        }
     }
 }
@@ -20,6 +20,7 @@ use crate::prompt_controller_collection_holder::PromptControllerCollectionHolder
 pub struct McpHttpServiceFactory {
     pub mount_path: String,
     pub prompt_controller_collection_holder: PromptControllerCollectionHolder,
+    pub prompts_list_changed: bool,
     pub resource_list_aggregate: Arc<ResourceListAggregate>,
     pub server_info: Implementation,
     pub session_manager: SessionManager,
@@ -36,6 +37,7 @@ impl ServiceFactory<ServiceRequest> for McpHttpServiceFactory {
 
     fn new_service(&self, _: Self::Config) -> Self::Future {
         let prompt_controller_collection_holder = self.prompt_controller_collection_holder.clone();
+        let prompts_list_changed = self.prompts_list_changed;
         let resource_list_aggregate = self.resource_list_aggregate.clone();
         let server_info = self.server_info.clone();
         let session_manager = self.session_manager.clone();
@@ -44,6 +46,7 @@ impl ServiceFactory<ServiceRequest> for McpHttpServiceFactory {
         Box::pin(async move {
             Ok(McpHttpService {
                 prompt_controller_collection_holder,
+                prompts_list_changed,
                 resource_list_aggregate,
                 server_info,
                 session_manager,
@@ -0,0 +1,437 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use log::error;
+use log::warn;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::io::AsyncBufRead;
+use tokio::io::AsyncBufReadExt as _;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt as _;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::Sender;
+use tokio::task::JoinSet;
+
+use crate::mcp::client_to_server_message_dispatcher::ClientToServerMessageDispatcher;
+use crate::mcp::handler::initialize_handler::InitializeHandler;
+use crate::mcp::jsonrpc::client_to_server_message::ClientToServerMessage;
+use crate::mcp::jsonrpc::id::Id;
+use crate::mcp::jsonrpc::implementation::Implementation;
+use crate::mcp::jsonrpc::response::error::Error;
+use crate::mcp::jsonrpc::server_to_client_response::ServerToClientResponse;
+use crate::mcp::resource_list_aggregate::ResourceListAggregate;
+use crate::mcp::session::Session;
+use crate::mcp::session_manager::SessionManager;
+use crate::mcp::session_with_notifications_receiver::SessionWithNotificationsReceiver;
+use crate::mcp::tool_registry::ToolRegistry;
+use crate::prompt_controller_collection_holder::PromptControllerCollectionHolder;
+
+const SUPPORTED_METHODS: [&str; 15] = [
+    "completion/complete",
+    "initialize",
+    "logging/setLevel",
+    "notifications/cancelled",
+    "notifications/initialized",
+    "ping",
+    "prompts/get",
+    "prompts/list",
+    "resources/list",
+    "resources/read",
+    "resources/subscribe",
+    "resources/templates/list",
+    "resources/unsubscribe",
+    "tools/call",
+    "tools/list",
+];
+
+async fn send_line<TMessage: Serialize>(line_tx: &Sender<String>, message: &TMessage) {
+    match serde_json::to_string(message) {
+        Ok(serialized) => {
+            // The writer only stops once every sender is gone
+            let _ = line_tx.send(serialized).await;
+        }
+        Err(err) => error!("Unable to serialize message: {err:#}"),
+    }
+}
+
+/// Serves the MCP over newline-delimited JSON-RPC, for clients that spawn
+/// the server as a subprocess. Stdout only ever carries messages, so logs
+/// have to go to stderr.
+///
+/// The connection is a single session. Requests are handled concurrently,
+/// so responses can arrive in a different order than the requests.
+pub struct McpStdioTransport {
+    pub prompt_controller_collection_holder: PromptControllerCollectionHolder,
+    pub resource_list_aggregate: Arc<ResourceListAggregate>,
+    pub server_info: Implementation,
+    pub session_manager: SessionManager,
+    pub tool_registry: Arc<ToolRegistry>,
+}
+
+impl McpStdioTransport {
+    fn dispatcher(&self) -> ClientToServerMessageDispatcher {
+        ClientToServerMessageDispatcher {
+            prompt_controller_collection_holder: self.prompt_controller_collection_holder.clone(),
+            resource_list_aggregate: self.resource_list_aggregate.clone(),
+            session_manager: self.session_manager.clone(),
+            tool_registry: self.tool_registry.clone(),
+        }
+    }
+
+    /// Answers lines that are not a message the server understands with
+    /// an error. Notifications never get a reply.
+    async fn parse_line(line: &str, line_tx: &Sender<String>) -> Option<ClientToServerMessage> {
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(err) => {
+                send_line(
+                    line_tx,
+                    &ServerToClientResponse::Error(Error::parse(format!("Parse error: {err:#}"))),
+                )
+                .await;
+
+                return None;
+            }
+        };
+        let id: Option<Id> = value
+            .get("id")
+            .and_then(|id| serde_json::from_value(id.clone()).ok());
+        let method = value
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        if !SUPPORTED_METHODS.contains(&method.as_str()) {
+            if let Some(id) = id {
+                send_line(
+                    line_tx,
+                    &ServerToClientResponse::Error(Error::method_not_found(id, &method)),
+                )
+                .await;
+            }
+
+            return None;
+        }
+
+        match serde_json::from_value(value) {
+            Ok(client_to_server_message) => Some(client_to_server_message),
+            Err(err) => {
+                let message = format!("Invalid '{method}' message: {err:#}");
+
+                match id {
+                    Some(id) => {
+                        send_line(
+                            line_tx,
+                            &ServerToClientResponse::Error(Error::invalid_params(id, message)),
+                        )
+                        .await;
+                    }
+                    None => warn!("{message}"),
+                }
+
+                None
+            }
+        }
+    }
+
+    /// Reads messages until the reader is closed, then waits for the
+    /// pending requests and ends the session
+    async fn read_messages<TReader>(&self, reader: TReader, line_tx: Sender<String>) -> Result<()>
+    where
+        TReader: AsyncBufRead + Unpin,
+    {
+        let mut lines = reader.lines();
+        let mut pending_requests = JoinSet::new();
+        let mut session: Option<Session> = None;
+        let mut notifications_forwarder = None;
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let Some(client_to_server_message) = Self::parse_line(&line, &line_tx).await else {
+                continue;
+            };
+
+            if let ClientToServerMessage::Initialize(request) = client_to_server_message {
+                if session.is_some() {
+                    send_line(
+                        &line_tx,
+                        &ServerToClientResponse::Error(Error::invalid_request(
+                            "The session is already initialized".to_string(),
+                        )),
+                    )
+                    .await;
+
+                    continue;
+                }
+
+                let (confirmation, session_with_notifications_receiver) = InitializeHandler {
+                    prompts_list_changed: false,
+                    server_info: self.server_info.clone(),
+                    session_manager: self.session_manager.clone(),
+                }
+                .handle(request)
+                .await?;
+
+                if let Some(SessionWithNotificationsReceiver {
+                    mut notification_rx,
+                    session: new_session,
+                }) = session_with_notifications_receiver
+                {
+                    let line_tx = line_tx.clone();
+
+                    notifications_forwarder = Some(tokio::spawn(async move {
+                        while let Some(notification) = notification_rx.recv().await {
+                            send_line(&line_tx, &notification).await;
+                        }
+                    }));
+                    session = Some(new_session);
+                }
+
+                send_line(&line_tx, &confirmation).await;
+
+                continue;
+            }
+
+            // Handlers update the stored session, for example its log level
+            let current_session = match &session {
+                Some(session) => {
+                    self.session_manager
+                        .session_storage
+                        .read(&session.id())
+                        .await?
+                }
+                None => None,
+            };
+            let Some(current_session) = current_session else {
+                if matches!(
+                    client_to_server_message,
+                    ClientToServerMessage::Cancelled(_) | ClientToServerMessage::Initialized(_)
+                ) {
+                    warn!("Ignoring a notification sent before initialization");
+
+                    continue;
+                }
+
+                send_line(
+                    &line_tx,
+                    &ServerToClientResponse::Error(Error::invalid_request(
+                        "The session is not initialized".to_string(),
+                    )),
+                )
+                .await;
+
+                continue;
+            };
+            let dispatcher = self.dispatcher();
+            let line_tx = line_tx.clone();
+
+            pending_requests.spawn(async move {
+                match dispatcher
+                    .dispatch(client_to_server_message, current_session)
+                    .await
+                {
+                    Ok(Some(response)) => send_line(&line_tx, &response).await,
+                    Ok(None) => {}
+                    Err(err) => error!("Unable to handle message: {err:#}"),
+                }
+            });
+        }
+
+        while let Some(result) = pending_requests.join_next().await {
+            if let Err(err) = result {
+                error!("Request handler failed: {err:#}");
+            }
+        }
+
+        if let Some(session) = session {
+            self.session_manager.terminate_session(session).await?;
+        }
+
+        if let Some(notifications_forwarder) = notifications_forwarder {
+            notifications_forwarder.abort();
+        }
+
+        Ok(())
+    }
+
+    pub async fn serve<TReader, TWriter>(&self, reader: TReader, mut writer: TWriter) -> Result<()>
+    where
+        TReader: AsyncBufRead + Unpin,
+        TWriter: AsyncWrite + Unpin,
+    {
+        let (line_tx, mut line_rx) = mpsc::channel::<String>(30);
+        let write_lines = async move {
+            while let Some(line) = line_rx.recv().await {
+                writer.write_all(line.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+
+            anyhow::Ok(())
+        };
+        let (read_result, write_result) =
+            tokio::join!(self.read_messages(reader, line_tx), write_lines);
+
+        read_result?;
+        write_result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use indoc::indoc;
+    use serde_json::json;
+    use tokio::io::BufReader;
+    use tokio::io::duplex;
+
+    use super::*;
+    use crate::holder::Holder as _;
+    use crate::mcp::MCP_PROTOCOL_VERSION;
+    use crate::mcp::prompt_controller::PromptController;
+    use crate::mcp::resource_provider::ResourceProvider;
+    use crate::prompt_document_controller::PromptDocumentController;
+
+    #[tokio::test]
+    async fn test_scripted_session_is_answered() -> Result<()> {
+        let prompt_controller: Arc<dyn PromptController> =
+            Arc::new(PromptDocumentController::mock(
+                "greeting",
+                indoc! {r#"
+                +++
+                description = "Greeting"
+                title = "Greeting"
+
+                [arguments]
+                +++
+
+                **user**: Hello over stdio
+                "#},
+            )?);
+        let prompt_controller_collection_holder: PromptControllerCollectionHolder =
+            Default::default();
+
+        prompt_controller_collection_holder
+            .set(Some(Arc::new(
+                [("greeting".to_string(), prompt_controller)]
+                    .into_iter()
+                    .collect(),
+            )))
+            .await;
+
+        let session_manager: SessionManager = Default::default();
+        let transport = McpStdioTransport {
+            prompt_controller_collection_holder,
+            resource_list_aggregate: Arc::new(Vec::<Arc<dyn ResourceProvider>>::new().into()),
+            session_manager: session_manager.clone(),
+            tool_registry: Default::default(),
+            server_info: Implementation {
+                description: None,
+                name: "poet".to_string(),
+                title: None,
+                version: "1.0.0".to_string(),
+            },
+        };
+        let script = [
+            json!({
+                "id": 1,
+                "jsonrpc": "2.0",
+                "method": "initialize",
+                "params": {
+                    "capabilities": {},
+                    "clientInfo": { "name": "test-client", "version": "1.0.0" },
+                    "protocolVersion": MCP_PROTOCOL_VERSION,
+                },
+            }),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+            json!({ "jsonrpc": "2.0", "method": "notifications/cancelled", "params": {} }),
+            json!({ "id": 2, "jsonrpc": "2.0", "method": "prompts/list", "params": {} }),
+            json!({
+                "id": 3,
+                "jsonrpc": "2.0",
+                "method": "prompts/get",
+                "params": { "name": "greeting" },
+            }),
+            json!({ "id": 4, "jsonrpc": "2.0", "method": "tools/list", "params": {} }),
+            json!({
+                "id": 5,
+                "jsonrpc": "2.0",
+                "method": "completion/complete",
+                "params": {
+                    "argument": { "name": "missing", "value": "" },
+                    "ref": { "name": "greeting", "type": "ref/prompt" },
+                },
+            }),
+            json!({ "id": 6, "jsonrpc": "2.0", "method": "unknown/method", "params": {} }),
+        ]
+        .iter()
+        .map(|message| format!("{message}\n"))
+        .collect::<String>()
+            + "{\"jsonrpc\": \n";
+        let (mut client_writer, server_reader) = duplex(16);
+
+        // Small chunks split messages across reads, and a single read can
+        // still hold the end of one message and the start of the next
+        let client = tokio::spawn(async move {
+            for chunk in script.as_bytes().chunks(7) {
+                client_writer.write_all(chunk).await?;
+            }
+
+            anyhow::Ok(())
+        });
+        let mut output: Vec<u8> = Vec::new();
+
+        transport
+            .serve(BufReader::new(server_reader), &mut output)
+            .await?;
+        client.await??;
+
+        let responses = String::from_utf8(output)?
+            .lines()
+            .map(serde_json::from_str::<Value>)
+            .collect::<Result<Vec<Value>, _>>()?
+            .into_iter()
+            // Notifications are the only messages without an id
+            .filter(|message| message.get("id").is_some())
+            .collect::<Vec<Value>>();
+        let response = |id: Value| {
+            responses
+                .iter()
+                .find(|response| response["id"] == id)
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        assert_eq!(responses.len(), 7);
+        assert_eq!(response(json!(1))["result"]["serverInfo"]["name"], "poet");
+        assert_eq!(
+            response(json!(1))["result"]["capabilities"]["completions"],
+            json!({})
+        );
+        assert_eq!(
+            response(json!(1))["result"]["capabilities"]["logging"],
+            json!({})
+        );
+        assert_eq!(
+            response(json!(2))["result"]["prompts"][0]["name"],
+            "greeting"
+        );
+        assert_eq!(
+            response(json!(3))["result"]["messages"][0]["content"]["text"],
+            "Hello over stdio"
+        );
+        assert!(response(json!(4))["result"]["tools"].is_array());
+        assert!(response(json!(5))["error"]["code"] != -32601);
+        assert_eq!(response(json!(6))["error"]["code"], -32601);
+        assert_eq!(response(Value::Null)["error"]["code"], -32700);
+        assert!(session_manager.session_storage.sessions.is_empty());
+
+        Ok(())
+    }
+}
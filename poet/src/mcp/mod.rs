@@ -1,5 +1,7 @@
 pub mod accepts_all;
+pub mod client_to_server_message_dispatcher;
 pub mod content_block;
+pub mod handler;
 pub mod jsonrpc;
 pub mod list_resources_cursor;
 pub mod log_level;
@@ -8,10 +10,24 @@ pub mod mcp_http_service_factory;
 pub mod mcp_responder;
 pub mod mcp_responder_context;
 pub mod mcp_responder_handler;
+pub mod mcp_stdio_transport;
 pub mod prompt;
+pub mod prompt_argument_description;
 pub mod prompt_controller;
+pub mod prompt_controller_cached;
 pub mod prompt_controller_collection;
+pub mod prompt_controller_conflict_policy;
+pub mod prompt_controller_metered;
+pub mod prompt_controller_with_middlewares;
+pub mod prompt_description;
+pub mod prompt_line_change;
 pub mod prompt_message;
+pub mod prompt_message_diff;
+pub mod prompt_messages_checksum;
+pub mod prompt_metrics;
+pub mod prompt_metrics_noop;
+pub mod prompt_middleware;
+pub mod prompt_middleware_next;
 pub mod resource;
 pub mod resource_content;
 pub mod resource_content_parts;
@@ -26,6 +42,7 @@ pub mod session;
 pub mod session_manager;
 pub mod session_storage;
 pub mod session_with_notifications_receiver;
+pub mod static_prompt_renders;
 pub mod tool;
 pub mod tool_call_error_message;
 pub mod tool_handler;
@@ -38,3 +55,4 @@ pub mod tool_responder;
 pub const MCP_HEADER_PROTOCOL_VERSION: &str = "Mcp-Protocol-Version";
 pub const MCP_HEADER_SESSION: &str = "Mcp-Session-Id";
 pub const MCP_PROTOCOL_VERSION: &str = "2025-11-25";
+pub const MCP_SUPPORTED_PROTOCOL_VERSIONS: [&str; 2] = ["2025-06-18", MCP_PROTOCOL_VERSION];
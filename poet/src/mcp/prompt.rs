@@ -1,5 +1,6 @@
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PromptArgument {
@@ -10,10 +11,33 @@ pub struct PromptArgument {
     pub title: String,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PromptMeta {
+    /// Same JSON Schema as in the prompt manifest, listed only when the
+    /// server is configured to, because it makes listings considerably larger
+    #[serde(
+        default,
+        rename = "argumentsSchema",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub arguments_schema: Option<Value>,
+    /// Clients can warn users before they pick a prompt that is phased out
+    #[serde(default)]
+    pub deprecated: bool,
+}
+
+impl PromptMeta {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Prompt {
     pub arguments: Vec<PromptArgument>,
     pub description: String,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<PromptMeta>,
     pub name: String,
     pub title: String,
 }
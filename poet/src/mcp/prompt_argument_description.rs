@@ -0,0 +1,26 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::prompt_document_front_matter::argument_content_type::ArgumentContentType;
+use crate::prompt_document_front_matter::argument_kind::ArgumentKind;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PromptArgumentDescription {
+    pub choices: Vec<String>,
+    pub content_type: ArgumentContentType,
+    pub default: Option<Value>,
+    pub description: String,
+    pub example: Option<Value>,
+    pub kind: ArgumentKind,
+    pub name: String,
+    /// Source of the whole-input pattern, see `ArgumentPattern`
+    pub pattern: Option<String>,
+    pub required: bool,
+    /// Kept out of logs and traces
+    pub sensitive: bool,
+    /// Filled in from the session, so clients cannot provide it
+    pub session_bound: bool,
+    pub title: String,
+    /// Trailing whitespace is removed from the input
+    pub trim: bool,
+}
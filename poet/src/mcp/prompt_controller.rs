@@ -1,13 +1,106 @@
+use std::sync::Arc;
+
 use anyhow::Result;
 use async_trait::async_trait;
+use esbuild_metafile::EsbuildMetaFile;
 
 use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
+use crate::mcp::jsonrpc::response::success::completion_complete::Completion;
 use crate::mcp::jsonrpc::response::success::prompts_get_result::PromptsGetResult;
 use crate::mcp::prompt::Prompt;
+use crate::mcp::prompt_argument_description::PromptArgumentDescription;
+use crate::mcp::prompt_controller_cached::PromptControllerCached;
+use crate::mcp::prompt_description::PromptDescription;
+use crate::mcp::prompt_metrics_noop::PromptMetricsNoop;
+use crate::prompt_messages_to_html::prompt_messages_to_html;
 
 #[async_trait]
 pub trait PromptController: Send + Sync {
+    /// Duplicates the controller behind a trait object, so it can be wrapped
+    /// or transformed without consuming the original
+    fn clone_box(&self) -> Box<dyn PromptController>;
+
+    /// Copy of the controller that resolves assets against an updated
+    /// metafile, or `None` when its responses do not depend on assets
+    fn with_esbuild_metafile(
+        &self,
+        _esbuild_metafile: Arc<EsbuildMetaFile>,
+    ) -> Option<Box<dyn PromptController>> {
+        None
+    }
+
+    /// Copy of the controller that serves a result rendered ahead of time to
+    /// requests without arguments. Wrappers that observe requests should
+    /// keep the cache inside of them.
+    fn with_cached_result(
+        &self,
+        cached_result: Arc<PromptsGetResult>,
+    ) -> Box<dyn PromptController> {
+        Box::new(PromptControllerCached {
+            cached_result,
+            inner: self.clone_box().into(),
+            metrics: Arc::new(PromptMetricsNoop),
+        })
+    }
+
+    /// Copy of the controller rebuilt from updated source, or `None` when it
+    /// is not built from a source file
+    fn with_source(&self, _source: &str) -> Option<Result<Box<dyn PromptController>>> {
+        None
+    }
+
+    /// Suggests values for an argument that start with what was typed so far
+    fn complete(&self, _argument_name: &str, _partial_value: &str) -> Completion {
+        Completion::default()
+    }
+
+    /// Summary based on the listing alone. Controllers that know more about
+    /// their prompt (like components it uses) should override it.
+    fn describe(&self) -> PromptDescription {
+        let Prompt {
+            arguments,
+            description,
+            name,
+            title,
+            ..
+        } = self.get_mcp_prompt();
+
+        PromptDescription {
+            arguments: arguments
+                .into_iter()
+                .map(|argument| PromptArgumentDescription {
+                    choices: Default::default(),
+                    content_type: Default::default(),
+                    default: None,
+                    description: argument.description,
+                    example: None,
+                    kind: Default::default(),
+                    name: argument.name,
+                    pattern: None,
+                    required: argument.required,
+                    sensitive: false,
+                    session_bound: false,
+                    title: argument.title,
+                    trim: false,
+                })
+                .collect(),
+            collections: Default::default(),
+            components: Default::default(),
+            description,
+            is_static: false,
+            name,
+            title,
+        }
+    }
+
     fn get_mcp_prompt(&self) -> Prompt;
 
     async fn respond_to(&self, request: PromptsGet) -> Result<PromptsGetResult>;
+
+    /// Sanitized HTML of the rendered messages, for a browser preview
+    async fn respond_to_html(&self, request: PromptsGet) -> Result<String> {
+        let PromptsGetResult { messages, .. } = self.respond_to(request).await?;
+
+        prompt_messages_to_html(&messages)
+    }
 }
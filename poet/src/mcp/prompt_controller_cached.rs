@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use esbuild_metafile::EsbuildMetaFile;
+
+use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
+use crate::mcp::jsonrpc::response::success::completion_complete::Completion;
+use crate::mcp::jsonrpc::response::success::prompts_get_result::PromptsGetResult;
+use crate::mcp::prompt::Prompt;
+use crate::mcp::prompt_controller::PromptController;
+use crate::mcp::prompt_description::PromptDescription;
+use crate::mcp::prompt_metrics::PromptMetrics;
+
+/// Serves a result rendered ahead of time to requests without arguments,
+/// and passes every other request through. Only static prompts are cached,
+/// because nothing else about the request can change how they render.
+#[derive(Clone)]
+pub struct PromptControllerCached {
+    pub cached_result: Arc<PromptsGetResult>,
+    pub inner: Arc<dyn PromptController>,
+    pub metrics: Arc<dyn PromptMetrics>,
+}
+
+#[async_trait]
+impl PromptController for PromptControllerCached {
+    fn clone_box(&self) -> Box<dyn PromptController> {
+        Box::new(self.clone())
+    }
+
+    /// The cached result is stale once the assets change
+    fn with_esbuild_metafile(
+        &self,
+        esbuild_metafile: Arc<EsbuildMetaFile>,
+    ) -> Option<Box<dyn PromptController>> {
+        self.inner.with_esbuild_metafile(esbuild_metafile)
+    }
+
+    fn with_source(&self, source: &str) -> Option<Result<Box<dyn PromptController>>> {
+        self.inner.with_source(source)
+    }
+
+    fn complete(&self, argument_name: &str, partial_value: &str) -> Completion {
+        self.inner.complete(argument_name, partial_value)
+    }
+
+    fn describe(&self) -> PromptDescription {
+        self.inner.describe()
+    }
+
+    fn get_mcp_prompt(&self) -> Prompt {
+        self.inner.get_mcp_prompt()
+    }
+
+    async fn respond_to(&self, request: PromptsGet) -> Result<PromptsGetResult> {
+        if request.params.arguments.is_empty() && request.params.meta.is_none() {
+            self.metrics.on_cache_hit(&request.params.name);
+
+            return Ok(self.cached_result.as_ref().clone());
+        }
+
+        self.metrics.on_cache_miss(&request.params.name);
+
+        self.inner.respond_to(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use indoc::indoc;
+
+    use super::*;
+    use crate::prompt_document_controller::PromptDocumentController;
+
+    #[derive(Default)]
+    struct PromptCacheMetricsRecorder {
+        hits: Mutex<Vec<String>>,
+        misses: Mutex<Vec<String>>,
+    }
+
+    impl PromptMetrics for PromptCacheMetricsRecorder {
+        fn on_cache_hit(&self, name: &str) {
+            self.hits
+                .lock()
+                .expect("Cache hits lock is poisoned")
+                .push(name.to_string());
+        }
+
+        fn on_cache_miss(&self, name: &str) {
+            self.misses
+                .lock()
+                .expect("Cache misses lock is poisoned")
+                .push(name.to_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_hits_and_misses_are_recorded() -> Result<()> {
+        let metrics = Arc::new(PromptCacheMetricsRecorder::default());
+        let prompt_controller = PromptControllerCached {
+            cached_result: Arc::new(PromptsGetResult {
+                description: None,
+                messages: Default::default(),
+                meta: None,
+            }),
+            inner: Arc::new(PromptDocumentController::mock(
+                "greet",
+                indoc! {r#"
+                +++
+                description = "greet"
+                title = "Greet"
+
+                [arguments.name]
+                description = "Who to greet"
+                required = false
+                title = "Name"
+                +++
+
+                **user**: Hello
+                "#},
+            )?),
+            metrics: metrics.clone(),
+        };
+
+        let cached_response = prompt_controller
+            .respond_to(PromptsGet::mock("greet", Default::default()))
+            .await?;
+        let rendered_response = prompt_controller
+            .respond_to(PromptsGet::mock(
+                "greet",
+                HashMap::from([("name".to_string(), "Ada".to_string())]),
+            ))
+            .await?;
+
+        assert!(cached_response.messages.is_empty());
+        assert_eq!(rendered_response.messages.len(), 1);
+        assert_eq!(
+            *metrics.hits.lock().expect("Cache hits lock is poisoned"),
+            vec!["greet".to_string()]
+        );
+        assert_eq!(
+            *metrics
+                .misses
+                .lock()
+                .expect("Cache misses lock is poisoned"),
+            vec!["greet".to_string()]
+        );
+
+        Ok(())
+    }
+}
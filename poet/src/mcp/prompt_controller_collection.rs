@@ -1,31 +1,1256 @@
 use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::btree_map::IntoIter;
+use std::collections::btree_map::Iter;
+use std::convert::identity;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
+use std::thread::available_parallelism;
 
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::anyhow;
 use dashmap::DashMap;
+use esbuild_metafile::EsbuildMetaFile;
+use futures_util::StreamExt as _;
+use futures_util::stream;
+use log::info;
+use log::warn;
+use tokio::runtime::Handle;
+use tokio::task::spawn_blocking;
 
+use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
+use crate::mcp::jsonrpc::response::success::prompts_get_result::PromptsGetResult;
+use crate::mcp::jsonrpc::response::success::prompts_list::PromptsList;
 use crate::mcp::list_resources_cursor::ListResourcesCursor;
 use crate::mcp::prompt::Prompt;
+use crate::mcp::prompt::PromptMeta;
 use crate::mcp::prompt_controller::PromptController;
+use crate::mcp::prompt_controller_conflict_policy::PromptControllerConflictPolicy;
+use crate::mcp::prompt_controller_with_middlewares::PromptControllerWithMiddlewares;
+use crate::mcp::prompt_message_diff::PromptMessageDiff;
+use crate::mcp::prompt_middleware::PromptMiddleware;
+use crate::mcp::static_prompt_renders::StaticPromptRenders;
 
-#[derive(Default)]
-pub struct PromptControllerCollection(pub BTreeMap<String, Arc<dyn PromptController>>);
+#[derive(Clone, Default)]
+pub struct PromptControllerCollection {
+    /// Lists the arguments schema under `_meta` of each prompt
+    pub argument_meta: bool,
+    /// Responds to requests for prompt names that do not exist
+    pub fallback: Option<Arc<dyn PromptController>>,
+    /// Wrap every prompt returned by `get` and `get_fallback`, outermost first
+    pub middlewares: Vec<Arc<dyn PromptMiddleware>>,
+    pub prompt_controllers: BTreeMap<String, Arc<dyn PromptController>>,
+}
 
 impl PromptControllerCollection {
+    pub fn get(&self, name: &str) -> Option<Arc<dyn PromptController>> {
+        self.prompt_controllers
+            .get(name)
+            .map(|prompt_controller| self.with_middlewares(prompt_controller.clone()))
+    }
+
+    /// Controller to respond with when there is no prompt with the requested
+    /// name
+    pub fn get_fallback(&self) -> Option<Arc<dyn PromptController>> {
+        self.fallback
+            .as_ref()
+            .map(|fallback| self.with_middlewares(fallback.clone()))
+    }
+
     pub fn list_mcp_prompts(
         &self,
         ListResourcesCursor { offset, per_page }: ListResourcesCursor,
     ) -> Vec<Prompt> {
-        self.0
+        self.prompt_controllers
             .iter()
             .skip(offset)
             .take(per_page)
-            .map(|(_, prompt_document_controller)| prompt_document_controller.get_mcp_prompt())
+            .map(|(_, prompt_document_controller)| {
+                let mut prompt = prompt_document_controller.get_mcp_prompt();
+
+                if self.argument_meta {
+                    prompt.meta.get_or_insert_default().arguments_schema =
+                        Some(prompt_document_controller.describe().arguments_schema());
+                }
+
+                if prompt.meta.as_ref().is_some_and(PromptMeta::is_empty) {
+                    prompt.meta = None;
+                }
+
+                prompt
+            })
+            .collect()
+    }
+
+    /// Pages through prompts sorted by name, so pages never overlap or skip
+    /// prompts. The offset comes from the cursor of the previous page. Pages
+    /// hold at least one prompt, otherwise paging would never advance.
+    pub fn list_prompts(
+        &self,
+        cursor: Option<ListResourcesCursor>,
+        page_size: usize,
+    ) -> PromptsList {
+        let page_size = page_size.max(1);
+        let offset = cursor.map_or(0, |ListResourcesCursor { offset, .. }| offset);
+        let next_offset = offset.saturating_add(page_size);
+
+        PromptsList {
+            next_cursor: (next_offset < self.prompt_controllers.len()).then_some(
+                ListResourcesCursor {
+                    offset: next_offset,
+                    per_page: page_size,
+                },
+            ),
+            prompts: self.list_mcp_prompts(ListResourcesCursor {
+                offset,
+                per_page: page_size,
+            }),
+        }
+    }
+
+    /// Adds prompts from an independently built collection. With the error
+    /// policy, nothing is merged if any of the names conflict.
+    pub fn merge(
+        &mut self,
+        other: PromptControllerCollection,
+        on_conflict: PromptControllerConflictPolicy,
+    ) -> Result<()> {
+        if on_conflict == PromptControllerConflictPolicy::Error {
+            let conflicting_names: Vec<&String> = other
+                .prompt_controllers
+                .keys()
+                .filter(|name| self.prompt_controllers.contains_key(*name))
+                .collect();
+
+            if !conflicting_names.is_empty() {
+                return Err(anyhow!(
+                    "Prompts are defined in both collections: {}",
+                    conflicting_names
+                        .iter()
+                        .map(|name| format!("'{name}'"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+
+        for (name, prompt_controller) in other.prompt_controllers {
+            match on_conflict {
+                PromptControllerConflictPolicy::KeepExisting => {
+                    self.prompt_controllers
+                        .entry(name)
+                        .or_insert(prompt_controller);
+                }
+                PromptControllerConflictPolicy::Error
+                | PromptControllerConflictPolicy::Overwrite => {
+                    self.prompt_controllers.insert(name, prompt_controller);
+                }
+            }
+        }
+
+        if self.fallback.is_none() {
+            self.fallback = other.fallback;
+        }
+
+        Ok(())
+    }
+
+    /// Rebuilds a single prompt from updated source and swaps it in. Taking
+    /// the collection mutably keeps readers from seeing a partial swap. When
+    /// the rebuild fails, the existing prompt stays as it was.
+    pub fn reload(&mut self, name: &str, source: &str) -> Result<()> {
+        let prompt_controller = self
+            .prompt_controllers
+            .get(name)
+            .ok_or_else(|| anyhow!("Prompt does not exist: '{name}'"))?;
+        let reloaded_prompt_controller = prompt_controller
+            .with_source(source)
+            .ok_or_else(|| anyhow!("Prompt '{name}' is not built from source"))?
+            .with_context(|| format!("Unable to reload prompt '{name}'"))?;
+
+        self.prompt_controllers
+            .insert(name.to_string(), reloaded_prompt_controller.into());
+
+        Ok(())
+    }
+
+    /// Renders the prompt once for every argument set in the JSON array.
+    /// Results are in the same order as the argument sets, so a failing
+    /// set does not prevent the others from rendering.
+    pub async fn render_batch(
+        &self,
+        name: &str,
+        argument_sets_json: &str,
+    ) -> Result<Vec<Result<PromptsGetResult>>> {
+        let prompt_controller = self
+            .get(name)
+            .ok_or_else(|| anyhow!("Prompt does not exist: '{name}'"))?;
+        let argument_sets: Vec<HashMap<String, String>> = serde_json::from_str(argument_sets_json)?;
+        let mut results = Vec::with_capacity(argument_sets.len());
+
+        for (index, arguments) in argument_sets.into_iter().enumerate() {
+            results.push(
+                prompt_controller
+                    .respond_to(PromptsGet::internal(
+                        format!("batch-{index}").into(),
+                        name,
+                        arguments,
+                    ))
+                    .await,
+            );
+        }
+
+        Ok(results)
+    }
+
+    /// Renders the prompt with both argument sets and compares the
+    /// messages at the same positions. Only messages that differ are
+    /// returned.
+    pub async fn render_diff(
+        &self,
+        name: &str,
+        arguments_before: HashMap<String, String>,
+        arguments_after: HashMap<String, String>,
+    ) -> Result<Vec<PromptMessageDiff>> {
+        let prompt_controller = self
+            .get(name)
+            .ok_or_else(|| anyhow!("Prompt does not exist: '{name}'"))?;
+        let render = |id: &str, arguments: HashMap<String, String>| {
+            prompt_controller.respond_to(PromptsGet::internal(id.into(), name, arguments))
+        };
+        let before = render("diff-before", arguments_before)
+            .await
+            .context("Unable to render the first argument set")?;
+        let after = render("diff-after", arguments_after)
+            .await
+            .context("Unable to render the second argument set")?;
+
+        Ok((0..before.messages.len().max(after.messages.len()))
+            .map(|index| {
+                PromptMessageDiff::new(index, before.messages.get(index), after.messages.get(index))
+            })
+            .filter(PromptMessageDiff::has_changes)
+            .collect())
+    }
+
+    /// Renders every static prompt that takes no arguments, as many at a
+    /// time as there are cores. Prompts that fail to render are left out.
+    pub async fn render_static_prompts(&self) -> StaticPromptRenders {
+        let concurrency = available_parallelism().map_or(1, NonZeroUsize::get);
+        let pending_renders: Vec<_> = self
+            .prompt_controllers
+            .iter()
+            .filter(|(_, prompt_controller)| {
+                let prompt_description = prompt_controller.describe();
+
+                prompt_description.is_static && prompt_description.arguments.is_empty()
+            })
+            .map(|(name, prompt_controller)| {
+                render_static_prompt(name.clone(), prompt_controller.clone())
+            })
+            .collect();
+        let renders: Vec<(String, Result<PromptsGetResult>)> = stream::iter(pending_renders)
+            .buffer_unordered(concurrency)
             .collect()
+            .await;
+        let mut static_prompt_renders = StaticPromptRenders::default();
+
+        for (name, result) in renders {
+            match result {
+                Ok(prompts_get_result) => {
+                    static_prompt_renders
+                        .results
+                        .insert(name, Arc::new(prompts_get_result));
+                }
+                Err(err) => warn!("Unable to render static prompt '{name}': {err:#}"),
+            }
+        }
+
+        static_prompt_renders
     }
+
+    /// Swaps in controllers that serve the already rendered static prompts,
+    /// so the first requests do not have to wait for them
+    pub fn warm(&mut self, static_prompt_renders: &StaticPromptRenders) {
+        let mut warmed: usize = 0;
+
+        for (name, prompts_get_result) in &static_prompt_renders.results {
+            if let Some(prompt_controller) = self.prompt_controllers.get_mut(name) {
+                *prompt_controller = prompt_controller
+                    .with_cached_result(prompts_get_result.clone())
+                    .into();
+                warmed += 1;
+            }
+        }
+
+        info!("Warmed {warmed} prompts");
+    }
+
+    /// Swaps an updated metafile into the prompts that resolve assets,
+    /// without rebuilding any of them. Other prompts are shared as they are,
+    /// together with the results cached by `warm`.
+    pub fn with_esbuild_metafile(&self, esbuild_metafile: Arc<EsbuildMetaFile>) -> Self {
+        let swap = |prompt_controller: &Arc<dyn PromptController>| -> Arc<dyn PromptController> {
+            match prompt_controller.with_esbuild_metafile(esbuild_metafile.clone()) {
+                Some(updated_prompt_controller) => updated_prompt_controller.into(),
+                None => prompt_controller.clone(),
+            }
+        };
+
+        Self {
+            argument_meta: self.argument_meta,
+            fallback: self.fallback.as_ref().map(swap),
+            middlewares: self.middlewares.clone(),
+            prompt_controllers: self
+                .prompt_controllers
+                .iter()
+                .map(|(name, prompt_controller)| (name.clone(), swap(prompt_controller)))
+                .collect(),
+        }
+    }
+
+    fn with_middlewares(
+        &self,
+        prompt_controller: Arc<dyn PromptController>,
+    ) -> Arc<dyn PromptController> {
+        if self.middlewares.is_empty() {
+            return prompt_controller;
+        }
+
+        Arc::new(PromptControllerWithMiddlewares {
+            inner: prompt_controller,
+            middlewares: self.middlewares.clone(),
+        })
+    }
+}
+
+/// Templates are evaluated synchronously, so the render runs on a blocking
+/// thread to keep it from stalling the runtime
+async fn render_static_prompt(
+    name: String,
+    prompt_controller: Arc<dyn PromptController>,
+) -> (String, Result<PromptsGetResult>) {
+    let request = PromptsGet::internal(format!("static-{name}").into(), &name, Default::default());
+    let runtime = Handle::current();
+    let result = spawn_blocking(move || runtime.block_on(prompt_controller.respond_to(request)))
+        .await
+        .context("Static prompt render task did not finish")
+        .and_then(identity);
+
+    (name, result)
 }
 
 impl From<DashMap<String, Arc<dyn PromptController>>> for PromptControllerCollection {
     fn from(prompt_controller_dashmap: DashMap<String, Arc<dyn PromptController>>) -> Self {
-        Self(prompt_controller_dashmap.into_iter().collect())
+        prompt_controller_dashmap.into_iter().collect()
+    }
+}
+
+impl FromIterator<(String, Arc<dyn PromptController>)> for PromptControllerCollection {
+    fn from_iter<TIterator>(iterator: TIterator) -> Self
+    where
+        TIterator: IntoIterator<Item = (String, Arc<dyn PromptController>)>,
+    {
+        Self {
+            argument_meta: false,
+            fallback: None,
+            middlewares: Vec::new(),
+            prompt_controllers: iterator.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for PromptControllerCollection {
+    type IntoIter = IntoIter<String, Arc<dyn PromptController>>;
+    type Item = (String, Arc<dyn PromptController>);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.prompt_controllers.into_iter()
+    }
+}
+
+impl<'collection> IntoIterator for &'collection PromptControllerCollection {
+    type IntoIter = Iter<'collection, String, Arc<dyn PromptController>>;
+    type Item = (&'collection String, &'collection Arc<dyn PromptController>);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.prompt_controllers.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::str::FromStr as _;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use indoc::formatdoc;
+    use indoc::indoc;
+    use serde_json::json;
+
+    use super::*;
+    use crate::mcp::content_block::ContentBlock;
+    use crate::mcp::jsonrpc::role::Role;
+    use crate::mcp::prompt_description::PromptDescription;
+    use crate::mcp::prompt_line_change::PromptLineChange;
+    use crate::mcp::prompt_message::PromptMessage;
+    use crate::mcp::prompt_middleware_next::PromptMiddlewareNext;
+    use crate::prompt_document_controller::PromptDocumentController;
+
+    #[test]
+    fn test_list_includes_argument_meta_when_enabled() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "summarize",
+            indoc! {r#"
+            +++
+            description = "summarize"
+            title = "Summarize"
+
+            [arguments.text]
+            description = "Text to summarize"
+            required = true
+            sensitive = true
+            title = "Text"
+            trim = true
+            +++
+
+            **user**: Summarize {context.arguments.text.input}
+            "#},
+        )?;
+        let mut prompt_controller_collection: PromptControllerCollection = BTreeMap::from([(
+            "summarize".to_string(),
+            Arc::new(prompt_controller) as Arc<dyn PromptController>,
+        )])
+        .into_iter()
+        .collect();
+
+        let list_meta = |prompt_controller_collection: &PromptControllerCollection| {
+            prompt_controller_collection
+                .list_mcp_prompts(Default::default())
+                .into_iter()
+                .map(|prompt| prompt.meta)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(list_meta(&prompt_controller_collection), vec![None]);
+
+        prompt_controller_collection.argument_meta = true;
+
+        assert_eq!(
+            list_meta(&prompt_controller_collection),
+            vec![Some(PromptMeta {
+                arguments_schema: Some(json!({
+                    "properties": {
+                        "text": {
+                            "description": "Text to summarize",
+                            "title": "Text",
+                            "type": "string",
+                            "writeOnly": true,
+                            "x-poet-kind": "string",
+                            "x-poet-trim": true,
+                        },
+                    },
+                    "required": ["text"],
+                    "type": "object",
+                })),
+                deprecated: false,
+            })]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_flags_deprecated_prompts() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "legacy",
+            indoc! {r#"
+            +++
+            deprecated = true
+            description = "legacy"
+            title = "Legacy"
+
+            [arguments]
+            +++
+
+            **user**: Hello
+            "#},
+        )?;
+        let prompt_controller_collection: PromptControllerCollection = BTreeMap::from([(
+            "legacy".to_string(),
+            Arc::new(prompt_controller) as Arc<dyn PromptController>,
+        )])
+        .into_iter()
+        .collect();
+
+        let prompts = prompt_controller_collection.list_mcp_prompts(Default::default());
+
+        assert_eq!(
+            prompts[0].meta,
+            Some(PromptMeta {
+                arguments_schema: None,
+                deprecated: true,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(&prompts[0])?["_meta"],
+            json!({ "deprecated": true })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_batch() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "greet",
+            indoc! {r#"
+            +++
+            description = "greet"
+            title = "Greet"
+
+            [arguments.animal]
+            description = "Animal to greet"
+            required = true
+            title = "Animal"
+            +++
+
+            **user**: Say hello to the {context.arguments.animal.input}
+            "#},
+        )?;
+        let prompt_controller_collection: PromptControllerCollection = BTreeMap::from([(
+            "greet".to_string(),
+            Arc::new(prompt_controller) as Arc<dyn PromptController>,
+        )])
+        .into_iter()
+        .collect();
+
+        let results = prompt_controller_collection
+            .render_batch("greet", r#"[{"animal": "horse"}, {}, {"animal": "goat"}]"#)
+            .await?;
+
+        assert_eq!(results.len(), 3);
+
+        let contents: Vec<Option<ContentBlock>> = results
+            .iter()
+            .map(|result| {
+                result
+                    .as_ref()
+                    .ok()
+                    .and_then(|response| response.messages.first())
+                    .map(|message| message.content.clone())
+            })
+            .collect();
+
+        assert_eq!(
+            contents,
+            vec![
+                Some("Say hello to the horse".into()),
+                None,
+                Some("Say hello to the goat".into()),
+            ]
+        );
+        assert!(results[1].is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_diff_highlights_changed_value() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "greet",
+            indoc! {r#"
+            +++
+            description = "greet"
+            title = "Greet"
+
+            [arguments.animal]
+            description = "Animal to greet"
+            required = true
+            title = "Animal"
+            +++
+
+            **user**: Say hello to the {context.arguments.animal.input}
+            "#},
+        )?;
+        let prompt_controller_collection: PromptControllerCollection = BTreeMap::from([(
+            "greet".to_string(),
+            Arc::new(prompt_controller) as Arc<dyn PromptController>,
+        )])
+        .into_iter()
+        .collect();
+
+        let prompt_message_diffs = prompt_controller_collection
+            .render_diff(
+                "greet",
+                HashMap::from([("animal".to_string(), "horse".to_string())]),
+                HashMap::from([("animal".to_string(), "goat".to_string())]),
+            )
+            .await?;
+
+        assert_eq!(
+            prompt_message_diffs,
+            vec![PromptMessageDiff {
+                index: 0,
+                line_changes: vec![PromptLineChange::Changed {
+                    after: "Say hello to the goat".to_string(),
+                    before: "Say hello to the horse".to_string(),
+                }],
+                role_after: Some(Role::User),
+                role_before: Some(Role::User),
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_into_iter_rebuilds_collection() -> Result<()> {
+        let prompt_controller_collection: PromptControllerCollection = BTreeMap::from([(
+            "greet".to_string(),
+            Arc::new(PromptDocumentController::mock(
+                "greet",
+                indoc! {r#"
+                +++
+                description = "greet"
+                title = "Greet"
+
+                [arguments]
+                +++
+
+                **user**: Hello
+                "#},
+            )?) as Arc<dyn PromptController>,
+        )])
+        .into_iter()
+        .collect();
+
+        let rebuilt_collection: PromptControllerCollection = prompt_controller_collection
+            .into_iter()
+            .map(|(name, prompt_controller)| (format!("wrapped-{name}"), prompt_controller))
+            .collect();
+
+        let names: Vec<String> = rebuilt_collection
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+
+        assert_eq!(names, vec!["wrapped-greet"]);
+
+        Ok(())
+    }
+
+    #[derive(Clone)]
+    struct HelpPromptController;
+
+    #[async_trait]
+    impl PromptController for HelpPromptController {
+        fn clone_box(&self) -> Box<dyn PromptController> {
+            Box::new(self.clone())
+        }
+
+        fn get_mcp_prompt(&self) -> Prompt {
+            Prompt {
+                arguments: Default::default(),
+                description: "Lists available prompts".to_string(),
+                meta: None,
+                name: "help".to_string(),
+                title: "Help".to_string(),
+            }
+        }
+
+        async fn respond_to(&self, request: PromptsGet) -> Result<PromptsGetResult> {
+            Ok(PromptsGetResult {
+                description: None,
+                messages: vec![PromptMessage {
+                    content: format!(
+                        "There is no prompt named '{}', try 'greet' instead",
+                        request.params.name
+                    )
+                    .into(),
+                    role: Role::User,
+                }],
+                meta: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_responds_to_unknown_prompt() -> Result<()> {
+        let prompt_controller_collection = PromptControllerCollection {
+            argument_meta: false,
+            fallback: Some(Arc::new(HelpPromptController)),
+            middlewares: Vec::new(),
+            prompt_controllers: Default::default(),
+        };
+
+        assert!(prompt_controller_collection.get("unknown").is_none());
+
+        let response = prompt_controller_collection
+            .get_fallback()
+            .ok_or_else(|| anyhow!("Fallback controller should be used"))?
+            .respond_to(PromptsGet::mock("unknown", Default::default()))
+            .await?;
+
+        assert_eq!(
+            response.messages[0].content,
+            "There is no prompt named 'unknown', try 'greet' instead".into()
+        );
+        assert!(
+            PromptControllerCollection::default()
+                .get_fallback()
+                .is_none()
+        );
+
+        Ok(())
+    }
+
+    #[derive(Default)]
+    struct LoggingPromptMiddleware {
+        calls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl PromptMiddleware for LoggingPromptMiddleware {
+        async fn handle(
+            &self,
+            request: PromptsGet,
+            next: PromptMiddlewareNext<'_>,
+        ) -> Result<PromptsGetResult> {
+            self.calls
+                .lock()
+                .expect("Middleware calls lock is poisoned")
+                .push(request.params.name.clone());
+
+            next.run(request).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_wraps_respond_to() -> Result<()> {
+        let logging_middleware = Arc::new(LoggingPromptMiddleware::default());
+        let prompt_controller_collection = PromptControllerCollection {
+            argument_meta: false,
+            fallback: Some(Arc::new(HelpPromptController)),
+            middlewares: vec![logging_middleware.clone()],
+            prompt_controllers: Default::default(),
+        };
+
+        let response = prompt_controller_collection
+            .get_fallback()
+            .ok_or_else(|| anyhow!("Fallback controller should be used"))?
+            .respond_to(PromptsGet::mock("unknown", Default::default()))
+            .await?;
+
+        assert_eq!(
+            response.messages[0].content,
+            "There is no prompt named 'unknown', try 'greet' instead".into()
+        );
+        assert_eq!(
+            *logging_middleware
+                .calls
+                .lock()
+                .expect("Middleware calls lock is poisoned"),
+            vec!["unknown".to_string()]
+        );
+
+        Ok(())
+    }
+
+    fn collection_with(prompts: &[(&str, &str)]) -> Result<PromptControllerCollection> {
+        prompts
+            .iter()
+            .map(|(name, greeting)| {
+                Ok((
+                    name.to_string(),
+                    Arc::new(PromptDocumentController::mock(
+                        name,
+                        &formatdoc! {r#"
+                        +++
+                        description = "{name}"
+                        title = "{name}"
+
+                        [arguments]
+                        +++
+
+                        **user**: {greeting}
+                        "#},
+                    )?) as Arc<dyn PromptController>,
+                ))
+            })
+            .collect()
+    }
+
+    async fn greeting_of(
+        prompt_controller_collection: &PromptControllerCollection,
+        name: &str,
+    ) -> Result<ContentBlock> {
+        Ok(prompt_controller_collection
+            .get(name)
+            .ok_or_else(|| anyhow!("Prompt '{name}' should exist"))?
+            .respond_to(PromptsGet::mock(name, Default::default()))
+            .await?
+            .messages[0]
+            .content
+            .clone())
+    }
+
+    #[tokio::test]
+    async fn test_merge_conflict_policies() -> Result<()> {
+        let core = || collection_with(&[("greet", "Hello from core")]);
+        let plugin = || {
+            collection_with(&[
+                ("greet", "Hello from plugin"),
+                ("farewell", "Goodbye from plugin"),
+            ])
+        };
+
+        let mut kept = core()?;
+
+        kept.merge(plugin()?, PromptControllerConflictPolicy::KeepExisting)?;
+
+        assert_eq!(greeting_of(&kept, "greet").await?, "Hello from core".into());
+        assert_eq!(
+            greeting_of(&kept, "farewell").await?,
+            "Goodbye from plugin".into()
+        );
+
+        let mut overwritten = core()?;
+
+        overwritten.merge(plugin()?, PromptControllerConflictPolicy::Overwrite)?;
+
+        assert_eq!(
+            greeting_of(&overwritten, "greet").await?,
+            "Hello from plugin".into()
+        );
+
+        let mut rejected = core()?;
+
+        let Err(err) = rejected.merge(plugin()?, PromptControllerConflictPolicy::Error) else {
+            return Err(anyhow!("Merge should fail on a conflicting name"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Prompts are defined in both collections: 'greet'"
+        );
+        assert!(rejected.get("farewell").is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_esbuild_metafile_swap_updates_asset_urls() -> Result<()> {
+        let esbuild_metafile_with_output = |output: &str| -> Result<Arc<EsbuildMetaFile>> {
+            Ok(Arc::new(EsbuildMetaFile::from_str(&formatdoc! {r#"
+                {{
+                    "outputs": {{
+                        "{output}": {{
+                            "imports": [],
+                            "inputs": {{
+                                "resources/images/logo.svg": {{}}
+                            }}
+                        }}
+                    }}
+                }}
+            "#})?))
+        };
+        let with_asset = PromptDocumentController {
+            esbuild_metafile: esbuild_metafile_with_output("static/logo-1111.svg")?,
+            ..PromptDocumentController::mock(
+                "with-asset",
+                indoc! {r#"
+                +++
+                description = "with asset"
+                title = "With asset"
+
+                [arguments]
+                +++
+
+                **user**: Load {context.assets.file("resources/images/logo.svg")}
+                "#},
+            )?
+        };
+        let without_asset: Arc<dyn PromptController> = Arc::new(PromptDocumentController::mock(
+            "without-asset",
+            indoc! {r#"
+            +++
+            description = "without asset"
+            title = "Without asset"
+
+            [arguments]
+            +++
+
+            **user**: Hello
+            "#},
+        )?);
+        let prompt_controller_collection: PromptControllerCollection = [
+            (
+                "with-asset".to_string(),
+                Arc::new(with_asset) as Arc<dyn PromptController>,
+            ),
+            ("without-asset".to_string(), without_asset.clone()),
+        ]
+        .into_iter()
+        .collect();
+        let respond_with_asset =
+            async |prompt_controller_collection: &PromptControllerCollection| {
+                prompt_controller_collection
+                    .get("with-asset")
+                    .ok_or_else(|| anyhow!("Prompt 'with-asset' is missing"))?
+                    .respond_to(PromptsGet::mock("with-asset", Default::default()))
+                    .await
+            };
+
+        assert_eq!(
+            respond_with_asset(&prompt_controller_collection)
+                .await?
+                .messages[0]
+                .content,
+            "Load https://example.com/static/logo-1111.svg".into()
+        );
+
+        let updated_prompt_controller_collection = prompt_controller_collection
+            .with_esbuild_metafile(esbuild_metafile_with_output("static/logo-2222.svg")?);
+
+        assert_eq!(
+            respond_with_asset(&updated_prompt_controller_collection)
+                .await?
+                .messages[0]
+                .content,
+            "Load https://example.com/static/logo-2222.svg".into()
+        );
+        assert!(Arc::ptr_eq(
+            &updated_prompt_controller_collection.prompt_controllers["without-asset"],
+            &without_asset
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_prompts_pages_without_overlap() -> Result<()> {
+        let prompt_controller_collection: PromptControllerCollection = (0..10)
+            .map(|index| -> Result<(String, Arc<dyn PromptController>)> {
+                let name = format!("prompt-{index}");
+                let prompt_controller = PromptDocumentController::mock(
+                    &name,
+                    &formatdoc! {r#"
+                    +++
+                    description = "Prompt {index}"
+                    title = "Prompt {index}"
+
+                    [arguments]
+                    +++
+
+                    **user**: Hello
+                    "#},
+                )?;
+
+                Ok((name, Arc::new(prompt_controller)))
+            })
+            .collect::<Result<_>>()?;
+        let mut cursor: Option<ListResourcesCursor> = None;
+        let mut listed_names: Vec<String> = Vec::new();
+        let mut pages: usize = 0;
+
+        loop {
+            let PromptsList {
+                next_cursor,
+                prompts,
+            } = prompt_controller_collection.list_prompts(cursor, 3);
+
+            pages += 1;
+            listed_names.extend(prompts.into_iter().map(|prompt| prompt.name));
+
+            match next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+
+        let unique_names: BTreeSet<&String> = listed_names.iter().collect();
+
+        assert_eq!(pages, 4);
+        assert_eq!(listed_names.len(), 10);
+        assert_eq!(unique_names.len(), 10);
+        assert_eq!(
+            listed_names,
+            prompt_controller_collection
+                .prompt_controllers
+                .keys()
+                .cloned()
+                .collect::<Vec<String>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_prompts_with_empty_page_size_advances() -> Result<()> {
+        let prompt_controller_collection: PromptControllerCollection = ["first", "second"]
+            .into_iter()
+            .map(|name| -> Result<(String, Arc<dyn PromptController>)> {
+                let prompt_controller = PromptDocumentController::mock(
+                    name,
+                    &formatdoc! {r#"
+                    +++
+                    description = "{name}"
+                    title = "{name}"
+
+                    [arguments]
+                    +++
+
+                    **user**: Hello
+                    "#},
+                )?;
+
+                Ok((name.to_string(), Arc::new(prompt_controller)))
+            })
+            .collect::<Result<_>>()?;
+
+        let first_page = prompt_controller_collection.list_prompts(None, 0);
+
+        assert_eq!(first_page.prompts.len(), 1);
+        assert_eq!(first_page.prompts[0].name, "first");
+
+        let second_page = prompt_controller_collection.list_prompts(first_page.next_cursor, 0);
+
+        assert_eq!(second_page.prompts.len(), 1);
+        assert_eq!(second_page.prompts[0].name, "second");
+        assert!(second_page.next_cursor.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reload_swaps_only_one_prompt() -> Result<()> {
+        let contents = |greeting: &str| {
+            formatdoc! {r#"
+            +++
+            description = "greeting"
+            title = "Greeting"
+
+            [arguments]
+            +++
+
+            **user**: {greeting}
+            "#}
+        };
+        let respond_with_text =
+            async |prompt_controller_collection: &PromptControllerCollection,
+                   name: &str|
+                   -> Result<ContentBlock> {
+                let response = prompt_controller_collection
+                    .get(name)
+                    .ok_or_else(|| anyhow!("Prompt '{name}' is missing"))?
+                    .respond_to(PromptsGet::mock(name, Default::default()))
+                    .await?;
+
+                Ok(response.messages[0].content.clone())
+            };
+        let mut prompt_controller_collection: PromptControllerCollection = ["hello", "welcome"]
+            .into_iter()
+            .map(|name| -> Result<(String, Arc<dyn PromptController>)> {
+                Ok((
+                    name.to_string(),
+                    Arc::new(PromptDocumentController::mock(name, &contents(name))?),
+                ))
+            })
+            .collect::<Result<_>>()?;
+        let welcome_before = prompt_controller_collection
+            .get("welcome")
+            .ok_or_else(|| anyhow!("Prompt 'welcome' is missing"))?;
+
+        prompt_controller_collection.reload("hello", &contents("Hello again"))?;
+
+        assert_eq!(
+            respond_with_text(&prompt_controller_collection, "hello").await?,
+            "Hello again".into()
+        );
+        assert!(Arc::ptr_eq(
+            &welcome_before,
+            &prompt_controller_collection
+                .get("welcome")
+                .ok_or_else(|| anyhow!("Prompt 'welcome' is missing"))?
+        ));
+
+        assert!(
+            prompt_controller_collection
+                .reload("hello", "**user**: No front matter")
+                .is_err()
+        );
+        assert_eq!(
+            respond_with_text(&prompt_controller_collection, "hello").await?,
+            "Hello again".into()
+        );
+        assert!(
+            prompt_controller_collection
+                .reload("missing", &contents("Hi"))
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[derive(Clone, Default)]
+    struct CountingPromptController {
+        is_static: bool,
+        renders: Arc<Mutex<usize>>,
+    }
+
+    #[async_trait]
+    impl PromptController for CountingPromptController {
+        fn clone_box(&self) -> Box<dyn PromptController> {
+            Box::new(self.clone())
+        }
+
+        fn describe(&self) -> PromptDescription {
+            PromptDescription {
+                arguments: Default::default(),
+                collections: Default::default(),
+                components: Default::default(),
+                description: "Counts renders".to_string(),
+                is_static: self.is_static,
+                name: "counting".to_string(),
+                title: "Counting".to_string(),
+            }
+        }
+
+        fn get_mcp_prompt(&self) -> Prompt {
+            Prompt {
+                arguments: Default::default(),
+                description: "Counts renders".to_string(),
+                meta: None,
+                name: "counting".to_string(),
+                title: "Counting".to_string(),
+            }
+        }
+
+        async fn respond_to(&self, _request: PromptsGet) -> Result<PromptsGetResult> {
+            let mut renders = self.renders.lock().expect("Renders lock is poisoned");
+
+            *renders += 1;
+
+            Ok(PromptsGetResult {
+                description: None,
+                messages: vec![PromptMessage {
+                    content: format!("Render {renders}").into(),
+                    role: Role::User,
+                }],
+                meta: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_static_prompts_with_identical_output_are_duplicates() -> Result<()> {
+        let prompt = |name: &str,
+                      arguments: &str,
+                      body: &str|
+         -> Result<(String, Arc<dyn PromptController>)> {
+            Ok((
+                name.to_string(),
+                Arc::new(PromptDocumentController::mock(
+                    name,
+                    &format!(
+                        "+++\ndescription = \"{name}\"\ntitle = \"{name}\"\n\n{arguments}\n+++\n\n**user**: {body}\n"
+                    ),
+                )?),
+            ))
+        };
+        let prompt_controller_collection: PromptControllerCollection = [
+            prompt("explain", "[arguments]", "Explain this code")?,
+            prompt("explain-copy", "[arguments]", "Explain this code")?,
+            prompt(
+                "explain-topic",
+                "[arguments.topic]\ndefault = \"code\"\ndescription = \"Topic\"\nrequired = false\ntitle = \"Topic\"",
+                "Explain this code",
+            )?,
+            prompt("review", "[arguments]", "Review this code")?,
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            prompt_controller_collection
+                .render_static_prompts()
+                .await
+                .find_duplicates(),
+            vec![("explain".to_string(), "explain-copy".to_string())]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_warm_caches_static_prompts_without_arguments() -> Result<()> {
+        let dynamic_prompt_controller = CountingPromptController::default();
+        let static_prompt_controller = CountingPromptController {
+            is_static: true,
+            ..Default::default()
+        };
+        let mut prompt_controller_collection: PromptControllerCollection = [
+            (
+                "dynamic".to_string(),
+                Arc::new(dynamic_prompt_controller.clone()) as Arc<dyn PromptController>,
+            ),
+            (
+                "static".to_string(),
+                Arc::new(static_prompt_controller.clone()) as Arc<dyn PromptController>,
+            ),
+            (
+                "summarize".to_string(),
+                Arc::new(PromptDocumentController::mock(
+                    "summarize",
+                    indoc! {r#"
+                    +++
+                    description = "summarize"
+                    title = "Summarize"
+
+                    [arguments.text]
+                    description = "Text to summarize"
+                    required = true
+                    title = "Text"
+                    +++
+
+                    **user**: Summarize {context.arguments.text.input}
+                    "#},
+                )?),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let static_prompt_renders = prompt_controller_collection.render_static_prompts().await;
+
+        prompt_controller_collection.warm(&static_prompt_renders);
+
+        let renders = |prompt_controller: &CountingPromptController| {
+            *prompt_controller
+                .renders
+                .lock()
+                .expect("Renders lock is poisoned")
+        };
+
+        assert_eq!(renders(&static_prompt_controller), 1);
+        assert_eq!(renders(&dynamic_prompt_controller), 0);
+
+        for name in ["dynamic", "static"] {
+            for _ in 0..2 {
+                prompt_controller_collection
+                    .get(name)
+                    .ok_or_else(|| anyhow!("Prompt '{name}' is missing"))?
+                    .respond_to(PromptsGet::mock(name, Default::default()))
+                    .await?;
+            }
+        }
+
+        assert_eq!(renders(&static_prompt_controller), 1);
+        assert_eq!(renders(&dynamic_prompt_controller), 2);
+
+        Ok(())
     }
 }
@@ -0,0 +1,8 @@
+/// What to do when two prompt collections contain a prompt with the same name
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PromptControllerConflictPolicy {
+    #[default]
+    Error,
+    KeepExisting,
+    Overwrite,
+}
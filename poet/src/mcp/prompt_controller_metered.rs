@@ -0,0 +1,250 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use esbuild_metafile::EsbuildMetaFile;
+
+use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
+use crate::mcp::jsonrpc::response::success::completion_complete::Completion;
+use crate::mcp::jsonrpc::response::success::prompts_get_result::PromptsGetResult;
+use crate::mcp::prompt::Prompt;
+use crate::mcp::prompt_controller::PromptController;
+use crate::mcp::prompt_controller_cached::PromptControllerCached;
+use crate::mcp::prompt_description::PromptDescription;
+use crate::mcp::prompt_metrics::PromptMetrics;
+use crate::prompt_render_cancelled::PromptRenderCancelled;
+
+#[derive(Clone)]
+pub struct PromptControllerMetered {
+    pub inner: Arc<dyn PromptController>,
+    pub metrics: Arc<dyn PromptMetrics>,
+}
+
+#[async_trait]
+impl PromptController for PromptControllerMetered {
+    fn clone_box(&self) -> Box<dyn PromptController> {
+        Box::new(self.clone())
+    }
+
+    fn with_esbuild_metafile(
+        &self,
+        esbuild_metafile: Arc<EsbuildMetaFile>,
+    ) -> Option<Box<dyn PromptController>> {
+        Some(Box::new(PromptControllerMetered {
+            inner: self.inner.with_esbuild_metafile(esbuild_metafile)?.into(),
+            metrics: self.metrics.clone(),
+        }))
+    }
+
+    fn with_cached_result(
+        &self,
+        cached_result: Arc<PromptsGetResult>,
+    ) -> Box<dyn PromptController> {
+        Box::new(PromptControllerMetered {
+            inner: Arc::new(PromptControllerCached {
+                cached_result,
+                inner: self.inner.clone(),
+                metrics: self.metrics.clone(),
+            }),
+            metrics: self.metrics.clone(),
+        })
+    }
+
+    fn with_source(&self, source: &str) -> Option<Result<Box<dyn PromptController>>> {
+        Some(self.inner.with_source(source)?.map(|inner| {
+            Box::new(PromptControllerMetered {
+                inner: inner.into(),
+                metrics: self.metrics.clone(),
+            }) as Box<dyn PromptController>
+        }))
+    }
+
+    fn complete(&self, argument_name: &str, partial_value: &str) -> Completion {
+        self.inner.complete(argument_name, partial_value)
+    }
+
+    fn describe(&self) -> PromptDescription {
+        self.inner.describe()
+    }
+
+    fn get_mcp_prompt(&self) -> Prompt {
+        self.inner.get_mcp_prompt()
+    }
+
+    async fn respond_to(&self, request: PromptsGet) -> Result<PromptsGetResult> {
+        let name = request.params.name.clone();
+        let started_at = Instant::now();
+        let result = self.inner.respond_to(request).await;
+
+        match &result {
+            Ok(_) => self.metrics.on_prompt_served(&name, started_at.elapsed()),
+            // The client gave up on the request, the prompt did not fail
+            Err(err) if err.chain().any(|cause| cause.is::<PromptRenderCancelled>()) => {}
+            Err(err) => self
+                .metrics
+                .on_prompt_failed(&name, started_at.elapsed(), err),
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use anyhow::Error;
+    use anyhow::anyhow;
+    use indoc::indoc;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::prompt_document_controller::PromptDocumentController;
+
+    #[derive(Default)]
+    struct PromptMetricsRecorder {
+        failed: Mutex<Vec<String>>,
+        served: Mutex<Vec<(String, Duration)>>,
+    }
+
+    impl PromptMetrics for PromptMetricsRecorder {
+        fn on_prompt_failed(&self, name: &str, _duration: Duration, _error: &Error) {
+            self.failed
+                .lock()
+                .expect("Failed prompts lock is poisoned")
+                .push(name.to_string());
+        }
+
+        fn on_prompt_served(&self, name: &str, duration: Duration) {
+            self.served
+                .lock()
+                .expect("Served prompts lock is poisoned")
+                .push((name.to_string(), duration));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_served_prompt_is_recorded() -> Result<()> {
+        let metrics = Arc::new(PromptMetricsRecorder::default());
+        let prompt_controller = PromptControllerMetered {
+            inner: Arc::new(PromptDocumentController::mock(
+                "greet",
+                indoc! {r#"
+                +++
+                description = "greet"
+                title = "Greet"
+
+                [arguments]
+                +++
+
+                **user**: Hello
+                "#},
+            )?),
+            metrics: metrics.clone(),
+        };
+
+        prompt_controller
+            .respond_to(PromptsGet::mock("greet", Default::default()))
+            .await?;
+
+        let served = metrics
+            .served
+            .lock()
+            .expect("Served prompts lock is poisoned");
+
+        assert_eq!(served.len(), 1);
+        assert_eq!(served[0].0, "greet");
+        assert!(served[0].1 > Duration::ZERO);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cache_hits_are_recorded() -> Result<()> {
+        let metrics = Arc::new(PromptMetricsRecorder::default());
+        let prompt_controller = PromptControllerMetered {
+            inner: Arc::new(PromptDocumentController::mock(
+                "greet",
+                indoc! {r#"
+                +++
+                description = "greet"
+                title = "Greet"
+
+                [arguments]
+                +++
+
+                **user**: Hello
+                "#},
+            )?),
+            metrics: metrics.clone(),
+        }
+        .with_cached_result(Arc::new(PromptsGetResult {
+            description: None,
+            messages: Default::default(),
+            meta: None,
+        }));
+
+        let response = prompt_controller
+            .respond_to(PromptsGet::mock("greet", Default::default()))
+            .await?;
+
+        assert!(response.messages.is_empty());
+        assert_eq!(
+            metrics
+                .served
+                .lock()
+                .expect("Served prompts lock is poisoned")
+                .len(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_prompt_is_not_a_failure() -> Result<()> {
+        let metrics = Arc::new(PromptMetricsRecorder::default());
+        let prompt_controller = PromptControllerMetered {
+            inner: Arc::new(PromptDocumentController::mock(
+                "greet",
+                indoc! {r#"
+                +++
+                description = "greet"
+                title = "Greet"
+
+                [arguments]
+                +++
+
+                **user**: Hello
+                "#},
+            )?),
+            metrics: metrics.clone(),
+        };
+        let cancellation_token = CancellationToken::new();
+
+        cancellation_token.cancel();
+
+        let Err(err) = prompt_controller
+            .respond_to(PromptsGet {
+                cancellation_token,
+                ..PromptsGet::mock("greet", Default::default())
+            })
+            .await
+        else {
+            return Err(anyhow!("Cancelled render should not produce a result"));
+        };
+
+        assert!(err.is::<PromptRenderCancelled>());
+        assert!(
+            metrics
+                .failed
+                .lock()
+                .expect("Failed prompts lock is poisoned")
+                .is_empty()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use esbuild_metafile::EsbuildMetaFile;
+
+use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
+use crate::mcp::jsonrpc::response::success::completion_complete::Completion;
+use crate::mcp::jsonrpc::response::success::prompts_get_result::PromptsGetResult;
+use crate::mcp::prompt::Prompt;
+use crate::mcp::prompt_controller::PromptController;
+use crate::mcp::prompt_description::PromptDescription;
+use crate::mcp::prompt_middleware::PromptMiddleware;
+use crate::mcp::prompt_middleware_next::PromptMiddlewareNext;
+
+#[derive(Clone)]
+pub struct PromptControllerWithMiddlewares {
+    pub inner: Arc<dyn PromptController>,
+    /// Outermost first
+    pub middlewares: Vec<Arc<dyn PromptMiddleware>>,
+}
+
+#[async_trait]
+impl PromptController for PromptControllerWithMiddlewares {
+    fn clone_box(&self) -> Box<dyn PromptController> {
+        Box::new(self.clone())
+    }
+
+    fn with_esbuild_metafile(
+        &self,
+        esbuild_metafile: Arc<EsbuildMetaFile>,
+    ) -> Option<Box<dyn PromptController>> {
+        Some(Box::new(PromptControllerWithMiddlewares {
+            inner: self.inner.with_esbuild_metafile(esbuild_metafile)?.into(),
+            middlewares: self.middlewares.clone(),
+        }))
+    }
+
+    fn with_source(&self, source: &str) -> Option<Result<Box<dyn PromptController>>> {
+        Some(self.inner.with_source(source)?.map(|inner| {
+            Box::new(PromptControllerWithMiddlewares {
+                inner: inner.into(),
+                middlewares: self.middlewares.clone(),
+            }) as Box<dyn PromptController>
+        }))
+    }
+
+    fn complete(&self, argument_name: &str, partial_value: &str) -> Completion {
+        self.inner.complete(argument_name, partial_value)
+    }
+
+    fn describe(&self) -> PromptDescription {
+        self.inner.describe()
+    }
+
+    fn get_mcp_prompt(&self) -> Prompt {
+        self.inner.get_mcp_prompt()
+    }
+
+    async fn respond_to(&self, request: PromptsGet) -> Result<PromptsGetResult> {
+        PromptMiddlewareNext {
+            inner: self.inner.as_ref(),
+            middlewares: &self.middlewares,
+        }
+        .run(request)
+        .await
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::mcp::prompt_argument_description::PromptArgumentDescription;
+
+fn argument_schema(
+    PromptArgumentDescription {
+        choices,
+        default,
+        description,
+        example,
+        kind,
+        pattern,
+        sensitive,
+        title,
+        trim,
+        ..
+    }: &PromptArgumentDescription,
+) -> Value {
+    let mut schema = Map::new();
+
+    schema.insert("description".to_string(), description.clone().into());
+    schema.insert("title".to_string(), title.clone().into());
+    // Every argument travels as a string, poet parses it into its kind
+    schema.insert("type".to_string(), "string".into());
+    schema.insert("x-poet-kind".to_string(), kind.to_string().into());
+
+    if !choices.is_empty() {
+        schema.insert("enum".to_string(), choices.clone().into());
+    }
+
+    if let Some(default) = default {
+        schema.insert("default".to_string(), wire_value(default));
+    }
+
+    if let Some(example) = example {
+        schema.insert("examples".to_string(), json!([wire_value(example)]));
+    }
+
+    // Rust regex syntax, which JSON Schema validators do not understand,
+    // matched against the whole input
+    if let Some(pattern) = pattern {
+        schema.insert("x-poet-pattern".to_string(), pattern.clone().into());
+    }
+
+    if *sensitive {
+        schema.insert("writeOnly".to_string(), true.into());
+    }
+
+    if *trim {
+        schema.insert("x-poet-trim".to_string(), true.into());
+    }
+
+    schema.into()
+}
+
+/// Text a client sends for the value
+fn wire_value(value: &Value) -> Value {
+    match value {
+        Value::String(_) => value.clone(),
+        other => other.to_string().into(),
+    }
+}
+
+/// Compact summary of a prompt for tooling and documentation
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PromptDescription {
+    pub arguments: Vec<PromptArgumentDescription>,
+    pub collections: Vec<String>,
+    pub components: BTreeSet<String>,
+    pub description: String,
+    /// Renders the same messages regardless of the request
+    pub is_static: bool,
+    pub name: String,
+    pub title: String,
+}
+
+impl PromptDescription {
+    /// JSON Schema of the arguments object. Arguments filled in from the
+    /// session are left out, since callers cannot provide them.
+    pub fn arguments_schema(&self) -> Value {
+        let arguments: Vec<&PromptArgumentDescription> = self
+            .arguments
+            .iter()
+            .filter(|argument| !argument.session_bound)
+            .collect();
+
+        json!({
+            "properties": arguments
+                .iter()
+                .map(|argument| (argument.name.clone(), argument_schema(argument)))
+                .collect::<Map<String, Value>>(),
+            "required": arguments
+                .iter()
+                .filter(|argument| argument.required)
+                .map(|argument| argument.name.clone())
+                .collect::<Vec<String>>(),
+            "type": "object",
+        })
+    }
+}
@@ -0,0 +1,14 @@
+/// A single line of a rendered message, compared between two renders
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PromptLineChange {
+    /// Only in the second render
+    Added(String),
+    /// Replaced in place, usually by a different interpolated value
+    Changed {
+        after: String,
+        before: String,
+    },
+    /// Only in the first render
+    Removed(String),
+    Unchanged(String),
+}
@@ -0,0 +1,204 @@
+use std::iter::once;
+
+use blake3::hash;
+
+use crate::mcp::content_block::ContentBlock;
+use crate::mcp::content_block::audio_content::AudioContent;
+use crate::mcp::content_block::embedded_resource::EmbeddedResource;
+use crate::mcp::content_block::image_content::ImageContent;
+use crate::mcp::content_block::resource_link::ResourceLink;
+use crate::mcp::content_block::text_content::TextContent;
+use crate::mcp::jsonrpc::role::Role;
+use crate::mcp::prompt_line_change::PromptLineChange;
+use crate::mcp::prompt_message::PromptMessage;
+use crate::mcp::resource_content::BlobResourceContent;
+use crate::mcp::resource_content::ResourceContent;
+use crate::mcp::resource_content::TextResourceContent;
+
+/// Media and blobs are compared by a one-line placeholder with a hash of
+/// their data, so replacing an image shows up as a changed line
+fn content_lines(content: &ContentBlock) -> Vec<String> {
+    match content {
+        ContentBlock::AudioContent(AudioContent { data, mime_type }) => {
+            vec![format!("[audio {mime_type}: {}]", hash(data.as_bytes()))]
+        }
+        ContentBlock::EmbeddedResource(EmbeddedResource {
+            resource: ResourceContent::Blob(BlobResourceContent { blob, uri, .. }),
+        }) => vec![format!("[resource {uri}: {}]", hash(blob.as_bytes()))],
+        ContentBlock::EmbeddedResource(EmbeddedResource {
+            resource: ResourceContent::Text(TextResourceContent { text, uri, .. }),
+        }) => once(format!("[resource {uri}]"))
+            .chain(text.lines().map(str::to_string))
+            .collect(),
+        ContentBlock::ImageContent(ImageContent { data, mime_type }) => {
+            vec![format!("[image {mime_type}: {}]", hash(data.as_bytes()))]
+        }
+        ContentBlock::ResourceLink(ResourceLink { uri, .. }) => {
+            vec![format!("[resource link: {uri}]")]
+        }
+        ContentBlock::TextContent(TextContent { text }) => {
+            text.lines().map(str::to_string).collect()
+        }
+    }
+}
+
+/// Pairs pending removed and added lines into changes, keeping the rest
+fn flush_pending(
+    removed: &mut Vec<String>,
+    added: &mut Vec<String>,
+    line_changes: &mut Vec<PromptLineChange>,
+) {
+    let paired = removed.len().min(added.len());
+
+    for (before_line, after_line) in removed.drain(..paired).zip(added.drain(..paired)) {
+        line_changes.push(PromptLineChange::Changed {
+            after: after_line,
+            before: before_line,
+        });
+    }
+
+    line_changes.extend(removed.drain(..).map(PromptLineChange::Removed));
+    line_changes.extend(added.drain(..).map(PromptLineChange::Added));
+}
+
+/// Line diff based on the longest common subsequence. A run of removed
+/// lines directly followed by added lines is paired up into changes.
+fn diff_lines(before: &[String], after: &[String]) -> Vec<PromptLineChange> {
+    let mut common_lengths = vec![vec![0usize; after.len() + 1]; before.len() + 1];
+
+    for before_index in (0..before.len()).rev() {
+        for after_index in (0..after.len()).rev() {
+            common_lengths[before_index][after_index] =
+                if before[before_index] == after[after_index] {
+                    common_lengths[before_index + 1][after_index + 1] + 1
+                } else {
+                    common_lengths[before_index + 1][after_index]
+                        .max(common_lengths[before_index][after_index + 1])
+                };
+        }
+    }
+
+    let mut line_changes: Vec<PromptLineChange> = Vec::new();
+    let mut removed: Vec<String> = Vec::new();
+    let mut added: Vec<String> = Vec::new();
+    let (mut before_index, mut after_index) = (0, 0);
+
+    while before_index < before.len() || after_index < after.len() {
+        if before_index < before.len()
+            && after_index < after.len()
+            && before[before_index] == after[after_index]
+        {
+            flush_pending(&mut removed, &mut added, &mut line_changes);
+            line_changes.push(PromptLineChange::Unchanged(before[before_index].clone()));
+            before_index += 1;
+            after_index += 1;
+        } else if after_index >= after.len()
+            || (before_index < before.len()
+                && common_lengths[before_index + 1][after_index]
+                    >= common_lengths[before_index][after_index + 1])
+        {
+            removed.push(before[before_index].clone());
+            before_index += 1;
+        } else {
+            added.push(after[after_index].clone());
+            after_index += 1;
+        }
+    }
+
+    flush_pending(&mut removed, &mut added, &mut line_changes);
+
+    line_changes
+}
+
+/// Differences between the messages at the same position in two renders.
+/// A message missing from one of the renders shows up as all lines added
+/// or removed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PromptMessageDiff {
+    pub index: usize,
+    pub line_changes: Vec<PromptLineChange>,
+    pub role_after: Option<Role>,
+    pub role_before: Option<Role>,
+}
+
+impl PromptMessageDiff {
+    pub fn new(
+        index: usize,
+        before: Option<&PromptMessage>,
+        after: Option<&PromptMessage>,
+    ) -> Self {
+        let lines_of = |prompt_message: Option<&PromptMessage>| {
+            prompt_message
+                .map(|prompt_message| content_lines(&prompt_message.content))
+                .unwrap_or_default()
+        };
+
+        Self {
+            index,
+            line_changes: diff_lines(&lines_of(before), &lines_of(after)),
+            role_after: after.map(|prompt_message| prompt_message.role.clone()),
+            role_before: before.map(|prompt_message| prompt_message.role.clone()),
+        }
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.role_after != self.role_before
+            || self
+                .line_changes
+                .iter()
+                .any(|line_change| !matches!(line_change, PromptLineChange::Unchanged(_)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_are_paired_into_changes() {
+        let prompt_message_diff = PromptMessageDiff::new(
+            0,
+            Some(&PromptMessage {
+                content: "Intro\nTopic: horses\nOutro".into(),
+                role: Role::User,
+            }),
+            Some(&PromptMessage {
+                content: "Intro\nTopic: goats\nOutro\nExtra".into(),
+                role: Role::User,
+            }),
+        );
+
+        assert!(prompt_message_diff.has_changes());
+        assert_eq!(
+            prompt_message_diff.line_changes,
+            vec![
+                PromptLineChange::Unchanged("Intro".to_string()),
+                PromptLineChange::Changed {
+                    after: "Topic: goats".to_string(),
+                    before: "Topic: horses".to_string(),
+                },
+                PromptLineChange::Unchanged("Outro".to_string()),
+                PromptLineChange::Added("Extra".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changed_image_data_is_a_change() {
+        let image = |data: &str| PromptMessage {
+            content: ContentBlock::ImageContent(ImageContent {
+                data: data.to_string(),
+                mime_type: "image/png".to_string(),
+            }),
+            role: Role::User,
+        };
+        let prompt_message_diff =
+            PromptMessageDiff::new(0, Some(&image("AAAA")), Some(&image("BBBB")));
+
+        assert!(prompt_message_diff.has_changes());
+        assert!(matches!(
+            prompt_message_diff.line_changes.as_slice(),
+            [PromptLineChange::Changed { .. }]
+        ));
+    }
+}
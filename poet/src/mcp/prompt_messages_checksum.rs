@@ -0,0 +1,12 @@
+use anyhow::Result;
+use blake3::hash;
+
+use crate::mcp::prompt_message::PromptMessage;
+
+/// Hashes roles and contents of the messages in order, so identical renders
+/// always produce the same checksum
+pub fn prompt_messages_checksum(prompt_messages: &[PromptMessage]) -> Result<String> {
+    Ok(hash(&serde_json::to_vec(prompt_messages)?)
+        .to_hex()
+        .to_string())
+}
@@ -0,0 +1,15 @@
+use std::time::Duration;
+
+use anyhow::Error;
+
+/// Hooks for reporting prompt handling to a metrics backend. Every hook is a
+/// no-op by default, so implementations only override what they record.
+pub trait PromptMetrics: Send + Sync {
+    fn on_cache_hit(&self, _name: &str) {}
+
+    fn on_cache_miss(&self, _name: &str) {}
+
+    fn on_prompt_failed(&self, _name: &str, _duration: Duration, _error: &Error) {}
+
+    fn on_prompt_served(&self, _name: &str, _duration: Duration) {}
+}
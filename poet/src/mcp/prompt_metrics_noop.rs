@@ -0,0 +1,5 @@
+use crate::mcp::prompt_metrics::PromptMetrics;
+
+pub struct PromptMetricsNoop;
+
+impl PromptMetrics for PromptMetricsNoop {}
@@ -0,0 +1,17 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
+use crate::mcp::jsonrpc::response::success::prompts_get_result::PromptsGetResult;
+use crate::mcp::prompt_middleware_next::PromptMiddlewareNext;
+
+#[async_trait]
+pub trait PromptMiddleware: Send + Sync {
+    /// Either short-circuits with its own result, or passes the (possibly
+    /// rewritten) request down the chain through `next`
+    async fn handle(
+        &self,
+        request: PromptsGet,
+        next: PromptMiddlewareNext<'_>,
+    ) -> Result<PromptsGetResult>;
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
+use crate::mcp::jsonrpc::response::success::prompts_get_result::PromptsGetResult;
+use crate::mcp::prompt_controller::PromptController;
+use crate::mcp::prompt_middleware::PromptMiddleware;
+
+/// Remainder of the middleware chain, ending with the prompt controller
+pub struct PromptMiddlewareNext<'chain> {
+    pub inner: &'chain dyn PromptController,
+    pub middlewares: &'chain [Arc<dyn PromptMiddleware>],
+}
+
+impl PromptMiddlewareNext<'_> {
+    pub async fn run(self, request: PromptsGet) -> Result<PromptsGetResult> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => {
+                middleware
+                    .handle(
+                        request,
+                        PromptMiddlewareNext {
+                            inner: self.inner,
+                            middlewares,
+                        },
+                    )
+                    .await
+            }
+            None => self.inner.respond_to(request).await,
+        }
+    }
+}
@@ -4,11 +4,14 @@ use actix_web::rt;
 use anyhow::Result;
 use anyhow::anyhow;
 use dashmap::DashMap;
+use dashmap::DashSet;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::mpsc::error::SendError;
 use tokio_util::sync::CancellationToken;
 
 use crate::mcp::jsonrpc::JSONRPC_VERSION;
+use crate::mcp::jsonrpc::id::Id;
+use crate::mcp::jsonrpc::implementation::Implementation;
 use crate::mcp::jsonrpc::notification::message::Message;
 use crate::mcp::jsonrpc::notification::message::MessageParams;
 use crate::mcp::jsonrpc::server_to_client_notification::ServerToClientNotification;
@@ -16,22 +19,60 @@ use crate::mcp::log_level::LogLevel;
 
 #[derive(Clone)]
 pub struct Session {
+    client_info: Implementation,
+    in_flight_requests: Arc<DashMap<Id, CancellationToken>>,
     log_level: LogLevel,
     notification_tx: Sender<ServerToClientNotification>,
+    /// Prompts the client was already warned about ending with a prefill
+    prefill_warned_prompts: Arc<DashSet<String>>,
+    prompts_list_changed: bool,
+    protocol_version: String,
     resource_subscriptions: Arc<DashMap<String, CancellationToken>>,
     session_id: String,
 }
 
 impl Session {
-    pub fn new(notification_tx: Sender<ServerToClientNotification>, session_id: String) -> Self {
+    pub fn new(
+        client_info: Implementation,
+        notification_tx: Sender<ServerToClientNotification>,
+        prompts_list_changed: bool,
+        protocol_version: String,
+        session_id: String,
+    ) -> Self {
         Self {
+            client_info,
+            in_flight_requests: Default::default(),
             log_level: LogLevel::Info,
             notification_tx,
+            prefill_warned_prompts: Default::default(),
+            prompts_list_changed,
+            protocol_version,
             resource_subscriptions: Default::default(),
             session_id,
         }
     }
 
+    /// Returns false if the request is not in flight, for example because it
+    /// has already been answered
+    pub fn cancel_request(&self, id: &Id) -> bool {
+        match self.in_flight_requests.remove(id) {
+            Some((_, cancellation_token)) => {
+                cancellation_token.cancel();
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn client_info(&self) -> Implementation {
+        self.client_info.clone()
+    }
+
+    pub fn finish_request(&self, id: &Id) {
+        self.in_flight_requests.remove(id);
+    }
+
     pub fn id(&self) -> String {
         self.session_id.clone()
     }
@@ -63,6 +104,33 @@ impl Session {
         self.notification_tx.send(notification).await
     }
 
+    /// Returns true only the first time it is called for a prompt, so the
+    /// warning is not repeated on every request
+    pub fn mark_prefill_warned(&self, prompt_name: &str) -> bool {
+        self.prefill_warned_prompts.insert(prompt_name.to_string())
+    }
+
+    /// Whether the server told the client during initialization that the
+    /// prompt list can change
+    pub fn prompts_list_changed(&self) -> bool {
+        self.prompts_list_changed
+    }
+
+    /// The version agreed on during initialization
+    pub fn protocol_version(&self) -> String {
+        self.protocol_version.clone()
+    }
+
+    /// The returned token is cancelled if the client cancels the request
+    pub fn start_request(&self, id: Id) -> CancellationToken {
+        let cancellation_token = CancellationToken::new();
+
+        self.in_flight_requests
+            .insert(id, cancellation_token.clone());
+
+        cancellation_token
+    }
+
     pub async fn subscribe_to_resource(&self, uri: &str) -> Result<CancellationToken> {
         if self.resource_subscriptions.contains_key(uri) {
             let message = format!("You are already subscribed to '{uri}'");
@@ -100,6 +168,10 @@ impl Session {
     }
 
     pub async fn terminate(self) {
+        for ref_multi in self.in_flight_requests.iter() {
+            ref_multi.value().cancel();
+        }
+
         for ref_multi in self.resource_subscriptions.iter() {
             ref_multi.value().cancel();
         }
@@ -107,10 +179,44 @@ impl Session {
 
     pub fn with_log_level(self, log_level: LogLevel) -> Self {
         Self {
+            client_info: self.client_info,
+            in_flight_requests: self.in_flight_requests,
             log_level,
             notification_tx: self.notification_tx,
+            prefill_warned_prompts: self.prefill_warned_prompts,
+            prompts_list_changed: self.prompts_list_changed,
+            protocol_version: self.protocol_version,
             resource_subscriptions: self.resource_subscriptions,
             session_id: self.session_id,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::mcp::MCP_PROTOCOL_VERSION;
+
+    #[test]
+    fn test_prefill_warning_is_marked_once_per_prompt() {
+        let (notification_tx, _notification_rx) = mpsc::channel(1);
+        let session = Session::new(
+            Implementation {
+                description: None,
+                name: "test-client".to_string(),
+                title: None,
+                version: "1.0.0".to_string(),
+            },
+            notification_tx,
+            false,
+            MCP_PROTOCOL_VERSION.to_string(),
+            "test-session".to_string(),
+        );
+
+        assert!(session.mark_prefill_warned("continue"));
+        assert!(!session.clone().mark_prefill_warned("continue"));
+        assert!(session.mark_prefill_warned("other"));
+    }
+}
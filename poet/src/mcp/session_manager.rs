@@ -1,13 +1,17 @@
 use std::sync::Arc;
 
-use actix_web::Result;
+use actix_web::Error;
 use actix_web::dev::ServiceRequest;
 use actix_web::error::ErrorInternalServerError;
+use anyhow::Result;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::SendError;
 use uuid::Uuid;
 
 use crate::mcp::MCP_HEADER_SESSION;
+use crate::mcp::jsonrpc::JSONRPC_VERSION;
+use crate::mcp::jsonrpc::implementation::Implementation;
+use crate::mcp::jsonrpc::notification::prompts_list_changed::PromptsListChanged;
 use crate::mcp::jsonrpc::server_to_client_notification::ServerToClientNotification;
 use crate::mcp::session::Session;
 use crate::mcp::session_storage::SessionStorage;
@@ -36,20 +40,52 @@ impl SessionManager {
         Ok(())
     }
 
-    pub async fn restore_session(&self, req: &ServiceRequest) -> Result<Option<Session>> {
-        match req.headers().get(MCP_HEADER_SESSION) {
-            Some(session_id) => {
-                self.session_storage
-                    .read(session_id.to_str().map_err(ErrorInternalServerError)?)
-                    .await
+    /// Skips sessions that were not told the prompt list can change
+    pub async fn notify_prompts_list_changed(
+        &self,
+    ) -> Result<(), SendError<ServerToClientNotification>> {
+        for entry in &self.session_storage.sessions {
+            let session = entry.value();
+
+            if session.prompts_list_changed() {
+                session
+                    .notify(ServerToClientNotification::PromptsListChanged(
+                        PromptsListChanged {
+                            jsonrpc: JSONRPC_VERSION.to_string(),
+                        },
+                    ))
+                    .await?;
             }
+        }
+
+        Ok(())
+    }
+
+    pub async fn restore_session(&self, req: &ServiceRequest) -> Result<Option<Session>, Error> {
+        match req.headers().get(MCP_HEADER_SESSION) {
+            Some(session_id) => self
+                .session_storage
+                .read(session_id.to_str().map_err(ErrorInternalServerError)?)
+                .await
+                .map_err(ErrorInternalServerError),
             None => Ok(None),
         }
     }
 
-    pub async fn start_new_session(&self) -> Result<SessionWithNotificationsReceiver> {
+    pub async fn start_new_session(
+        &self,
+        client_info: Implementation,
+        prompts_list_changed: bool,
+        protocol_version: String,
+    ) -> Result<SessionWithNotificationsReceiver> {
         let (notification_tx, notification_rx) = mpsc::channel(30);
-        let session = Session::new(notification_tx, generate_session_id());
+        let session = Session::new(
+            client_info,
+            notification_tx,
+            prompts_list_changed,
+            protocol_version,
+            generate_session_id(),
+        );
 
         self.session_storage
             .store_new_session(session.clone())
@@ -1,4 +1,4 @@
-use actix_web::Result;
+use anyhow::Result;
 use dashmap::DashMap;
 
 use crate::mcp::session::Session;
@@ -0,0 +1,39 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use log::warn;
+
+use crate::mcp::jsonrpc::response::success::prompts_get_result::PromptsGetResult;
+use crate::mcp::prompt_messages_checksum::prompt_messages_checksum;
+
+/// Every static prompt that takes no arguments, rendered once, by prompt
+/// name. Shared by duplicate detection and warming, so neither has to
+/// render the prompts again.
+#[derive(Default)]
+pub struct StaticPromptRenders {
+    pub results: BTreeMap<String, Arc<PromptsGetResult>>,
+}
+
+impl StaticPromptRenders {
+    /// Pairs each prompt with the first prompt, by name, that rendered the
+    /// same messages
+    pub fn find_duplicates(&self) -> Vec<(String, String)> {
+        let mut first_name_by_checksum: HashMap<String, &String> = HashMap::new();
+        let mut duplicates: Vec<(String, String)> = Vec::new();
+
+        for (name, prompts_get_result) in &self.results {
+            match prompt_messages_checksum(&prompts_get_result.messages) {
+                Ok(checksum) => match first_name_by_checksum.get(&checksum) {
+                    Some(first_name) => duplicates.push(((*first_name).clone(), name.clone())),
+                    None => {
+                        first_name_by_checksum.insert(checksum, name);
+                    }
+                },
+                Err(err) => warn!("Unable to check prompt '{name}' for duplicates: {err:#}"),
+            }
+        }
+
+        duplicates
+    }
+}
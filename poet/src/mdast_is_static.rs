@@ -0,0 +1,18 @@
+use markdown::mdast::Node;
+
+use crate::find_components_in_mdast::find_components_in_mdast;
+
+fn contains_expression(mdast: &Node) -> bool {
+    match mdast {
+        Node::MdxFlowExpression(_) | Node::MdxTextExpression(_) => true,
+        _ => mdast
+            .children()
+            .is_some_and(|children| children.iter().any(contains_expression)),
+    }
+}
+
+/// A document without expressions or components renders the same messages
+/// regardless of the request
+pub fn mdast_is_static(mdast: &Node) -> bool {
+    !contains_expression(mdast) && find_components_in_mdast(mdast).is_empty()
+}
@@ -0,0 +1,44 @@
+use std::collections::HashSet;
+
+use markdown::mdast::Node;
+
+use crate::find_components_in_mdast::find_components_in_mdast;
+use crate::find_unknown_context_references_in_mdast::find_unknown_context_references_in_mdast;
+
+/// Components receive the whole context, so any of them may resolve assets
+/// without the document mentioning `context.assets` directly
+pub fn mdast_uses_assets(mdast: &Node) -> bool {
+    find_unknown_context_references_in_mdast(mdast, "context", &HashSet::new())
+        .contains("context.assets")
+        || !find_components_in_mdast(mdast).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use indoc::indoc;
+
+    use super::*;
+    use crate::string_to_mdast::string_to_mdast;
+
+    #[test]
+    fn test_asset_usage_is_detected() -> Result<()> {
+        let plain = string_to_mdast(indoc! {r#"
+            **user**: Hello, {context.arguments.name.input}
+        "#})?;
+        let with_expression = string_to_mdast(indoc! {r#"
+            **user**: Look at {context.assets.file("resources/logo.png")}
+        "#})?;
+        let with_component = string_to_mdast(indoc! {r#"
+            **user**: Look at this:
+
+            <Logo />
+        "#})?;
+
+        assert!(!mdast_uses_assets(&plain));
+        assert!(mdast_uses_assets(&with_expression));
+        assert!(mdast_uses_assets(&with_component));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+/// Characters that fence code blocks in rendered Markdown
+const CODE_FENCE_CHARACTERS: [char; 2] = ['`', '~'];
+
+/// Shortest run of fence characters that opens a code block
+const MIN_CODE_FENCE_LENGTH: usize = 3;
+
+/// Fence character and length of the run the line starts with
+fn code_fence_run(trimmed_line: &str) -> Option<(char, usize)> {
+    let fence_character = trimmed_line.chars().next()?;
+
+    if !CODE_FENCE_CHARACTERS.contains(&fence_character) {
+        return None;
+    }
+
+    let length = trimmed_line
+        .chars()
+        .take_while(|character| *character == fence_character)
+        .count();
+
+    if length < MIN_CODE_FENCE_LENGTH {
+        return None;
+    }
+
+    Some((fence_character, length))
+}
+
+/// Replaces typographic quotes and dashes with their ASCII equivalents,
+/// leaving code blocks and code spans as they are
+pub fn normalize_smart_punctuation(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    let mut open_code_fence: Option<(char, usize)> = None;
+
+    for line in text.split_inclusive('\n') {
+        let trimmed_line = line.trim_start();
+
+        if let Some((open_character, open_length)) = open_code_fence {
+            // Only a bare run at least as long as the opening one closes it
+            if let Some((fence_character, length)) = code_fence_run(trimmed_line)
+                && fence_character == open_character
+                && length >= open_length
+                && trimmed_line[length..].trim().is_empty()
+            {
+                open_code_fence = None;
+            }
+
+            normalized.push_str(line);
+
+            continue;
+        }
+
+        if let Some(code_fence) = code_fence_run(trimmed_line) {
+            open_code_fence = Some(code_fence);
+            normalized.push_str(line);
+
+            continue;
+        }
+
+        let mut is_in_code_span = false;
+
+        for character in line.chars() {
+            match character {
+                '`' => {
+                    is_in_code_span = !is_in_code_span;
+                    normalized.push(character);
+                }
+                _ if is_in_code_span => normalized.push(character),
+                '\u{2018}' | '\u{2019}' | '\u{201A}' => normalized.push('\''),
+                '\u{201C}' | '\u{201D}' | '\u{201E}' => normalized.push('"'),
+                '\u{2013}' => normalized.push('-'),
+                '\u{2014}' => normalized.push_str("--"),
+                '\u{2026}' => normalized.push_str("..."),
+                _ => normalized.push(character),
+            }
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_is_left_as_is() {
+        assert_eq!(
+            normalize_smart_punctuation(
+                "“quoted” `‘span’`\n```rust\nlet quote = ‘kept’;\n```\nit’s"
+            ),
+            "\"quoted\" `‘span’`\n```rust\nlet quote = ‘kept’;\n```\nit's"
+        );
+    }
+
+    #[test]
+    fn test_shorter_fence_does_not_close_code_block() {
+        assert_eq!(
+            normalize_smart_punctuation(
+                "````markdown\n```\nit’s kept\n```\n‘still’ code\n````\nit’s"
+            ),
+            "````markdown\n```\nit’s kept\n```\n‘still’ code\n````\nit's"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::Error;
+use anyhow::Result;
+use anyhow::anyhow;
+use serde::de::DeserializeOwned;
+use toml::Deserializer;
+use toml::de::Error as TomlError;
+
+use crate::deserialize_front_matter::deserialize_front_matter;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+
+const DUPLICATE_KEY_MESSAGE: &str = "duplicate key";
+
+/// TOML does not name the key it found twice, and a copied line is an easy
+/// mistake to make, so that error gets a clearer message
+fn describe_parse_error(source: &str, err: TomlError) -> Error {
+    if err.message() == DUPLICATE_KEY_MESSAGE
+        && let Some(key) = err.span().and_then(|span| source.get(span))
+    {
+        return anyhow!("Front matter defines '{key}' more than once");
+    }
+
+    err.into()
+}
+
+pub fn parse_front_matter<TFrontMatter: DeserializeOwned>(
+    source: &str,
+    unknown_fields_policy: FrontMatterUnknownFieldsPolicy,
+) -> Result<TFrontMatter> {
+    let deserializer =
+        Deserializer::parse(source).map_err(|err| describe_parse_error(source, err))?;
+
+    deserialize_front_matter(deserializer, unknown_fields_policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use toml::Table;
+
+    use super::*;
+
+    #[test]
+    fn test_duplicate_key_is_reported_by_name() -> Result<()> {
+        let Err(err) = parse_front_matter::<Table>(
+            indoc! {r#"
+            title = "First"
+            description = """
+            title = "Not a key"
+            """
+            title = "Second"
+        "#},
+            Default::default(),
+        ) else {
+            return Err(anyhow!("Expected the duplicated key to be an error"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Front matter defines 'title' more than once"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_key_in_different_tables_is_allowed() -> Result<()> {
+        parse_front_matter::<Table>(
+            indoc! {r#"
+            title = "Prompt"
+
+            [arguments.first]
+            title = "First"
+
+            [arguments.second]
+            title = "Second"
+        "#},
+            Default::default(),
+        )?;
+
+        Ok(())
+    }
+}
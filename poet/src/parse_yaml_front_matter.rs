@@ -0,0 +1,15 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+
+use crate::deserialize_front_matter::deserialize_front_matter;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+
+pub fn parse_yaml_front_matter<TFrontMatter: DeserializeOwned>(
+    source: &str,
+    unknown_fields_policy: FrontMatterUnknownFieldsPolicy,
+) -> Result<TFrontMatter> {
+    deserialize_front_matter(
+        serde_norway::Deserializer::from_str(source),
+        unknown_fields_policy,
+    )
+}
@@ -0,0 +1,12 @@
+/// Names and sources of the components every prompt can use. A shortcode
+/// with the same name takes their place.
+pub const PROMPT_BUILTIN_COMPONENTS: [(&str, &str); 2] = [
+    (
+        "Audio",
+        include_str!("prompt_builtin_components/Audio.rhai"),
+    ),
+    (
+        "Resource",
+        include_str!("prompt_builtin_components/Resource.rhai"),
+    ),
+];
@@ -0,0 +1,14 @@
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::prompt_document_front_matter::argument::Argument;
+
+/// Settings shared by every prompt that belongs to a collection
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PromptCollectionConfig {
+    /// Merged into member prompts, unless a prompt declares the same argument
+    #[serde(default)]
+    pub arguments: IndexMap<String, Argument>,
+}
@@ -0,0 +1,199 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use globset::GlobBuilder;
+use globset::GlobSet;
+use globset::GlobSetBuilder;
+
+use crate::filesystem::file_entry::FileEntry;
+
+const GLOB_METACHARACTERS: [char; 6] = ['*', '?', '[', ']', '{', '}'];
+
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut glob_set_builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        glob_set_builder.add(GlobBuilder::new(pattern).literal_separator(true).build()?);
+    }
+
+    Ok(glob_set_builder.build()?)
+}
+
+/// The part of an include glob that prompt names leave out
+#[derive(Clone, Debug)]
+struct IncludeRoot {
+    /// Leading directories of the glob without any wildcards
+    directory: PathBuf,
+    /// Literal end of the file name, like `.prompt.md`
+    suffix: String,
+}
+
+impl From<&String> for IncludeRoot {
+    fn from(pattern: &String) -> Self {
+        let mut segments: Vec<&str> = pattern.split('/').collect();
+        let file_name = segments.pop().unwrap_or_default();
+
+        Self {
+            directory: segments
+                .into_iter()
+                .take_while(|segment| !segment.contains(GLOB_METACHARACTERS))
+                .collect(),
+            suffix: match file_name.rfind(GLOB_METACHARACTERS) {
+                Some(index) => file_name[index + 1..].to_string(),
+                None => String::new(),
+            },
+        }
+    }
+}
+
+/// Decides which project files are built as prompts, and how their names
+/// are derived from their paths
+#[derive(Clone, Debug)]
+pub struct PromptDiscovery {
+    exclude: GlobSet,
+    include: GlobSet,
+    /// Same order as the include globs
+    include_roots: Vec<IncludeRoot>,
+    /// Limits the build to prompts with matching names, for quick iteration
+    /// on a few of them
+    names: Option<GlobSet>,
+}
+
+impl PromptDiscovery {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        Ok(Self {
+            exclude: build_glob_set(exclude)?,
+            include: build_glob_set(include)?,
+            include_roots: include.iter().map(IncludeRoot::from).collect(),
+            names: None,
+        })
+    }
+
+    /// Directories that can contain prompts, so they can be watched
+    pub fn directories(&self) -> BTreeSet<PathBuf> {
+        self.include_roots
+            .iter()
+            .map(|include_root| include_root.directory.clone())
+            .collect()
+    }
+
+    /// Prompts left out by the name filter are still not content documents
+    pub fn is_included(&self, relative_path: &Path) -> bool {
+        self.include.is_match(relative_path) && !self.exclude.is_match(relative_path)
+    }
+
+    pub fn is_prompt(&self, file: &FileEntry) -> bool {
+        self.is_included(&file.relative_path)
+            && self
+                .names
+                .as_ref()
+                .is_none_or(|names| names.is_match(self.prompt_name(file)))
+    }
+
+    /// Path relative to the directory of the include glob that matched,
+    /// without the literal file name suffix of that glob
+    pub fn prompt_name(&self, file: &FileEntry) -> String {
+        let Some(include_root) = self
+            .include
+            .matches(&file.relative_path)
+            .first()
+            .and_then(|index| self.include_roots.get(*index))
+        else {
+            return file.get_stem_relative_to(&PathBuf::new());
+        };
+
+        let relative_path = file
+            .relative_path
+            .strip_prefix(&include_root.directory)
+            .unwrap_or(&file.relative_path)
+            .to_string_lossy();
+
+        match relative_path.strip_suffix(&include_root.suffix) {
+            Some(name) if !include_root.suffix.is_empty() => name.to_string(),
+            _ => file.get_stem_relative_to(&include_root.directory),
+        }
+    }
+
+    /// An empty list keeps every prompt
+    pub fn with_name_filter(self, names: &[String]) -> Result<Self> {
+        Ok(Self {
+            names: if names.is_empty() {
+                None
+            } else {
+                Some(build_glob_set(names)?)
+            },
+            ..self
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filesystem::file_entry_stub::FileEntryStub;
+
+    fn file_entry(relative_path: &str) -> Result<FileEntry> {
+        FileEntryStub {
+            contents: String::new(),
+            relative_path: PathBuf::from(relative_path),
+        }
+        .try_into()
+    }
+
+    #[test]
+    fn test_prompts_directory_is_included() -> Result<()> {
+        let prompt_discovery = PromptDiscovery::new(&["prompts/**/*.md".to_string()], &[])?;
+
+        assert!(prompt_discovery.is_prompt(&file_entry("prompts/guides/review.md")?));
+        assert!(!prompt_discovery.is_prompt(&file_entry("content/guides/review.md")?));
+        assert!(!prompt_discovery.is_prompt(&file_entry("prompts/guides/review.toml")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_include_and_exclude_globs() -> Result<()> {
+        let prompt_discovery = PromptDiscovery::new(
+            &[
+                "content/**/*.prompt.md".to_string(),
+                "prompts/**/*.md".to_string(),
+            ],
+            &["**/drafts/**".to_string()],
+        )?;
+
+        assert!(prompt_discovery.is_prompt(&file_entry("content/guides/review.prompt.md")?));
+        assert!(prompt_discovery.is_prompt(&file_entry("prompts/summarize.md")?));
+        assert!(!prompt_discovery.is_prompt(&file_entry("content/guides/review.md")?));
+        assert!(!prompt_discovery.is_prompt(&file_entry("content/drafts/idea.prompt.md")?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prompt_name_is_relative_to_the_matching_include_glob() -> Result<()> {
+        let prompt_discovery = PromptDiscovery::new(
+            &[
+                "content/**/*.prompt.md".to_string(),
+                "prompts/**/*.md".to_string(),
+            ],
+            &[],
+        )?;
+
+        assert_eq!(
+            prompt_discovery.prompt_name(&file_entry("content/guides/review.prompt.md")?),
+            "guides/review"
+        );
+        assert_eq!(
+            prompt_discovery.prompt_name(&file_entry("prompts/guides/summarize.md")?),
+            "guides/summarize"
+        );
+        assert_eq!(
+            prompt_discovery.directories(),
+            BTreeSet::from([PathBuf::from("content"), PathBuf::from("prompts")])
+        );
+
+        Ok(())
+    }
+}
@@ -1,35 +1,131 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::mem::take;
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::time::Instant;
 
 use anyhow::Result;
 use anyhow::anyhow;
+use log::trace;
+use regex::Regex;
+use rhai::Array;
 use rhai::CustomType;
 use rhai::Dynamic;
 use rhai::EvalAltResult;
 use rhai::Map;
 use rhai::TypeBuilder;
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
 
 use crate::asset_manager::AssetManager;
 use crate::content_document_linker::ContentDocumentLinker;
+use crate::estimate_tokens::estimate_tokens;
+use crate::mcp::content_block::ContentBlock;
+use crate::mcp::content_block::audio_content::AudioContent;
+use crate::mcp::content_block::embedded_resource::EmbeddedResource;
+use crate::mcp::content_block::image_content::ImageContent;
+use crate::mcp::content_block::text_content::TextContent;
 use crate::mcp::jsonrpc::role::Role;
 use crate::mcp::prompt_message::PromptMessage;
+use crate::mcp::resource_content::ResourceContent;
+use crate::mcp::resource_content::TextResourceContent;
+use crate::mcp::resource_provider::ResourceProvider as _;
+use crate::mcp::resource_template_provider::ResourceTemplateProvider as _;
+use crate::mcp_resource_provider_content_documents::McpResourceProviderContentDocuments;
+use crate::prompt_document_component_context_checkpoint::PromptDocumentComponentContextCheckpoint;
 use crate::prompt_document_front_matter::PromptDocumentFrontMatter;
 use crate::prompt_document_front_matter::argument_with_input::ArgumentWithInput;
+use crate::prompt_document_role::PromptDocumentRole;
+use crate::prompt_image_policy::PromptImagePolicy;
+use crate::prompt_random::PromptRandom;
+use crate::prompt_render_limits::PromptRenderLimits;
+use crate::prompt_system_role_lowering::PromptSystemRoleLowering;
+use crate::token_counter::TokenCounter;
+
+/// Delimits the placeholder of an embedded content block in the message text
+const EMBEDDED_CONTENT_BLOCK_MARKER: char = '\u{FFFC}';
+
+/// Starts every system block that is kept in place as a user message
+const SYSTEM_MESSAGE_PREFIX: &str = "System:";
 
 #[derive(Clone)]
 pub struct PromptDocumentComponentContext {
+    pub allow_remote_images: bool,
     pub arguments: HashMap<String, ArgumentWithInput>,
     pub asset_manager: AssetManager,
+    /// Cancelled when the client no longer waits for the result
+    pub cancellation_token: CancellationToken,
     pub content_document_linker: ContentDocumentLinker,
-    pub current_role: Option<Role>,
+    /// Shared with the clones that expressions receive, like the rest of the
+    /// message state, so expressions can switch roles
+    pub current_role: Arc<RwLock<Option<PromptDocumentRole>>>,
+    /// Unique to the render, so placeholders cannot be forged by the content
+    /// that surrounds them
+    pub embedded_content_block_nonce: String,
+    /// Referenced by index from the placeholders in the message text
+    pub embedded_content_blocks: Arc<RwLock<Vec<ContentBlock>>>,
+    /// Bodies retrieved for `<Fetch>` components, by URL
+    pub fetched_contents: HashMap<String, String>,
     pub front_matter: PromptDocumentFrontMatter,
-    pub prompt_messages: Vec<PromptMessage>,
+    pub image_policy: PromptImagePolicy,
+    /// Local images read ahead of time, since evaluation is synchronous
+    pub inline_images: Arc<HashMap<String, ImageContent>>,
+    pub prompt_messages: Arc<RwLock<Vec<PromptMessage>>>,
+    pub prompt_name: String,
+    pub random: PromptRandom,
+    pub render_limits: PromptRenderLimits,
+    pub render_started_at: Instant,
+    pub request: Map,
+    pub result_meta: BTreeMap<String, Value>,
+    pub system_role_lowering: PromptSystemRoleLowering,
+    /// Texts of system blocks, in order, waiting to be lowered into the
+    /// leading user message
+    pub system_texts: Arc<RwLock<Vec<String>>>,
+    pub token_counter: Arc<dyn TokenCounter>,
     pub unprocessed_message_chunk: Arc<RwLock<String>>,
 }
 
 impl PromptDocumentComponentContext {
+    /// Appends a top-level block, separated from the previous one by a blank
+    /// line, so paragraphs and blockquotes keep their markdown structure
+    pub fn append_block_to_message(&mut self, block: String) -> Result<()> {
+        if block.is_empty() {
+            return Ok(());
+        }
+
+        let has_previous_block = !self
+            .unprocessed_message_chunk
+            .read()
+            .expect("Unprocessed message lock is poisoned")
+            .is_empty();
+
+        if has_previous_block {
+            self.append_to_message("\n\n".to_string())?;
+        }
+
+        self.append_to_message(block)
+    }
+
+    /// Checked between nodes, so a runaway render is aborted instead of
+    /// being reported only after it finishes
+    pub fn assert_within_render_deadline(&self) -> Result<()> {
+        self.render_limits
+            .assert_rendered_within_deadline(self.render_started_at.elapsed())
+    }
+
+    /// Ends the current message and starts a new one with the same role
+    pub fn break_message(&mut self) -> Result<()> {
+        let role = self
+            .current_role
+            .read()
+            .expect("Current role lock is poisoned")
+            .clone()
+            .ok_or_else(|| anyhow!("Tried to break a message, but there is no role set"))?;
+
+        self.switch_role_to(role)
+    }
+
     pub fn append_to_message(&mut self, chunk: String) -> Result<()> {
         if !chunk.is_empty() {
             let mut unprocessed_message_chunk = self
@@ -43,6 +139,117 @@ impl PromptDocumentComponentContext {
         Ok(())
     }
 
+    pub fn embed_audio(&mut self, src: &str) -> Result<String> {
+        let audio_content = AudioContent::from_base64_data_uri(src)?;
+
+        Ok(self.embed_content_block(ContentBlock::AudioContent(audio_content)))
+    }
+
+    /// Content document behind a `<Resource />` component, by its resource
+    /// URI, path, or `#id`
+    pub fn embed_resource(&mut self, uri: &str) -> Result<String> {
+        let content_documents = McpResourceProviderContentDocuments::default();
+        let resource_uri_prefix = format!("{}/", content_documents.resource_uri_prefix());
+        let path = uri.strip_prefix(&resource_uri_prefix).unwrap_or(uri);
+        let basename = self
+            .content_document_linker
+            .resolve_reference(path)
+            .map_err(|err| anyhow!("Resource '{uri}' cannot be embedded: {err}"))?
+            .to_string();
+        let text = self
+            .content_document_linker
+            .linked_content(&basename)
+            .map_err(|err| anyhow!("Resource '{uri}' cannot be embedded: {err}"))?
+            .to_string();
+
+        Ok(
+            self.embed_content_block(ContentBlock::EmbeddedResource(EmbeddedResource {
+                resource: ResourceContent::Text(TextResourceContent {
+                    mime_type: content_documents.mime_type(),
+                    text,
+                    uri: content_documents.resource_uri(&basename),
+                }),
+            })),
+        )
+    }
+
+    /// Content blocks cannot be mixed with text inside a single message, so
+    /// they are split into separate messages of the same role when flushing
+    pub fn embed_content_block(&mut self, content_block: ContentBlock) -> String {
+        let mut embedded_content_blocks = self
+            .embedded_content_blocks
+            .write()
+            .expect("Embedded content blocks lock is poisoned");
+        let index = embedded_content_blocks.len();
+
+        embedded_content_blocks.push(content_block);
+
+        format!(
+            "{EMBEDDED_CONTENT_BLOCK_MARKER}{}:{index}{EMBEDDED_CONTENT_BLOCK_MARKER}",
+            self.embedded_content_block_nonce
+        )
+    }
+
+    pub fn checkpoint(&self) -> PromptDocumentComponentContextCheckpoint {
+        PromptDocumentComponentContextCheckpoint {
+            current_role: self
+                .current_role
+                .read()
+                .expect("Current role lock is poisoned")
+                .clone(),
+            embedded_content_blocks_count: self.embedded_content_blocks_count(),
+            prompt_messages_count: self.prompt_messages_count(),
+            result_meta: self.result_meta.clone(),
+            system_texts_count: self
+                .system_texts
+                .read()
+                .expect("System texts lock is poisoned")
+                .len(),
+            unprocessed_message_chunk: self
+                .unprocessed_message_chunk
+                .read()
+                .expect("Unprocessed message lock is poisoned")
+                .clone(),
+        }
+    }
+
+    pub fn embedded_content_blocks_count(&self) -> usize {
+        self.embedded_content_blocks
+            .read()
+            .expect("Embedded content blocks lock is poisoned")
+            .len()
+    }
+
+    /// Tokens in the messages rendered so far, including the one that is
+    /// still being written. Embedded content blocks other than text are not
+    /// counted.
+    pub fn estimate_rendered_tokens(&self) -> usize {
+        let unprocessed_message_chunk = self
+            .unprocessed_message_chunk
+            .read()
+            .expect("Unprocessed message lock is poisoned");
+
+        self.prompt_messages
+            .read()
+            .expect("Prompt messages lock is poisoned")
+            .iter()
+            .map(|prompt_message| match &prompt_message.content {
+                ContentBlock::TextContent(TextContent { text }) => {
+                    estimate_tokens(self.token_counter.as_ref(), text)
+                }
+                _ => 0,
+            })
+            .sum::<usize>()
+            + estimate_tokens(self.token_counter.as_ref(), &unprocessed_message_chunk)
+    }
+
+    pub fn has_role(&self) -> bool {
+        self.current_role
+            .read()
+            .expect("Current role lock is poisoned")
+            .is_some()
+    }
+
     pub fn flush(&mut self) -> Result<()> {
         let unprocessed_message_chunk = take(
             &mut *self
@@ -51,27 +258,241 @@ impl PromptDocumentComponentContext {
                 .expect("Unprocessed message lock is poisoned"),
         );
 
-        if let Some(role) = self.current_role.take() {
-            self.prompt_messages.push(PromptMessage {
-                content: unprocessed_message_chunk.into(),
-                role,
-            });
+        let current_role = self
+            .current_role
+            .write()
+            .expect("Current role lock is poisoned")
+            .take();
 
-            Ok(())
-        } else if unprocessed_message_chunk.is_empty() {
-            Ok(())
-        } else {
-            Err(anyhow!("Tried to flush messages, but there is no role set"))
+        match current_role {
+            Some(PromptDocumentRole::Message(role)) => {
+                let embedded_content_block_placeholder =
+                    self.embedded_content_block_placeholder()?;
+
+                if !embedded_content_block_placeholder.is_match(&unprocessed_message_chunk) {
+                    trace!("Flushing {role:?} message");
+
+                    return self.push_prompt_message(PromptMessage {
+                        content: unprocessed_message_chunk.into(),
+                        role,
+                    });
+                }
+
+                trace!("Flushing {role:?} message with embedded content blocks");
+
+                let mut text_start = 0;
+
+                for captures in
+                    embedded_content_block_placeholder.captures_iter(&unprocessed_message_chunk)
+                {
+                    let placeholder = captures.get_match();
+                    let (_, [index]) = captures.extract();
+                    let index: usize = index.parse()?;
+
+                    self.push_text_message(
+                        &unprocessed_message_chunk[text_start..placeholder.start()],
+                        &role,
+                    )?;
+
+                    let content_block = self
+                        .embedded_content_blocks
+                        .read()
+                        .expect("Embedded content blocks lock is poisoned")
+                        .get(index)
+                        .cloned()
+                        .ok_or_else(|| anyhow!("Embedded content block {index} is missing"))?;
+
+                    self.push_prompt_message(PromptMessage {
+                        content: content_block,
+                        role: role.clone(),
+                    })?;
+
+                    text_start = placeholder.end();
+                }
+
+                self.push_text_message(&unprocessed_message_chunk[text_start..], &role)
+            }
+            Some(PromptDocumentRole::System) => {
+                if self
+                    .embedded_content_block_placeholder()?
+                    .is_match(&unprocessed_message_chunk)
+                {
+                    return Err(anyhow!("System blocks can only contain text"));
+                }
+
+                let system_text = unprocessed_message_chunk.trim();
+
+                if system_text.is_empty() {
+                    return Ok(());
+                }
+
+                match self.system_role_lowering {
+                    PromptSystemRoleLowering::MergeIntoFirstUserMessage => {
+                        self.system_texts
+                            .write()
+                            .expect("System texts lock is poisoned")
+                            .push(system_text.to_string());
+
+                        Ok(())
+                    }
+                    PromptSystemRoleLowering::SeparateUserMessage => {
+                        self.push_prompt_message(PromptMessage {
+                            content: format!("{SYSTEM_MESSAGE_PREFIX} {system_text}").into(),
+                            role: Role::User,
+                        })
+                    }
+                }
+            }
+            None if unprocessed_message_chunk.is_empty() => Ok(()),
+            None => Err(anyhow!("Tried to flush messages, but there is no role set")),
         }
     }
 
-    pub fn switch_role_to(&mut self, role: Role) -> Result<()> {
+    pub fn prompt_messages_count(&self) -> usize {
+        self.prompt_messages
+            .read()
+            .expect("Prompt messages lock is poisoned")
+            .len()
+    }
+
+    /// Undoes messages, role switches and result meta that happened after
+    /// the checkpoint was taken
+    pub fn restore_checkpoint(
+        &mut self,
+        PromptDocumentComponentContextCheckpoint {
+            current_role,
+            embedded_content_blocks_count,
+            prompt_messages_count,
+            result_meta,
+            system_texts_count,
+            unprocessed_message_chunk,
+        }: PromptDocumentComponentContextCheckpoint,
+    ) {
+        *self
+            .current_role
+            .write()
+            .expect("Current role lock is poisoned") = current_role;
+        *self
+            .unprocessed_message_chunk
+            .write()
+            .expect("Unprocessed message lock is poisoned") = unprocessed_message_chunk;
+        self.result_meta = result_meta;
+        self.system_texts
+            .write()
+            .expect("System texts lock is poisoned")
+            .truncate(system_texts_count);
+        self.truncate_embedded_content_blocks(embedded_content_blocks_count);
+        self.truncate_prompt_messages(prompt_messages_count);
+    }
+
+    pub fn switch_role_to(&mut self, role: PromptDocumentRole) -> Result<()> {
+        trace!("Switching role to {role:?}");
+
         self.flush()?;
-        self.current_role = Some(role);
+        *self
+            .current_role
+            .write()
+            .expect("Current role lock is poisoned") = Some(role);
+
+        Ok(())
+    }
+
+    /// System texts go into the leading user message, or into a new one if
+    /// the prompt starts with something else
+    pub fn take_prompt_messages(&mut self) -> Vec<PromptMessage> {
+        let mut prompt_messages = take(
+            &mut *self
+                .prompt_messages
+                .write()
+                .expect("Prompt messages lock is poisoned"),
+        );
+        let system_texts = take(
+            &mut *self
+                .system_texts
+                .write()
+                .expect("System texts lock is poisoned"),
+        );
+
+        if system_texts.is_empty() {
+            return prompt_messages;
+        }
+
+        let system_text = system_texts.join("\n\n");
+
+        if let Some(PromptMessage {
+            content: ContentBlock::TextContent(TextContent { text }),
+            role: Role::User,
+        }) = prompt_messages.first_mut()
+        {
+            *text = format!("{system_text}\n\n{text}");
+        } else {
+            prompt_messages.insert(
+                0,
+                PromptMessage {
+                    content: system_text.into(),
+                    role: Role::User,
+                },
+            );
+        }
+
+        prompt_messages
+    }
+
+    pub fn truncate_embedded_content_blocks(&mut self, len: usize) {
+        self.embedded_content_blocks
+            .write()
+            .expect("Embedded content blocks lock is poisoned")
+            .truncate(len);
+    }
+
+    pub fn truncate_prompt_messages(&mut self, len: usize) {
+        self.prompt_messages
+            .write()
+            .expect("Prompt messages lock is poisoned")
+            .truncate(len);
+    }
+
+    fn embedded_content_block_placeholder(&self) -> Result<Regex> {
+        Ok(Regex::new(&format!(
+            "{EMBEDDED_CONTENT_BLOCK_MARKER}{}:([0-9]+){EMBEDDED_CONTENT_BLOCK_MARKER}",
+            self.embedded_content_block_nonce
+        ))?)
+    }
+
+    /// Enforced while messages accumulate, so a runaway template fails
+    /// before it produces all of its output
+    fn push_prompt_message(&mut self, prompt_message: PromptMessage) -> Result<()> {
+        let mut prompt_messages = self
+            .prompt_messages
+            .write()
+            .expect("Prompt messages lock is poisoned");
+
+        if let Some(max_messages) = self.render_limits.max_messages
+            && prompt_messages.len() >= max_messages
+        {
+            return Err(anyhow!(
+                "Prompt '{}' produces more than {max_messages} messages",
+                self.prompt_name
+            ));
+        }
+
+        prompt_messages.push(prompt_message);
 
         Ok(())
     }
 
+    /// Skips the whitespace between embedded content blocks
+    fn push_text_message(&mut self, text: &str, role: &Role) -> Result<()> {
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+
+        self.push_prompt_message(PromptMessage {
+            content: text.trim().into(),
+            role: role.clone(),
+        })
+    }
+
     fn rhai_append_to_message(&mut self, chunk: String) -> Result<(), Box<EvalAltResult>> {
         if let Err(err) = self.append_to_message(chunk) {
             Err(Box::new(EvalAltResult::ErrorSystem(
@@ -83,6 +504,33 @@ impl PromptDocumentComponentContext {
         }
     }
 
+    fn rhai_embed_audio(&mut self, src: &str) -> Result<String, Box<EvalAltResult>> {
+        self.embed_audio(src).map_err(|err| {
+            Box::new(EvalAltResult::ErrorSystem(
+                "Unable to embed audio".to_string(),
+                err.into(),
+            ))
+        })
+    }
+
+    fn rhai_embed_resource(&mut self, uri: &str) -> Result<String, Box<EvalAltResult>> {
+        self.embed_resource(uri).map_err(|err| {
+            Box::new(EvalAltResult::ErrorSystem(
+                "Unable to embed resource".to_string(),
+                err.into(),
+            ))
+        })
+    }
+
+    fn rhai_excerpt(&mut self, excerpt_type: &str) -> Result<String, Box<EvalAltResult>> {
+        self.front_matter
+            .excerpts
+            .iter()
+            .find(|excerpt| excerpt.excerpt_type == excerpt_type)
+            .map(|excerpt| excerpt.content.clone())
+            .ok_or_else(|| format!("Prompt has no excerpt of type '{excerpt_type}'").into())
+    }
+
     fn rhai_get_arguments(&mut self) -> Map {
         self.arguments
             .clone()
@@ -99,17 +547,47 @@ impl PromptDocumentComponentContext {
         self.front_matter.clone()
     }
 
+    fn rhai_get_request(&mut self) -> Map {
+        self.request.clone()
+    }
+
+    fn rhai_linked_content(&mut self, path: &str) -> Result<String, Box<EvalAltResult>> {
+        Ok(self
+            .content_document_linker
+            .linked_content(path)?
+            .to_string())
+    }
+
     fn rhai_link_to(&mut self, path: &str) -> Result<String, Box<EvalAltResult>> {
         Ok(self.content_document_linker.link_to(path)?)
     }
 
+    fn rhai_link_to_id(&mut self, id: &str) -> Result<String, Box<EvalAltResult>> {
+        Ok(self.content_document_linker.link_to_id(id)?)
+    }
+
+    fn rhai_random(&mut self, upper_bound: i64) -> Result<i64, Box<EvalAltResult>> {
+        self.random.below(upper_bound).map_err(|err| {
+            Box::new(EvalAltResult::ErrorSystem(
+                "Unable to pick a random number".to_string(),
+                err.into(),
+            ))
+        })
+    }
+
+    fn rhai_shuffle(&mut self, mut items: Array) -> Array {
+        self.random.shuffle(&mut items);
+
+        items
+    }
+
     fn rhai_switch_role_to(&mut self, role_string: String) -> Result<(), Box<EvalAltResult>> {
-        let role: Role = match role_string.clone().try_into() {
+        let role: PromptDocumentRole = match role_string.clone().try_into() {
             Ok(role) => role,
             Err(err) => {
                 return Err(Box::new(EvalAltResult::ErrorSystem(
                     format!(
-                        "Unknown role name: '{role_string} (you can only use 'assistant' or 'user')"
+                        "Unknown role name: '{role_string} (you can only use 'assistant', 'system' or 'user')"
                     ),
                     err.into(),
                 )));
@@ -134,8 +612,16 @@ impl CustomType for PromptDocumentComponentContext {
             .with_get("arguments", Self::rhai_get_arguments)
             .with_get("assets", Self::rhai_get_assets)
             .with_get("front_matter", Self::rhai_get_front_matter)
+            .with_get("request", Self::rhai_get_request)
             .with_fn("append_to_message", Self::rhai_append_to_message)
+            .with_fn("embed_audio", Self::rhai_embed_audio)
+            .with_fn("embed_resource", Self::rhai_embed_resource)
+            .with_fn("excerpt", Self::rhai_excerpt)
+            .with_fn("random", Self::rhai_random)
+            .with_fn("shuffle", Self::rhai_shuffle)
             .with_fn("link_to", Self::rhai_link_to)
+            .with_fn("link_to_id", Self::rhai_link_to_id)
+            .with_fn("linked_content", Self::rhai_linked_content)
             .with_fn("switch_role_to", Self::rhai_switch_role_to);
     }
 }
@@ -0,0 +1,16 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::prompt_document_role::PromptDocumentRole;
+
+/// Message state of a render at some point, so the side effects of nodes
+/// that end up dropped can be undone
+pub struct PromptDocumentComponentContextCheckpoint {
+    pub current_role: Option<PromptDocumentRole>,
+    pub embedded_content_blocks_count: usize,
+    pub prompt_messages_count: usize,
+    pub result_meta: BTreeMap<String, Value>,
+    pub system_texts_count: usize,
+    pub unprocessed_message_chunk: String,
+}
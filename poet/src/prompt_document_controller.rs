@@ -1,38 +1,452 @@
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::mem::take;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
+use anyhow::Context as _;
 use anyhow::Result;
+use anyhow::anyhow;
 use async_trait::async_trait;
+use base64::Engine as _;
+use base64::engine::general_purpose;
 use esbuild_metafile::EsbuildMetaFile;
 use markdown::mdast::Node;
+use rhai::Dynamic;
 use rhai_components::rhai_template_renderer::RhaiTemplateRenderer;
+use tokio::time::timeout;
+use url::Url;
+use uuid::Uuid;
 
 use crate::asset_manager::AssetManager;
 use crate::asset_path_renderer::AssetPathRenderer;
+use crate::build_prompt_document_controller::build_prompt_document_controller;
+use crate::build_prompt_document_controller_params::BuildPromptDocumentControllerParams;
 use crate::content_document_linker::ContentDocumentLinker;
 use crate::eval_prompt_document_mdast::eval_prompt_document_mdast;
 use crate::eval_prompt_document_mdast_params::EvalPromptDocumentMdastParams;
+use crate::filesystem::Filesystem;
+use crate::filesystem::file_entry_stub::FileEntryStub;
+use crate::find_components_in_mdast::find_components_in_mdast;
+use crate::find_local_image_urls_in_mdast::find_local_image_urls_in_mdast;
+use crate::front_matter_unknown_fields_policy::FrontMatterUnknownFieldsPolicy;
+use crate::mcp::content_block::image_content::ImageContent;
 use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
 use crate::mcp::jsonrpc::request::prompts_get::PromptsGetParams;
+use crate::mcp::jsonrpc::response::success::completion_complete::Completion;
 use crate::mcp::jsonrpc::response::success::prompts_get_result::PromptsGetResult;
+use crate::mcp::jsonrpc::response::success::prompts_get_result::PromptsGetResultMeta;
+use crate::mcp::jsonrpc::role::Role;
 use crate::mcp::prompt::Prompt;
 use crate::mcp::prompt::PromptArgument;
+use crate::mcp::prompt::PromptMeta;
+use crate::mcp::prompt_argument_description::PromptArgumentDescription;
 use crate::mcp::prompt_controller::PromptController;
+use crate::mcp::prompt_description::PromptDescription;
+use crate::mcp::prompt_message::PromptMessage;
+use crate::mcp::prompt_messages_checksum::prompt_messages_checksum;
+use crate::mdast_is_static::mdast_is_static;
+use crate::prompt_collection_config::PromptCollectionConfig;
 use crate::prompt_document_component_context::PromptDocumentComponentContext;
 use crate::prompt_document_front_matter::PromptDocumentFrontMatter;
 use crate::prompt_document_front_matter::argument::Argument;
+use crate::prompt_document_role::PromptDocumentRole;
+use crate::prompt_fetch_policy::PromptFetchPolicy;
+use crate::prompt_fetcher::PromptFetcher;
+use crate::prompt_image_policy::PromptImagePolicy;
+use crate::prompt_provenance::PromptProvenance;
+use crate::prompt_random::PromptRandom;
+use crate::prompt_render_limits::PromptRenderLimits;
+use crate::prompt_render_limits_policy::PromptRenderLimitsPolicy;
+use crate::prompt_render_pipeline::PromptRenderPipeline;
+use crate::prompt_request_metadata::prompt_request_metadata;
+use crate::prompt_uncategorized_policy::PromptUncategorizedPolicy;
+use crate::resolve_local_image_path::resolve_local_image_path;
+use crate::token_counter::TokenCounter;
 
+#[derive(Clone)]
 pub struct PromptDocumentController {
+    /// Passes remote images through as resource links when they are inlined
+    pub allow_remote_images: bool,
     pub asset_path_renderer: AssetPathRenderer,
     pub content_document_linker: ContentDocumentLinker,
     pub esbuild_metafile: Arc<EsbuildMetaFile>,
+    pub fetch_policy: PromptFetchPolicy,
+    /// Retrieved before every render, since evaluation is synchronous
+    pub fetch_urls: BTreeSet<String>,
+    pub fetcher: Arc<dyn PromptFetcher>,
     pub front_matter: PromptDocumentFrontMatter,
+    pub image_policy: PromptImagePolicy,
+    /// Local images read from the project files, by their URL in the
+    /// document. Only loaded when images are inlined.
+    pub inline_images: Arc<HashMap<String, ImageContent>>,
     pub name: String,
     pub mdast: Node,
+    /// Kept, with the other build inputs below, to rebuild the prompt from
+    /// updated source
+    pub prompt_collection_configs: Arc<HashMap<String, PromptCollectionConfig>>,
+    pub provenance: Option<PromptProvenance>,
+    /// Makes randomness helpers deterministic across renders
+    pub random_seed: Option<u64>,
+    pub relative_path: PathBuf,
+    pub render_limits: PromptRenderLimits,
+    pub render_limits_policy: PromptRenderLimitsPolicy,
+    pub render_pipeline: PromptRenderPipeline,
     pub rhai_template_renderer: RhaiTemplateRenderer,
+    pub source: String,
+    pub token_counter: Arc<dyn TokenCounter>,
+    pub uncategorized_policy: PromptUncategorizedPolicy,
+    pub unknown_fields_policy: FrontMatterUnknownFieldsPolicy,
+    /// Whether rendering may resolve assets against the esbuild metafile
+    pub uses_assets: bool,
+}
+
+impl PromptDocumentController {
+    #[cfg(test)]
+    pub fn mock(name: &str, contents: &str) -> Result<Self> {
+        build_prompt_document_controller(BuildPromptDocumentControllerParams::mock(name, contents)?)
+    }
+
+    /// Renders messages up to and including the one at the given index,
+    /// without evaluating the rest of the document. Nothing is fetched, so
+    /// documents with `<Fetch>` components cannot be rendered this way.
+    pub fn respond_up_to_message(
+        &self,
+        request: PromptsGet,
+        message_index: usize,
+    ) -> Result<PromptsGetResult> {
+        self.render(request, Some(message_index), Default::default())
+    }
+
+    /// Reads the local images the document refers to and encodes them, so
+    /// they can be inlined as image content when the prompt is rendered
+    pub async fn load_inline_images<TFilesystem: Filesystem>(
+        &mut self,
+        filesystem: &TFilesystem,
+    ) -> Result<()> {
+        if self.image_policy != PromptImagePolicy::Inline {
+            return Ok(());
+        }
+
+        let mut inline_images: HashMap<String, ImageContent> = HashMap::new();
+
+        for url in find_local_image_urls_in_mdast(&self.mdast) {
+            let image_path = resolve_local_image_path(&self.relative_path, &url)?;
+            let bytes = filesystem
+                .read_file_bytes(&image_path)
+                .await
+                .context(format!("Failed to load image '{url}'"))?;
+            let mime_type = mime_guess::from_path(&image_path)
+                .first()
+                .filter(|mime| mime.type_() == mime::IMAGE)
+                .ok_or_else(|| anyhow!("File '{url}' is not a recognized image type"))?;
+
+            inline_images.insert(
+                url,
+                ImageContent {
+                    data: general_purpose::STANDARD.encode(bytes),
+                    mime_type: mime_type.to_string(),
+                },
+            );
+        }
+
+        self.inline_images = Arc::new(inline_images);
+
+        Ok(())
+    }
+
+    fn assert_assistant_prefill(&self, prompt_messages: &[PromptMessage]) -> Result<()> {
+        match prompt_messages.last() {
+            Some(PromptMessage {
+                role: Role::Assistant,
+                ..
+            }) => Ok(()),
+            _ => Err(anyhow!(
+                "Prompt '{}' declares an assistant prefill, but its last message is not an assistant message",
+                self.name
+            )),
+        }
+    }
+
+    async fn fetch_contents(&self) -> Result<HashMap<String, String>> {
+        let mut fetched_contents: HashMap<String, String> = HashMap::new();
+
+        for fetch_url in &self.fetch_urls {
+            let url = Url::parse(fetch_url)
+                .with_context(|| format!("Prompt '{}' has an invalid fetch URL", self.name))?;
+
+            self.fetch_policy
+                .assert_allowed(&url)
+                .with_context(|| format!("Prompt '{}' cannot fetch '{url}'", self.name))?;
+
+            let contents = timeout(
+                self.fetch_policy.timeout,
+                self.fetcher.fetch(&url, self.fetch_policy.max_bytes),
+            )
+            .await
+            .map_err(|err| {
+                anyhow!(
+                    "Fetching '{url}' took longer than {}ms: {err}",
+                    self.fetch_policy.timeout.as_millis()
+                )
+            })??;
+
+            fetched_contents.insert(fetch_url.clone(), contents);
+        }
+
+        Ok(fetched_contents)
+    }
+
+    /// Images read for the previous source are kept, images that are new
+    /// to the updated source are linked until the next full build
+    fn rebuild(&self, source: &str) -> Result<Self> {
+        let prompt_document_controller =
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                allow_remote_images: self.allow_remote_images,
+                asset_path_renderer: self.asset_path_renderer.clone(),
+                content_document_linker: self.content_document_linker.clone(),
+                esbuild_metafile: self.esbuild_metafile.clone(),
+                fetch_policy: self.fetch_policy.clone(),
+                fetcher: self.fetcher.clone(),
+                file: FileEntryStub {
+                    contents: source.to_string(),
+                    relative_path: self.relative_path.clone(),
+                }
+                .try_into()?,
+                image_policy: self.image_policy,
+                name: self.name.clone(),
+                prompt_collection_configs: self.prompt_collection_configs.clone(),
+                provenance_meta: self.provenance.is_some(),
+                random_seed: self.random_seed,
+                render_limits_policy: self.render_limits_policy,
+                render_pipeline: self.render_pipeline.clone(),
+                rhai_template_renderer: self.rhai_template_renderer.clone(),
+                token_counter: self.token_counter.clone(),
+                uncategorized_policy: self.uncategorized_policy,
+                unknown_fields_policy: self.unknown_fields_policy,
+            })?;
+
+        Ok(Self {
+            inline_images: self.inline_images.clone(),
+            ..prompt_document_controller
+        })
+    }
+
+    /// The layout renders a preamble shared by the prompts that use it. It
+    /// goes into a system block that comes before the document.
+    fn render_layout(
+        &self,
+        layout: &str,
+        prompt_document_component_context: &mut PromptDocumentComponentContext,
+    ) -> Result<()> {
+        let preamble = self
+            .rhai_template_renderer
+            .render(
+                layout,
+                prompt_document_component_context.clone(),
+                Dynamic::from_map(prompt_document_component_context.front_matter.props.clone()),
+                Dynamic::from(String::new()),
+            )
+            .with_context(|| format!("Unable to render layout '{layout}'"))?;
+
+        prompt_document_component_context.switch_role_to(PromptDocumentRole::System)?;
+        prompt_document_component_context.append_block_to_message(preamble.trim().to_string())?;
+
+        // The document has to choose its own role
+        prompt_document_component_context.flush()
+    }
+
+    fn render(
+        &self,
+        request: PromptsGet,
+        target_message_index: Option<usize>,
+        fetched_contents: HashMap<String, String>,
+    ) -> Result<PromptsGetResult> {
+        let request_metadata = prompt_request_metadata(&request);
+        let PromptsGet {
+            cancellation_token,
+            client_info,
+            params: PromptsGetParams {
+                arguments, props, ..
+            },
+            ..
+        } = request;
+        let mut front_matter = self.front_matter.clone();
+
+        if let Some(props) = props {
+            front_matter.override_props(props)?;
+        }
+
+        let mut prompt_document_component_context = PromptDocumentComponentContext {
+            allow_remote_images: self.allow_remote_images,
+            arguments: self
+                .front_matter
+                .map_arguments(arguments, client_info.as_ref())?,
+            asset_manager: AssetManager::from_esbuild_metafile(
+                self.esbuild_metafile.clone(),
+                self.asset_path_renderer.clone(),
+            ),
+            cancellation_token,
+            content_document_linker: self.content_document_linker.clone(),
+            current_role: Default::default(),
+            embedded_content_block_nonce: Uuid::new_v4().simple().to_string(),
+            embedded_content_blocks: Default::default(),
+            fetched_contents,
+            front_matter,
+            image_policy: self.image_policy,
+            inline_images: self.inline_images.clone(),
+            prompt_messages: Default::default(),
+            prompt_name: self.name.clone(),
+            random: PromptRandom::new(self.random_seed),
+            render_limits: self.render_limits,
+            render_started_at: Instant::now(),
+            request: request_metadata,
+            result_meta: self.front_matter.result_meta.clone(),
+            system_role_lowering: self.render_pipeline.system_role_lowering,
+            system_texts: Default::default(),
+            token_counter: self.token_counter.clone(),
+            unprocessed_message_chunk: Default::default(),
+        };
+
+        if let Some(layout) = &self.front_matter.layout {
+            self.render_layout(layout, &mut prompt_document_component_context)?;
+        }
+
+        eval_prompt_document_mdast(
+            EvalPromptDocumentMdastParams {
+                mdast: &self.mdast,
+                is_directly_in_root: false,
+                is_first_child: false,
+                is_in_top_paragraph: false,
+                rhai_template_renderer: &self.rhai_template_renderer,
+                source: &self.source,
+                target_message_index,
+            },
+            &mut prompt_document_component_context,
+        )?;
+
+        let prompt_messages = self.render_pipeline.apply(
+            prompt_document_component_context.take_prompt_messages(),
+            &self.front_matter,
+        );
+
+        for prompt_message in &prompt_messages {
+            self.render_limits.assert_message_fits(prompt_message)?;
+        }
+
+        let mut meta = PromptsGetResultMeta {
+            annotations: take(&mut prompt_document_component_context.result_meta),
+            provenance: self.provenance.clone(),
+            ..Default::default()
+        };
+
+        // A partial render does not end where the prefill is
+        if self.front_matter.assistant_prefill && target_message_index.is_none() {
+            self.assert_assistant_prefill(&prompt_messages)?;
+
+            meta.assistant_prefill = Some(true);
+        }
+
+        if self.front_matter.checksum {
+            meta.checksum = Some(prompt_messages_checksum(&prompt_messages)?);
+        }
+
+        Ok(PromptsGetResult {
+            description: Some(self.front_matter.description.clone()),
+            messages: prompt_messages,
+            meta: if meta.is_empty() { None } else { Some(meta) },
+        })
+    }
 }
 
 #[async_trait]
 impl PromptController for PromptDocumentController {
+    fn clone_box(&self) -> Box<dyn PromptController> {
+        Box::new(self.clone())
+    }
+
+    fn with_esbuild_metafile(
+        &self,
+        esbuild_metafile: Arc<EsbuildMetaFile>,
+    ) -> Option<Box<dyn PromptController>> {
+        if !self.uses_assets {
+            return None;
+        }
+
+        Some(Box::new(PromptDocumentController {
+            esbuild_metafile,
+            ..self.clone()
+        }))
+    }
+
+    fn with_source(&self, source: &str) -> Option<Result<Box<dyn PromptController>>> {
+        Some(self.rebuild(source).map(|prompt_document_controller| {
+            Box::new(prompt_document_controller) as Box<dyn PromptController>
+        }))
+    }
+
+    fn complete(&self, argument_name: &str, partial_value: &str) -> Completion {
+        self.front_matter
+            .arguments
+            .get(argument_name)
+            .filter(|argument| argument.session.is_none())
+            .map(|argument| {
+                argument
+                    .choices
+                    .iter()
+                    .filter(|choice| choice.starts_with(partial_value))
+                    .cloned()
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default()
+            .into()
+    }
+
+    fn describe(&self) -> PromptDescription {
+        let mut components = find_components_in_mdast(&self.mdast);
+
+        components.extend(self.front_matter.layout.clone());
+
+        PromptDescription {
+            arguments: self
+                .front_matter
+                .arguments
+                .iter()
+                .map(|(name, argument)| PromptArgumentDescription {
+                    choices: argument.choices.clone(),
+                    content_type: argument.content_type,
+                    default: argument.default.clone(),
+                    description: argument.description.clone(),
+                    example: argument.example.clone(),
+                    kind: argument.kind,
+                    name: name.clone(),
+                    pattern: argument
+                        .pattern
+                        .as_ref()
+                        .map(|pattern| pattern.pattern.clone()),
+                    required: argument.required,
+                    sensitive: argument.sensitive,
+                    session_bound: argument.session.is_some(),
+                    title: argument.title.clone(),
+                    trim: argument.trim,
+                })
+                .collect(),
+            collections: self
+                .front_matter
+                .primary_collection
+                .iter()
+                .chain(&self.front_matter.collections)
+                .cloned()
+                .collect(),
+            components,
+            description: self.front_matter.description.clone(),
+            is_static: self.front_matter.layout.is_none() && mdast_is_static(&self.mdast),
+            name: self.name.clone(),
+            title: self.front_matter.title.clone(),
+        }
+    }
+
     fn get_mcp_prompt(&self) -> Prompt {
         Prompt {
             arguments: self
@@ -40,6 +454,7 @@ impl PromptController for PromptDocumentController {
                 .clone()
                 .arguments
                 .into_iter()
+                .filter(|(_, argument)| argument.session.is_none())
                 .map(
                     |(
                         name,
@@ -47,6 +462,7 @@ impl PromptController for PromptDocumentController {
                             description,
                             required,
                             title,
+                            ..
                         },
                     )| PromptArgument {
                         description,
@@ -57,65 +473,55 @@ impl PromptController for PromptDocumentController {
                 )
                 .collect(),
             description: self.front_matter.description.clone(),
+            meta: Some(PromptMeta {
+                arguments_schema: None,
+                deprecated: self.front_matter.deprecated,
+            }),
             name: self.name.clone(),
             title: self.front_matter.title.clone(),
         }
     }
 
-    async fn respond_to(
-        &self,
-        PromptsGet {
-            params: PromptsGetParams { arguments, .. },
-            ..
-        }: PromptsGet,
-    ) -> Result<PromptsGetResult> {
-        let mut prompt_document_component_context = PromptDocumentComponentContext {
-            arguments: self.front_matter.map_arguments(arguments)?,
-            asset_manager: AssetManager::from_esbuild_metafile(
-                self.esbuild_metafile.clone(),
-                self.asset_path_renderer.clone(),
-            ),
-            content_document_linker: self.content_document_linker.clone(),
-            current_role: Default::default(),
-            front_matter: self.front_matter.clone(),
-            prompt_messages: Default::default(),
-            unprocessed_message_chunk: Default::default(),
-        };
-
-        eval_prompt_document_mdast(
-            EvalPromptDocumentMdastParams {
-                mdast: &self.mdast,
-                is_directly_in_root: false,
-                is_first_child: false,
-                is_in_top_paragraph: false,
-                rhai_template_renderer: &self.rhai_template_renderer,
-            },
-            &mut prompt_document_component_context,
-        )?;
+    async fn respond_to(&self, request: PromptsGet) -> Result<PromptsGetResult> {
+        let fetched_contents = self.fetch_contents().await?;
 
-        Ok(PromptsGetResult {
-            description: Some(self.front_matter.description.clone()),
-            messages: prompt_document_component_context.prompt_messages,
-            meta: None,
-        })
+        self.render(request, None, fetched_contents)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
+    use std::fs;
+    use std::future::pending;
     use std::path::PathBuf;
+    use std::time::Duration;
 
+    use chrono::Utc;
+    use indoc::formatdoc;
     use indoc::indoc;
+    use serde_json::Value;
+    use serde_json::json;
+    use tempfile::tempdir;
+    use tokio_util::sync::CancellationToken;
 
     use super::*;
-    use crate::build_prompt_document_controller::build_prompt_document_controller;
-    use crate::build_prompt_document_controller_params::BuildPromptDocumentControllerParams;
     use crate::filesystem::file_entry_stub::FileEntryStub;
+    use crate::filesystem::storage::DEFAULT_MAX_FILE_BYTES;
+    use crate::filesystem::storage::Storage;
+    use crate::mcp::content_block::ContentBlock;
+    use crate::mcp::content_block::audio_content::AudioContent;
+    use crate::mcp::content_block::image_content::ImageContent;
+    use crate::mcp::content_block::resource_link::ResourceLink;
     use crate::mcp::jsonrpc::JSONRPC_VERSION;
-    use crate::mcp::jsonrpc::role::Role;
-    use crate::mcp::prompt_message::PromptMessage;
+    use crate::mcp::jsonrpc::implementation::Implementation;
+    use crate::prompt_document_front_matter::argument_content_type::ArgumentContentType;
+    use crate::prompt_fetcher_http::PromptFetcherHttp;
+    use crate::prompt_render_cancelled::PromptRenderCancelled;
+    use crate::prompt_render_limits_policy::PromptRenderLimitsPolicy;
+    use crate::prompt_system_role_lowering::PromptSystemRoleLowering;
     use crate::rhai_template_renderer_factory::RhaiTemplateRendererFactory;
+    use crate::token_counter_heuristic::TokenCounterHeuristic;
 
     #[tokio::test]
     async fn test_convert_to_prompt_messages() -> Result<()> {
@@ -148,22 +554,36 @@ mod tests {
 
         let prompt_controller =
             build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                allow_remote_images: false,
                 asset_path_renderer: AssetPathRenderer {
                     base_path: "https://example.com".to_string(),
                 },
                 content_document_linker: Default::default(),
                 esbuild_metafile: Default::default(),
+                fetch_policy: Default::default(),
+                fetcher: Arc::new(PromptFetcherHttp::new()?),
                 file: FileEntryStub {
                     contents,
                     relative_path: PathBuf::from("prompts/help-me-finish-task.md"),
                 }
                 .try_into()?,
+                image_policy: Default::default(),
                 name: name.clone(),
+                prompt_collection_configs: Default::default(),
+                provenance_meta: false,
+                random_seed: None,
+                render_limits_policy: Default::default(),
+                render_pipeline: Default::default(),
                 rhai_template_renderer,
+                token_counter: Arc::new(TokenCounterHeuristic),
+                uncategorized_policy: Default::default(),
+                unknown_fields_policy: Default::default(),
             })?;
 
         let response = prompt_controller
             .respond_to(PromptsGet {
+                cancellation_token: Default::default(),
+                client_info: None,
                 id: "1".into(),
                 jsonrpc: JSONRPC_VERSION.to_string(),
                 params: PromptsGetParams {
@@ -176,6 +596,7 @@ mod tests {
                     },
                     meta: None,
                     name,
+                    props: None,
                 },
             })
             .await?;
@@ -206,4 +627,2191 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_title_falls_back_to_leading_heading() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "untitled",
+            indoc! {r#"
+            +++
+            description = "untitled"
+
+            [arguments]
+            +++
+
+            # Code *review* checklist
+
+            **user**: Hello
+            "#},
+        )?;
+
+        assert_eq!(
+            prompt_controller.front_matter.title,
+            "Code review checklist"
+        );
+
+        let response = prompt_controller
+            .respond_to(PromptsGet::mock("untitled", Default::default()))
+            .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(response.messages[0].content, "Hello".into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_only_leading_heading_is_dropped() -> Result<()> {
+        let result = PromptDocumentController::mock(
+            "sections",
+            indoc! {r#"
+            +++
+            description = "sections"
+            title = "Sections"
+
+            [arguments]
+            +++
+
+            # Sections
+
+            # Setup
+
+            **user**: Hello
+            "#},
+        )?
+        .respond_to(PromptsGet::mock("sections", Default::default()))
+        .await;
+
+        let Err(err) = result else {
+            return Err(anyhow!("Heading outside of a message was dropped"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Tried to flush messages, but there is no role set"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_same_seed_shuffles_identically() -> Result<()> {
+        let contents = indoc! {r#"
+            +++
+            description = "shuffled"
+            title = "Shuffled"
+
+            [arguments]
+            +++
+
+            **user**: {context.shuffle(["a", "b", "c", "d", "e", "f", "g", "h"])} {context.random(1000)}
+        "#};
+        let render_with_seed = async |seed: u64| -> Result<ContentBlock> {
+            let response = build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                random_seed: Some(seed),
+                ..BuildPromptDocumentControllerParams::mock("shuffled", contents)?
+            })?
+            .respond_to(PromptsGet::mock("shuffled", Default::default()))
+            .await?;
+
+            Ok(response.messages[0].content.clone())
+        };
+
+        assert_eq!(render_with_seed(7).await?, render_with_seed(7).await?);
+        assert_ne!(render_with_seed(7).await?, render_with_seed(8).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_empty_random_range_fails() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "ranged",
+            indoc! {r#"
+            +++
+            description = "ranged"
+            title = "Ranged"
+
+            [arguments]
+            +++
+
+            **user**: {context.random(0)}
+            "#},
+        )?;
+        let Err(err) = prompt_controller
+            .respond_to(PromptsGet::mock("ranged", Default::default()))
+            .await
+        else {
+            return Err(anyhow!("Expected an empty random range to fail"));
+        };
+
+        assert!(format!("{err:#}").contains("random() needs an upper bound above 0, got 0"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prop_override_changes_rendered_output() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "toned",
+            indoc! {r#"
+            +++
+            description = "toned"
+            title = "Toned"
+
+            [arguments]
+
+            [props]
+            examples = ["hello"]
+            temperature = 0.5
+            tone = "formal"
+
+            [props.audience]
+            level = "expert"
+            +++
+
+            **user**: Answer in a {context.front_matter.props.tone} tone at {context.front_matter.props.temperature} for {context.front_matter.props.audience.level}
+            "#},
+        )?;
+        let render_with_props = async |props: Value| -> Result<PromptsGetResult> {
+            let mut request = PromptsGet::mock("toned", Default::default());
+
+            request.params.props = Some(serde_json::from_value(props)?);
+
+            prompt_controller.respond_to(request).await
+        };
+
+        assert_eq!(
+            prompt_controller
+                .respond_to(PromptsGet::mock("toned", Default::default()))
+                .await?
+                .messages[0]
+                .content,
+            "Answer in a formal tone at 0.5 for expert".into()
+        );
+        assert_eq!(
+            render_with_props(json!({
+                "audience": { "level": "beginner" },
+                "temperature": 1,
+                "tone": "playful",
+            }))
+            .await?
+            .messages[0]
+                .content,
+            "Answer in a playful tone at 1.0 for beginner".into()
+        );
+
+        let Err(err) = render_with_props(json!({ "examples": [3] })).await else {
+            return Err(anyhow!("Expected a mistyped array item to be rejected"));
+        };
+
+        assert_eq!(
+            format!("{err:#}"),
+            "Prop 'examples[0]' expects string, got i64"
+        );
+
+        let Err(err) = render_with_props(json!({ "audience": { "age": 30 } })).await else {
+            return Err(anyhow!("Expected an undeclared map entry to be rejected"));
+        };
+
+        assert_eq!(
+            format!("{err:#}"),
+            "Prop 'audience.age' is not declared in the front matter"
+        );
+
+        let Err(err) = render_with_props(json!({ "tone": 3 })).await else {
+            return Err(anyhow!("Expected a mistyped prop override to be rejected"));
+        };
+
+        assert_eq!(format!("{err:#}"), "Prop 'tone' expects string, got i64");
+
+        let Err(err) = render_with_props(json!({ "mood": "happy" })).await else {
+            return Err(anyhow!(
+                "Expected an undeclared prop override to be rejected"
+            ));
+        };
+
+        assert_eq!(
+            format!("{err:#}"),
+            "Prop 'mood' cannot be overridden, because it is not declared in the front matter"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_trailing_empty_message_is_dropped() -> Result<()> {
+        let render = async |keep_empty_messages: bool| -> Result<Vec<PromptMessage>> {
+            Ok(PromptDocumentController::mock(
+                "unanswered",
+                &formatdoc! {r#"
+                +++
+                description = "unanswered"
+                keep_empty_messages = {keep_empty_messages}
+                title = "Unanswered"
+
+                [arguments]
+                +++
+
+                **user**: Describe a horse
+
+                **assistant**:
+                "#},
+            )?
+            .respond_to(PromptsGet::mock("unanswered", Default::default()))
+            .await?
+            .messages)
+        };
+
+        let messages = render(false).await?;
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].role, Role::User);
+
+        let messages = render(true).await?;
+
+        assert_eq!(messages.len(), 2);
+        assert!(messages[1].content.is_empty_text());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_code_argument_is_fenced() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "review",
+            indoc! {r#"
+            +++
+            description = "review"
+            title = "Review"
+
+            [arguments.code]
+            content_type = "code"
+            description = "Code to review"
+            required = true
+            title = "Code"
+            +++
+
+            **user**: Review this:
+
+            {context.arguments.code.input}
+            "#},
+        )?;
+
+        let response = prompt_controller
+            .respond_to(PromptsGet::mock(
+                "review",
+                HashMap::from([("code".to_string(), "fn main() {}".to_string())]),
+            ))
+            .await?;
+
+        assert_eq!(
+            response.messages[0].content,
+            "Review this:\n\n```\nfn main() {}\n```".into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_result_meta_merges_front_matter_and_components() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "hinted",
+            indoc! {r#"
+            +++
+            description = "hinted"
+            title = "Hinted"
+
+            [arguments]
+
+            [result_meta]
+            max_tokens = 1024
+            model = "fast"
+            +++
+
+            **user**: Summarize the document
+
+            <ResultMeta key="model" value="thorough" />
+            "#},
+        )?;
+
+        let response = prompt_controller
+            .respond_to(PromptsGet::mock("hinted", Default::default()))
+            .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&response)?["_meta"],
+            serde_json::json!({
+                "max_tokens": 1024,
+                "model": "thorough",
+            })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_assistant_prefill() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "prefill",
+            indoc! {r#"
+            +++
+            assistant_prefill = true
+            description = "prefill"
+            title = "Prefill"
+
+            [arguments]
+            +++
+
+            **user**: Describe a horse in one sentence
+
+            **assistant**: The horse is
+            "#},
+        )?;
+
+        let response = prompt_controller
+            .respond_to(PromptsGet::mock("prefill", Default::default()))
+            .await?;
+
+        assert!(response.is_assistant_prefill());
+        assert_eq!(response.messages.len(), 2);
+        assert_eq!(
+            response.messages.last().map(|message| message.role.clone()),
+            Some(Role::Assistant)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_assistant_prefill_requires_trailing_assistant_message() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "prefill",
+            indoc! {r#"
+            +++
+            assistant_prefill = true
+            description = "prefill"
+            title = "Prefill"
+
+            [arguments]
+            +++
+
+            **user**: Describe a horse
+            "#},
+        )?;
+
+        assert!(
+            prompt_controller
+                .respond_to(PromptsGet::mock("prefill", Default::default()))
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_limits_override_up_to_ceiling() -> Result<()> {
+        let render_limits_policy = PromptRenderLimitsPolicy {
+            ceiling: PromptRenderLimits {
+                max_message_bytes: Some(64),
+                max_messages: None,
+                render_deadline_ms: None,
+            },
+            defaults: PromptRenderLimits {
+                max_message_bytes: Some(16),
+                max_messages: None,
+                render_deadline_ms: None,
+            },
+        };
+        let contents = |limits: &str| {
+            formatdoc! {r#"
+                +++
+                description = "large"
+                title = "Large"
+
+                [arguments]
+
+                [limits]
+                {limits}
+                +++
+
+                **user**: This message is longer than sixteen bytes
+            "#}
+        };
+
+        assert!(
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                render_limits_policy,
+                ..BuildPromptDocumentControllerParams::mock("large", &contents(""))?
+            })?
+            .respond_to(PromptsGet::mock("large", Default::default()))
+            .await
+            .is_err()
+        );
+
+        let response = build_prompt_document_controller(BuildPromptDocumentControllerParams {
+            render_limits_policy,
+            ..BuildPromptDocumentControllerParams::mock(
+                "large",
+                &contents("max_message_bytes = 64"),
+            )?
+        })?
+        .respond_to(PromptsGet::mock("large", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert!(
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                render_limits_policy,
+                ..BuildPromptDocumentControllerParams::mock(
+                    "large",
+                    &contents("max_message_bytes = 65")
+                )?
+            })
+            .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_deadline_override_up_to_ceiling() -> Result<()> {
+        let render_limits_policy = PromptRenderLimitsPolicy {
+            ceiling: PromptRenderLimits {
+                max_message_bytes: None,
+                max_messages: None,
+                render_deadline_ms: Some(60_000),
+            },
+            defaults: PromptRenderLimits {
+                max_message_bytes: None,
+                max_messages: None,
+                render_deadline_ms: Some(0),
+            },
+        };
+        let contents = |limits: &str| {
+            formatdoc! {r#"
+                +++
+                description = "heavy"
+                title = "Heavy"
+
+                [arguments]
+
+                [limits]
+                {limits}
+                +++
+
+                **user**: Take your time
+            "#}
+        };
+
+        assert!(
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                render_limits_policy,
+                ..BuildPromptDocumentControllerParams::mock("heavy", &contents(""))?
+            })?
+            .respond_to(PromptsGet::mock("heavy", Default::default()))
+            .await
+            .is_err()
+        );
+
+        let response = build_prompt_document_controller(BuildPromptDocumentControllerParams {
+            render_limits_policy,
+            ..BuildPromptDocumentControllerParams::mock(
+                "heavy",
+                &contents("render_deadline_ms = 60000"),
+            )?
+        })?
+        .respond_to(PromptsGet::mock("heavy", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert!(
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                render_limits_policy,
+                ..BuildPromptDocumentControllerParams::mock(
+                    "heavy",
+                    &contents("render_deadline_ms = 60001")
+                )?
+            })
+            .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_messages_is_enforced() -> Result<()> {
+        let contents = |max_messages: usize| {
+            formatdoc! {r#"
+                +++
+                description = "chatty"
+                title = "Chatty"
+
+                [arguments]
+
+                [limits]
+                max_messages = {max_messages}
+                +++
+
+                **user**: First
+
+                <Break />
+
+                Second
+
+                <Break />
+
+                Third
+            "#}
+        };
+
+        let response = PromptDocumentController::mock("chatty", &contents(3))?
+            .respond_to(PromptsGet::mock("chatty", Default::default()))
+            .await?;
+
+        assert_eq!(response.messages.len(), 3);
+
+        let Err(err) = PromptDocumentController::mock("chatty", &contents(2))?
+            .respond_to(PromptsGet::mock("chatty", Default::default()))
+            .await
+        else {
+            return Err(anyhow!("Expected the message limit to be exceeded"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Prompt 'chatty' produces more than 2 messages"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_system_role_is_lowered_into_user_message() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "system",
+            &formatdoc! {r#"
+                +++
+                description = "system"
+                title = "System"
+
+                [arguments]
+                +++
+
+                **system**: Answer in one sentence.
+
+                **user**: What is MCP?
+            "#},
+        )?
+        .respond_to(PromptsGet::mock("system", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(response.messages[0].role, Role::User);
+        assert_eq!(
+            response.messages[0].content,
+            "Answer in one sentence.\n\nWhat is MCP?".into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_system_role_is_lowered_only_into_leading_user_message() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "system",
+            &formatdoc! {r#"
+                +++
+                description = "system"
+                title = "System"
+
+                [arguments]
+                +++
+
+                **assistant**: How can I help?
+
+                **system**: Answer in one sentence.
+
+                **user**: What is MCP?
+            "#},
+        )?
+        .respond_to(PromptsGet::mock("system", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 3);
+        assert_eq!(response.messages[0].role, Role::User);
+        assert_eq!(
+            response.messages[0].content,
+            "Answer in one sentence.".into()
+        );
+        assert_eq!(response.messages[1].role, Role::Assistant);
+        assert_eq!(response.messages[2].content, "What is MCP?".into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_system_role_is_kept_in_place_as_prefixed_user_message() -> Result<()> {
+        let response = build_prompt_document_controller(BuildPromptDocumentControllerParams {
+            render_pipeline: PromptRenderPipeline {
+                system_role_lowering: PromptSystemRoleLowering::SeparateUserMessage,
+                ..Default::default()
+            },
+            ..BuildPromptDocumentControllerParams::mock(
+                "system",
+                indoc! {r#"
+                +++
+                description = "system"
+                title = "System"
+
+                [arguments]
+                +++
+
+                **assistant**: How can I help?
+
+                **system**: Answer in one sentence.
+
+                **user**: What is MCP?
+                "#},
+            )?
+        })?
+        .respond_to(PromptsGet::mock("system", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 3);
+        assert_eq!(response.messages[0].role, Role::Assistant);
+        assert_eq!(response.messages[1].role, Role::User);
+        assert_eq!(
+            response.messages[1].content,
+            "System: Answer in one sentence.".into()
+        );
+        assert_eq!(response.messages[2].content, "What is MCP?".into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_yaml_and_toml_front_matter_are_equivalent() -> Result<()> {
+        let body = indoc! {r#"
+            **user**: Summarize {context.arguments.topic.input} in {context.arguments.length.input} words
+        "#};
+        let respond_to = async |front_matter: &str| {
+            PromptDocumentController::mock("summary", &format!("{front_matter}\n{body}"))?
+                .respond_to(PromptsGet::mock(
+                    "summary",
+                    HashMap::from([
+                        ("length".to_string(), "50".to_string()),
+                        ("topic".to_string(), "MCP".to_string()),
+                    ]),
+                ))
+                .await
+        };
+
+        let toml_response = respond_to(indoc! {r#"
+            +++
+            description = "Summarize a topic"
+            title = "Summary"
+            result_meta = { audience = "internal" }
+
+            [arguments.length]
+            description = "Number of words"
+            required = true
+            title = "Length"
+
+            [arguments.topic]
+            description = "What to summarize"
+            required = true
+            title = "Topic"
+            +++
+        "#})
+        .await?;
+        let yaml_response = respond_to(indoc! {r#"
+            ---
+            description: Summarize a topic
+            title: Summary
+            result_meta:
+              audience: internal
+            arguments:
+              length:
+                description: Number of words
+                required: true
+                title: Length
+              topic:
+                description: What to summarize
+                required: true
+                title: Topic
+            ---
+        "#})
+        .await?;
+
+        assert_eq!(
+            serde_json::to_value(&yaml_response)?,
+            serde_json::to_value(&toml_response)?
+        );
+        assert_eq!(
+            yaml_response.messages[0].content,
+            "Summarize MCP in 50 words".into()
+        );
+
+        let Err(err) = PromptDocumentController::mock(
+            "summary",
+            indoc! {r#"
+                ---
+                description: Summarize a topic
+                arguments: {}
+                unknown_field: true
+                ---
+
+                **user**: Hello
+            "#},
+        ) else {
+            return Err(anyhow!(
+                "Expected unknown YAML front matter fields to be rejected"
+            ));
+        };
+
+        assert!(format!("{err:#}").contains("Unknown front matter fields: unknown_field"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_provenance_meta_when_enabled() -> Result<()> {
+        let contents = indoc! {r#"
+            +++
+            description = "traced"
+            title = "Traced"
+
+            [arguments]
+            +++
+
+            **user**: Hello
+        "#};
+
+        let response = PromptDocumentController::mock("traced", contents)?
+            .respond_to(PromptsGet::mock("traced", Default::default()))
+            .await?;
+
+        assert!(response.meta.is_none());
+
+        let response = build_prompt_document_controller(BuildPromptDocumentControllerParams {
+            provenance_meta: true,
+            ..BuildPromptDocumentControllerParams::mock("traced", contents)?
+        })?
+        .respond_to(PromptsGet::mock("traced", Default::default()))
+        .await?;
+
+        let Some(PromptsGetResultMeta {
+            provenance: Some(provenance),
+            ..
+        }) = response.meta
+        else {
+            return Err(anyhow!("Expected provenance in the result meta"));
+        };
+
+        assert_eq!(provenance.poet_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(provenance.source_path, "prompts/traced.md");
+        assert!(provenance.built_at <= Utc::now());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_integer_argument_is_a_number_in_templates() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "count",
+            indoc! {r#"
+                +++
+                description = "count"
+                title = "Count"
+
+                [arguments.count]
+                description = "Starting count"
+                kind = "integer"
+                required = true
+                title = "Count"
+                +++
+
+                **user**: Count from {context.arguments.count.input + 1}
+            "#},
+        )?
+        .respond_to(PromptsGet::mock(
+            "count",
+            HashMap::from([("count".to_string(), "41".to_string())]),
+        ))
+        .await?;
+
+        assert_eq!(response.messages[0].content, "Count from 42".into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_argument_default_is_used_when_omitted() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "answer",
+            indoc! {r#"
+                +++
+                description = "answer"
+                title = "Answer"
+
+                [arguments.verbose]
+                default = false
+                description = "Whether to explain in detail"
+                kind = "boolean"
+                required = false
+                title = "Verbose"
+                +++
+
+                **user**: {if context.arguments.verbose.input { "Explain in detail" } else { "Answer briefly" }}
+            "#},
+        )?;
+
+        let response = prompt_controller
+            .respond_to(PromptsGet::mock("answer", Default::default()))
+            .await?;
+
+        assert_eq!(response.messages[0].content, "Answer briefly".into());
+
+        let response = prompt_controller
+            .respond_to(PromptsGet::mock(
+                "answer",
+                HashMap::from([("verbose".to_string(), "true".to_string())]),
+            ))
+            .await?;
+
+        assert_eq!(response.messages[0].content, "Explain in detail".into());
+
+        Ok(())
+    }
+
+    async fn respond_with_image_policy(
+        image_policy: PromptImagePolicy,
+        src: &str,
+    ) -> Result<PromptsGetResult> {
+        build_prompt_document_controller(BuildPromptDocumentControllerParams {
+            image_policy,
+            ..BuildPromptDocumentControllerParams::mock(
+                "image",
+                &formatdoc! {r#"
+                    +++
+                    description = "image"
+                    title = "Image"
+
+                    [arguments]
+                    +++
+
+                    **user**: Look at this ![horse]({src}) closely
+                "#},
+            )?
+        })?
+        .respond_to(PromptsGet::mock("image", Default::default()))
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_image_policy_markdown() -> Result<()> {
+        let response =
+            respond_with_image_policy(PromptImagePolicy::Markdown, "https://example.com/horse.png")
+                .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(
+            response.messages[0].content,
+            "Look at this ![horse](https://example.com/horse.png) closely".into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_image_policy_resource_link() -> Result<()> {
+        let response = respond_with_image_policy(
+            PromptImagePolicy::ResourceLink,
+            "https://example.com/horse.png",
+        )
+        .await?;
+        let contents: Vec<ContentBlock> = response
+            .messages
+            .into_iter()
+            .map(|message| message.content)
+            .collect();
+
+        assert_eq!(
+            contents,
+            vec![
+                "Look at this".into(),
+                ContentBlock::ResourceLink(ResourceLink {
+                    description: None,
+                    mime_type: Some("image/png".to_string()),
+                    name: "horse".to_string(),
+                    title: None,
+                    uri: "https://example.com/horse.png".to_string(),
+                }),
+                "closely".into(),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_image_policy_inline() -> Result<()> {
+        let response =
+            respond_with_image_policy(PromptImagePolicy::Inline, "data:image/png;base64,aG9yc2U=")
+                .await?;
+
+        assert_eq!(response.messages.len(), 3);
+        assert_eq!(
+            response.messages[1].content,
+            ContentBlock::ImageContent(ImageContent {
+                data: "aG9yc2U=".to_string(),
+                mime_type: "image/png".to_string(),
+            })
+        );
+        assert!(
+            respond_with_image_policy(PromptImagePolicy::Inline, "https://example.com/horse.png")
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_audio_content_round_trips() -> Result<()> {
+        let respond_with_audio = async |src: &str| {
+            PromptDocumentController::mock(
+                "audio",
+                &formatdoc! {r#"
+                    +++
+                    description = "audio"
+                    title = "Audio"
+
+                    [arguments]
+                    +++
+
+                    **user**: Listen to this
+
+                    <Audio src="{src}" />
+                "#},
+            )?
+            .respond_to(PromptsGet::mock("audio", Default::default()))
+            .await
+        };
+        let response = respond_with_audio("data:audio/wav;base64,UklGRg==").await?;
+        let serialized = serde_json::to_value(&response)?;
+        let deserialized: PromptsGetResult = serde_json::from_value(serialized.clone())?;
+        let audio_content = ContentBlock::AudioContent(AudioContent {
+            data: "UklGRg==".to_string(),
+            mime_type: "audio/wav".to_string(),
+        });
+
+        assert_eq!(serialized["messages"][1]["content"]["type"], "audio");
+        assert_eq!(
+            serialized["messages"][1]["content"]["mimeType"],
+            "audio/wav"
+        );
+        assert_eq!(response.messages[1].content, audio_content);
+        assert_eq!(deserialized.messages[1].content, audio_content);
+        assert!(
+            respond_with_audio("data:image/png;base64,aG9yc2U=")
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_object_replacement_character_in_content_is_kept() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "audio",
+            &formatdoc! {r#"
+                +++
+                description = "audio"
+                title = "Audio"
+
+                [arguments]
+                +++
+
+                **user**: Listen to this {marker}
+
+                <Audio src="data:audio/wav;base64,UklGRg==" />
+
+                Then describe {marker} it
+                "#,
+                marker = '\u{FFFC}',
+            },
+        )?
+        .respond_to(PromptsGet::mock("audio", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 3);
+        assert_eq!(
+            response.messages[0].content,
+            "Listen to this \u{FFFC}".into()
+        );
+        assert_eq!(
+            response.messages[1].content,
+            ContentBlock::AudioContent(AudioContent {
+                data: "UklGRg==".to_string(),
+                mime_type: "audio/wav".to_string(),
+            })
+        );
+        assert_eq!(
+            response.messages[2].content,
+            "Then describe \u{FFFC} it".into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_local_image_is_inlined_from_filesystem() -> Result<()> {
+        let base_directory = tempdir()?;
+
+        fs::create_dir_all(base_directory.path().join("prompts"))?;
+        fs::write(
+            base_directory.path().join("prompts/logo.png"),
+            [0x89, b'P', b'N', b'G'],
+        )?;
+
+        let storage = Storage {
+            base_directory: base_directory.path().to_path_buf(),
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            source_encodings: Default::default(),
+        };
+        let mut controller =
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                image_policy: PromptImagePolicy::Inline,
+                ..BuildPromptDocumentControllerParams::mock(
+                    "image",
+                    indoc! {r#"
+                    +++
+                    description = "image"
+                    title = "Image"
+
+                    [arguments]
+                    +++
+
+                    **user**: ![alt](logo.png)
+                "#},
+                )?
+            })?;
+
+        controller.load_inline_images(&storage).await?;
+
+        let response = controller
+            .respond_to(PromptsGet::mock("image", Default::default()))
+            .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(
+            response.messages[0].content,
+            ContentBlock::ImageContent(ImageContent {
+                data: "iVBORw==".to_string(),
+                mime_type: "image/png".to_string(),
+            })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_local_image_outside_of_the_document_directory_is_rejected() -> Result<()> {
+        let base_directory = tempdir()?;
+
+        fs::write(
+            base_directory.path().join("secret.png"),
+            [0x89, b'P', b'N', b'G'],
+        )?;
+
+        let storage = Storage {
+            base_directory: base_directory.path().to_path_buf(),
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            source_encodings: Default::default(),
+        };
+        let mut controller =
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                image_policy: PromptImagePolicy::Inline,
+                ..BuildPromptDocumentControllerParams::mock(
+                    "image",
+                    indoc! {r#"
+                    +++
+                    description = "image"
+                    title = "Image"
+
+                    [arguments]
+                    +++
+
+                    **user**: ![alt](../secret.png)
+                "#},
+                )?
+            })?;
+
+        let Err(err) = controller.load_inline_images(&storage).await else {
+            return Err(anyhow!(
+                "Expected the image outside of the prompts to be rejected"
+            ));
+        };
+
+        assert!(
+            err.to_string()
+                .contains("stays within the document directory")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_escaped_interpolation_renders_verbatim() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "escape",
+            indoc! {r#"
+            +++
+            description = "escape"
+            title = "Escape"
+
+            [arguments]
+            +++
+
+            **user**: Template syntax looks like \{context.arguments.topic.input}
+            "#},
+        )?
+        .respond_to(PromptsGet::mock("escape", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(
+            response.messages[0].content,
+            "Template syntax looks like {context.arguments.topic.input}".into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_raw_component_keeps_source_verbatim() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "raw",
+            indoc! {r#"
+            +++
+            description = "raw"
+            title = "Raw"
+
+            [arguments]
+            +++
+
+            **user**:
+
+            <Raw>
+            keep \*stars\*, _under_ and  **bold**
+
+            <Unknown />
+            </Raw>
+            "#},
+        )?
+        .respond_to(PromptsGet::mock("raw", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(
+            response.messages[0].content,
+            "keep \\*stars\\*, _under_ and  **bold**\n\n<Unknown />".into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_from_positional_arguments() -> Result<()> {
+        let controller = PromptDocumentController::mock(
+            "positional",
+            indoc! {r#"
+            +++
+            description = "positional"
+            title = "Positional"
+
+            [arguments.subject]
+            description = "Who to greet"
+            required = true
+            title = "Subject"
+
+            [arguments.greeting]
+            description = "How to greet"
+            required = true
+            title = "Greeting"
+            +++
+
+            **user**: {context.arguments.greeting.input}, {context.arguments.subject.input}!
+            "#},
+        )?;
+
+        let arguments = controller
+            .front_matter
+            .map_positional_arguments(vec!["world".to_string(), "Hello".to_string()])?;
+        let response = controller
+            .respond_to(PromptsGet::mock("positional", arguments))
+            .await?;
+
+        assert_eq!(response.messages[0].content, "Hello, world!".into());
+
+        let Err(err) = controller.front_matter.map_positional_arguments(vec![
+            "world".to_string(),
+            "Hello".to_string(),
+            "again".to_string(),
+        ]) else {
+            return Err(anyhow!("Expected too many positional arguments to fail"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Expected at most 2 positional arguments, but 3 were provided"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_template_reads_request_metadata() -> Result<()> {
+        let controller = PromptDocumentController::mock(
+            "request",
+            indoc! {r#"
+            +++
+            description = "request"
+            title = "Request"
+
+            [arguments]
+            +++
+
+            **user**: Request {context.request.id} from {context.request.client.name}
+            "#},
+        )?;
+
+        let response = controller
+            .respond_to(PromptsGet {
+                cancellation_token: Default::default(),
+                client_info: Some(Implementation {
+                    description: None,
+                    name: "inspector".to_string(),
+                    title: None,
+                    version: "1.0.0".to_string(),
+                }),
+                ..PromptsGet::mock("request", Default::default())
+            })
+            .await?;
+
+        assert_eq!(
+            response.messages[0].content,
+            "Request 1 from inspector".into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_bound_argument_uses_client_info() -> Result<()> {
+        let controller = PromptDocumentController::mock(
+            "session",
+            indoc! {r#"
+            +++
+            description = "session"
+            title = "Session"
+
+            [arguments.client]
+            description = "Client the prompt is rendered for"
+            required = true
+            session = "client_name"
+            title = "Client"
+
+            [arguments.topic]
+            description = "Topic to explain"
+            required = true
+            title = "Topic"
+            +++
+
+            **user**: Explain {context.arguments.topic.input} to {context.arguments.client.input}
+            "#},
+        )?;
+
+        let response = controller
+            .respond_to(PromptsGet {
+                cancellation_token: Default::default(),
+                client_info: Some(Implementation {
+                    description: None,
+                    name: "inspector".to_string(),
+                    title: None,
+                    version: "1.0.0".to_string(),
+                }),
+                ..PromptsGet::mock(
+                    "session",
+                    HashMap::from([
+                        ("client".to_string(), "spoofed".to_string()),
+                        ("topic".to_string(), "MCP".to_string()),
+                    ]),
+                )
+            })
+            .await?;
+
+        assert_eq!(
+            response.messages[0].content,
+            "Explain MCP to inspector".into()
+        );
+
+        let prompt = controller.get_mcp_prompt();
+
+        assert_eq!(
+            prompt
+                .arguments
+                .iter()
+                .map(|argument| argument.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["topic"]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_array_argument_produces_message_per_item() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "review",
+            indoc! {r#"
+            +++
+            description = "review"
+            title = "Review"
+
+            [arguments.files]
+            description = "Files to review"
+            kind = "array"
+            required = true
+            title = "Files"
+            +++
+
+            {for file in context.arguments.files.input { context.switch_role_to("user"); context.append_to_message("Review " + file); }}
+            "#},
+        )?
+        .respond_to(PromptsGet::mock(
+            "review",
+            HashMap::from([("files".to_string(), "main.rs,lib.rs".to_string())]),
+        ))
+        .await?;
+
+        assert_eq!(response.messages.len(), 2);
+        assert_eq!(response.messages[0].content, "Review main.rs".into());
+        assert_eq!(response.messages[1].content, "Review lib.rs".into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_clone_box_renders_identically() -> Result<()> {
+        let prompt_controller: Box<dyn PromptController> =
+            Box::new(PromptDocumentController::mock(
+                "clone",
+                indoc! {r#"
+            +++
+            description = "clone"
+            title = "Clone"
+
+            [arguments.animal]
+            description = "Animal to greet"
+            required = true
+            title = "Animal"
+            +++
+
+            **user**: Say hello to the {context.arguments.animal.input}
+            "#},
+            )?);
+        let cloned_prompt_controller = prompt_controller.clone_box();
+        let arguments = HashMap::from([("animal".to_string(), "horse".to_string())]);
+
+        let response = prompt_controller
+            .respond_to(PromptsGet::mock("clone", arguments.clone()))
+            .await?;
+        let cloned_response = cloned_prompt_controller
+            .respond_to(PromptsGet::mock("clone", arguments))
+            .await?;
+
+        assert_eq!(
+            prompt_messages_checksum(&response.messages)?,
+            prompt_messages_checksum(&cloned_response.messages)?
+        );
+        assert_eq!(
+            cloned_response.messages[0].content,
+            "Say hello to the horse".into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_respond_up_to_first_message() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "preamble",
+            indoc! {r#"
+            +++
+            description = "preamble"
+            title = "Preamble"
+
+            [arguments]
+            +++
+
+            **user**: You are reviewing a pull request.
+
+            Keep the review short.
+
+            **assistant**: {error("The rest of the prompt should not be evaluated")}
+            "#},
+        )?
+        .respond_up_to_message(PromptsGet::mock("preamble", Default::default()), 0)?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(response.messages[0].role, Role::User);
+        assert_eq!(
+            response.messages[0].content,
+            "You are reviewing a pull request.\n\nKeep the review short.".into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_break_splits_message_with_same_role() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "break",
+            indoc! {r#"
+            +++
+            description = "break"
+            title = "Break"
+
+            [arguments]
+            +++
+
+            **user**: Here is the document.
+
+            <Break />
+
+            Now summarize it.
+            "#},
+        )?
+        .respond_to(PromptsGet::mock("break", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 2);
+        assert_eq!(response.messages[0].role, Role::User);
+        assert_eq!(response.messages[0].content, "Here is the document.".into());
+        assert_eq!(response.messages[1].role, Role::User);
+        assert_eq!(response.messages[1].content, "Now summarize it.".into());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_smart_punctuation_is_normalized_outside_code() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "punctuation",
+            indoc! {r#"
+            +++
+            description = "punctuation"
+            normalize_punctuation = true
+            title = "Punctuation"
+
+            [arguments]
+            +++
+
+            **user**: Explain “borrowing” — it’s short.
+
+            ```rust
+            let quote = ‘kept’;
+            ```
+            "#},
+        )?
+        .respond_to(PromptsGet::mock("punctuation", Default::default()))
+        .await?;
+
+        assert_eq!(
+            response.messages[0].content,
+            "Explain \"borrowing\" -- it's short.\n\n```rust\nlet quote = ‘kept’;\n```".into()
+        );
+
+        Ok(())
+    }
+
+    async fn respond_with_checksum(greeting: &str) -> Result<PromptsGetResult> {
+        PromptDocumentController::mock(
+            "checksum",
+            &formatdoc! {r#"
+                +++
+                checksum = true
+                description = "checksum"
+                title = "Checksum"
+
+                [arguments]
+                +++
+
+                **user**: {greeting}
+            "#},
+        )?
+        .respond_to(PromptsGet::mock("checksum", Default::default()))
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_checksum() -> Result<()> {
+        let first_response = respond_with_checksum("Hello").await?;
+        let second_response = respond_with_checksum("Hello").await?;
+        let changed_response = respond_with_checksum("Goodbye").await?;
+
+        assert!(first_response.checksum().is_some());
+        assert_eq!(first_response.checksum(), second_response.checksum());
+        assert_ne!(first_response.checksum(), changed_response.checksum());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_blockquote_round_trips() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "quote",
+            indoc! {r#"
+            +++
+            description = "quote"
+            title = "Quote"
+
+            [arguments]
+            +++
+
+            **user**: Reply in this style:
+
+            > Thank you for reaching out.
+            > We will get back to you soon.
+            "#},
+        )?
+        .respond_to(PromptsGet::mock("quote", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(
+            response.messages[0].content,
+            "Reply in this style:\n\n> Thank you for reaching out.\n> We will get back to you soon."
+                .into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_leading_colon_is_kept_outside_of_role_marker() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "colon",
+            indoc! {r#"
+            +++
+            description = "colon"
+            title = "Colon"
+
+            [arguments]
+            +++
+
+            **user**: Greet everyone
+
+            :wave: Hello there
+            "#},
+        )?
+        .respond_to(PromptsGet::mock("colon", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(
+            response.messages[0].content,
+            "Greet everyone\n\n:wave: Hello there".into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_component_content_type_is_validated() -> Result<()> {
+        let base_directory = tempdir()?;
+        let contents = indoc! {r#"
+            fn template(context, props, content) {
+                #{ type: props.kind, data: "aGVsbG8=", mimeType: "image/png" }
+            }
+        "#};
+
+        fs::create_dir(base_directory.path().join("shortcodes"))?;
+        fs::write(
+            base_directory.path().join("shortcodes/ImageBlock.rhai"),
+            contents,
+        )?;
+
+        let rhai_template_factory = RhaiTemplateRendererFactory::new(
+            base_directory.path().to_path_buf(),
+            PathBuf::from("shortcodes"),
+        );
+
+        rhai_template_factory.register_component_file(
+            FileEntryStub {
+                contents: contents.to_string(),
+                relative_path: PathBuf::from("shortcodes/ImageBlock.rhai"),
+            }
+            .try_into()?,
+        )?;
+
+        let rhai_template_renderer: RhaiTemplateRenderer = rhai_template_factory.try_into()?;
+        let respond_with_kind = async |kind: &str| {
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                rhai_template_renderer: rhai_template_renderer.clone(),
+                ..BuildPromptDocumentControllerParams::mock(
+                    "picture",
+                    &formatdoc! {r#"
+                    +++
+                    description = "picture"
+                    title = "Picture"
+
+                    [arguments]
+                    +++
+
+                    **user**: Look at this:
+
+                    <ImageBlock kind="{kind}" />
+                    "#},
+                )?
+            })?
+            .respond_to(PromptsGet::mock("picture", Default::default()))
+            .await
+        };
+
+        let response = respond_with_kind("image").await?;
+
+        assert_eq!(response.messages.len(), 2);
+        assert_eq!(
+            response.messages[1].content,
+            ContentBlock::ImageContent(ImageContent {
+                data: "aGVsbG8=".to_string(),
+                mime_type: "image/png".to_string(),
+            })
+        );
+
+        let Err(err) = respond_with_kind("imgae").await else {
+            return Err(anyhow!("Expected an unknown content type to be rejected"));
+        };
+
+        let message = format!("{err:#}");
+
+        assert!(message.contains("ImageBlock"));
+        assert!(message.contains("'imgae'"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_layout_renders_leading_system_block() -> Result<()> {
+        let base_directory = tempdir()?;
+        let contents = indoc! {r#"
+            fn template(context, props, content) {
+                `Answer as ${props.persona}.`
+            }
+        "#};
+
+        fs::create_dir(base_directory.path().join("shortcodes"))?;
+        fs::write(
+            base_directory.path().join("shortcodes/Preamble.rhai"),
+            contents,
+        )?;
+
+        let rhai_template_factory = RhaiTemplateRendererFactory::new(
+            base_directory.path().to_path_buf(),
+            PathBuf::from("shortcodes"),
+        );
+
+        rhai_template_factory.register_component_file(
+            FileEntryStub {
+                contents: contents.to_string(),
+                relative_path: PathBuf::from("shortcodes/Preamble.rhai"),
+            }
+            .try_into()?,
+        )?;
+
+        let response = build_prompt_document_controller(BuildPromptDocumentControllerParams {
+            rhai_template_renderer: rhai_template_factory.try_into()?,
+            ..BuildPromptDocumentControllerParams::mock(
+                "layout",
+                indoc! {r#"
+                    +++
+                    description = "layout"
+                    layout = "Preamble"
+                    title = "Layout"
+
+                    [arguments]
+
+                    [props]
+                    persona = "a pirate"
+                    +++
+
+                    **user**: What is MCP?
+                "#},
+            )?
+        })?
+        .respond_to(PromptsGet::mock("layout", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(response.messages[0].role, Role::User);
+        assert_eq!(
+            response.messages[0].content,
+            "Answer as a pirate.\n\nWhat is MCP?".into()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_describe_summarizes_arguments_and_components() -> Result<()> {
+        let prompt_controller = PromptDocumentController::mock(
+            "review",
+            indoc! {r#"
+            +++
+            collections = ["engineering"]
+            description = "Reviews a change"
+            primary_collection = "code"
+            title = "Review"
+
+            [arguments.diff]
+            content_type = "code"
+            description = "Diff to review"
+            required = true
+            title = "Diff"
+
+            [arguments.focus]
+            description = "What to focus on"
+            required = false
+            title = "Focus"
+            +++
+
+            **user**: Review this change:
+
+            <Callout tone="info">
+              Focus on {context.arguments.focus.input}
+            </Callout>
+
+            {context.arguments.diff.input}
+            "#},
+        )?;
+        let static_prompt_controller = PromptDocumentController::mock(
+            "hello",
+            indoc! {r#"
+            +++
+            description = "Says hello"
+            title = "Hello"
+
+            [arguments]
+            +++
+
+            **user**: Hello
+            "#},
+        )?;
+
+        assert_eq!(
+            prompt_controller.describe(),
+            PromptDescription {
+                arguments: vec![
+                    PromptArgumentDescription {
+                        choices: Default::default(),
+                        content_type: ArgumentContentType::Code,
+                        default: None,
+                        description: "Diff to review".to_string(),
+                        example: None,
+                        kind: Default::default(),
+                        name: "diff".to_string(),
+                        pattern: None,
+                        required: true,
+                        sensitive: false,
+                        session_bound: false,
+                        title: "Diff".to_string(),
+                        trim: false,
+                    },
+                    PromptArgumentDescription {
+                        choices: Default::default(),
+                        content_type: ArgumentContentType::Text,
+                        default: None,
+                        description: "What to focus on".to_string(),
+                        example: None,
+                        kind: Default::default(),
+                        name: "focus".to_string(),
+                        pattern: None,
+                        required: false,
+                        sensitive: false,
+                        session_bound: false,
+                        title: "Focus".to_string(),
+                        trim: false,
+                    },
+                ],
+                collections: vec!["code".to_string(), "engineering".to_string()],
+                components: ["Callout".to_string()].into(),
+                description: "Reviews a change".to_string(),
+                is_static: false,
+                name: "review".to_string(),
+                title: "Review".to_string(),
+            }
+        );
+        assert!(static_prompt_controller.describe().is_static);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_budget_drops_content_once_exceeded() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "budget",
+            &formatdoc! {r#"
+            +++
+            description = "budget"
+            title = "Budget"
+
+            [arguments]
+            +++
+
+            **user**: Answer the question using the references below.
+
+            <Budget max_tokens={{40}}>
+              Short reference.
+            </Budget>
+
+            <Budget max_tokens="40">
+              {long_reference}
+            </Budget>
+            "#,
+                long_reference = "Long reference. ".repeat(20),
+            },
+        )?
+        .respond_to(PromptsGet::mock("budget", Default::default()))
+        .await?;
+
+        assert_eq!(
+            response.messages[0].content,
+            "Answer the question using the references below.\n\nShort reference.\n\n[Omitted to stay within the token budget]"
+                .into()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_budget_rolls_back_side_effects_of_dropped_content() -> Result<()> {
+        let response = PromptDocumentController::mock(
+            "budget",
+            &formatdoc! {r#"
+            +++
+            description = "budget"
+            title = "Budget"
+
+            [arguments]
+            +++
+
+            **user**: Answer the question
+
+            <Budget max_tokens="10">
+              <ResultMeta key="model" value="thorough" />
+
+              **assistant**: {long_draft}
+            </Budget>
+            "#,
+                long_draft = "Long draft. ".repeat(20),
+            },
+        )?
+        .respond_to(PromptsGet::mock("budget", Default::default()))
+        .await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(
+            response.messages[0].content,
+            "Answer the question\n\n[Omitted to stay within the token budget]".into()
+        );
+        assert!(serde_json::to_value(&response)?.get("_meta").is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_comment_is_dropped_but_evaluated() -> Result<()> {
+        let respond_with_comment = async |comment: &str| {
+            PromptDocumentController::mock(
+                "comment",
+                &formatdoc! {r#"
+                    +++
+                    description = "comment"
+                    title = "Comment"
+
+                    [arguments]
+                    +++
+
+                    **user**: Summarize the changelog
+
+                    <Comment>
+                      {comment}
+
+                      **assistant**: Draft answer
+                    </Comment>
+                "#},
+            )?
+            .respond_to(PromptsGet::mock("comment", Default::default()))
+            .await
+        };
+        let response = respond_with_comment("Checked against release {1 + 1}").await?;
+
+        assert_eq!(response.messages.len(), 1);
+        assert_eq!(
+            response.messages[0].content,
+            "Summarize the changelog".into()
+        );
+
+        let Err(err) = respond_with_comment("Checked against release {missing_release}").await
+        else {
+            return Err(anyhow!("Broken expression inside a comment should fail"));
+        };
+
+        assert!(format!("{err:#}").contains("missing_release"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_complete_filters_and_truncates_choices() -> Result<()> {
+        let choices = (0..150)
+            .map(|index| format!("\"option-{index:03}\""))
+            .chain(["\"other\"".to_string()])
+            .collect::<Vec<String>>()
+            .join(", ");
+        let prompt_controller = PromptDocumentController::mock(
+            "choose",
+            &formatdoc! {r#"
+            +++
+            description = "choose"
+            title = "Choose"
+
+            [arguments.client]
+            choices = ["cursor", "zed"]
+            description = "Client the prompt is rendered for"
+            required = true
+            session = "client_name"
+            title = "Client"
+
+            [arguments.tone]
+            choices = [{choices}]
+            description = "Tone of the answer"
+            required = true
+            title = "Tone"
+            +++
+
+            **user**: Answer in a {{context.arguments.tone.input}} tone
+            "#},
+        )?;
+
+        let truncated = prompt_controller.complete("tone", "option-");
+
+        assert_eq!(truncated.values.len(), 100);
+        assert_eq!(truncated.values[0], "option-000");
+        assert!(truncated.has_more);
+        assert_eq!(truncated.total, Some(150));
+
+        assert_eq!(
+            prompt_controller.complete("tone", "oth"),
+            Completion {
+                has_more: false,
+                total: Some(1),
+                values: vec!["other".to_string()],
+            }
+        );
+        assert!(prompt_controller.complete("mood", "").values.is_empty());
+        assert!(prompt_controller.complete("client", "").values.is_empty());
+
+        Ok(())
+    }
+
+    struct PromptFetcherStub {
+        responses: HashMap<String, String>,
+    }
+
+    #[async_trait]
+    impl PromptFetcher for PromptFetcherStub {
+        async fn fetch(&self, url: &Url, _max_bytes: usize) -> Result<String> {
+            self.responses
+                .get(url.as_str())
+                .cloned()
+                .ok_or_else(|| anyhow!("Unexpected fetch of '{url}'"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_is_gated_by_allowlist() -> Result<()> {
+        let respond_with_fetch = async |url: &str| {
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                fetch_policy: PromptFetchPolicy {
+                    allowlist: vec![Url::parse("https://internal.example.com/api/")?],
+                    ..Default::default()
+                },
+                fetcher: Arc::new(PromptFetcherStub {
+                    responses: HashMap::from([
+                        (
+                            "https://internal.example.com/api/status".to_string(),
+                            "All systems operational".to_string(),
+                        ),
+                        (
+                            "https://example.org/status".to_string(),
+                            "Should never be fetched".to_string(),
+                        ),
+                    ]),
+                }),
+                ..BuildPromptDocumentControllerParams::mock(
+                    "status",
+                    &formatdoc! {r#"
+                    +++
+                    description = "status"
+                    title = "Status"
+
+                    [arguments]
+                    +++
+
+                    **user**: Current status:
+
+                    <Fetch url="{url}" />
+                    "#},
+                )?
+            })?
+            .respond_to(PromptsGet::mock("status", Default::default()))
+            .await
+        };
+
+        let response = respond_with_fetch("https://internal.example.com/api/status").await?;
+
+        assert_eq!(
+            response.messages[0].content,
+            "Current status:\n\nAll systems operational".into()
+        );
+
+        let Err(err) = respond_with_fetch("https://example.org/status").await else {
+            return Err(anyhow!(
+                "Expected a URL outside of the allowlist to be rejected"
+            ));
+        };
+
+        assert_eq!(
+            format!("{err:#}"),
+            "Prompt 'status' cannot fetch 'https://example.org/status': URL 'https://example.org/status' is not on the fetch allowlist"
+        );
+
+        Ok(())
+    }
+
+    /// Never responds, like a server that accepted the connection and hangs
+    struct PromptFetcherStalled;
+
+    #[async_trait]
+    impl PromptFetcher for PromptFetcherStalled {
+        async fn fetch(&self, _url: &Url, _max_bytes: usize) -> Result<String> {
+            pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stalled_fetch_times_out() -> Result<()> {
+        let Err(err) = build_prompt_document_controller(BuildPromptDocumentControllerParams {
+            fetch_policy: PromptFetchPolicy {
+                allowlist: vec![Url::parse("https://internal.example.com/")?],
+                timeout: Duration::from_millis(10),
+                ..Default::default()
+            },
+            fetcher: Arc::new(PromptFetcherStalled),
+            ..BuildPromptDocumentControllerParams::mock(
+                "status",
+                indoc! {r#"
+                +++
+                description = "status"
+                title = "Status"
+
+                [arguments]
+                +++
+
+                **user**: Current status:
+
+                <Fetch url="https://internal.example.com/status" />
+                "#},
+            )?
+        })?
+        .respond_to(PromptsGet::mock("status", Default::default()))
+        .await
+        else {
+            return Err(anyhow!("Expected a stalled fetch to time out"));
+        };
+
+        assert_eq!(
+            format!("{err:#}"),
+            "Fetching 'https://internal.example.com/status' took longer than 10ms: deadline has elapsed"
+        );
+
+        Ok(())
+    }
+
+    /// Cancels the request as soon as a `<Budget>` measures its children,
+    /// like a client giving up halfway through the render
+    struct TokenCounterCancelling {
+        cancellation_token: CancellationToken,
+    }
+
+    impl TokenCounter for TokenCounterCancelling {
+        fn count_tokens(&self, text: &str) -> usize {
+            self.cancellation_token.cancel();
+
+            TokenCounterHeuristic.count_tokens(text)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_render_produces_no_result() -> Result<()> {
+        let cancellation_token = CancellationToken::new();
+        let prompt_document_controller =
+            build_prompt_document_controller(BuildPromptDocumentControllerParams {
+                token_counter: Arc::new(TokenCounterCancelling {
+                    cancellation_token: cancellation_token.clone(),
+                }),
+                ..BuildPromptDocumentControllerParams::mock(
+                    "cancelled",
+                    indoc! {r#"
+                    +++
+                    description = "cancelled"
+                    title = "Cancelled"
+
+                    [arguments]
+                    +++
+
+                    **user**: Answer the question using the references below.
+
+                    <Budget max_tokens={40}>
+                      Short reference.
+                    </Budget>
+
+                    **assistant**: Never rendered
+                    "#},
+                )?
+            })?;
+
+        let Err(err) = prompt_document_controller
+            .respond_to(PromptsGet {
+                cancellation_token: cancellation_token.clone(),
+                ..PromptsGet::mock("cancelled", Default::default())
+            })
+            .await
+        else {
+            return Err(anyhow!("Cancelled render should not produce a result"));
+        };
+
+        assert!(cancellation_token.is_cancelled());
+        assert!(err.is::<PromptRenderCancelled>());
+
+        Ok(())
+    }
 }
@@ -1,10 +1,118 @@
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::anyhow;
+use rhai::Dynamic;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
+
+use crate::prompt_document_front_matter::argument_content_type::ArgumentContentType;
+use crate::prompt_document_front_matter::argument_kind::ArgumentKind;
+use crate::prompt_document_front_matter::argument_pattern::ArgumentPattern;
+use crate::prompt_document_front_matter::argument_session_value::ArgumentSessionValue;
 
 #[derive(Clone, Deserialize, Serialize)]
-#[serde(deny_unknown_fields)]
 pub struct Argument {
+    /// Only values accepted when not empty, also offered to clients that
+    /// autocomplete the argument
+    #[serde(default)]
+    pub choices: Vec<String>,
+    /// Influences how the input is interpolated, for example code is fenced
+    #[serde(default)]
+    pub content_type: ArgumentContentType,
+    /// Used when the client omits the argument, parsed like any other input
+    #[serde(default)]
+    pub default: Option<Value>,
+    /// `default` parsed once, when the prompt is built
+    #[serde(skip)]
+    pub default_input: Option<Dynamic>,
     pub description: String,
+    /// Sample value shown to clients as a placeholder, not used as input
+    #[serde(default)]
+    pub example: Option<Value>,
+    /// Type of the value templates receive, parsed from the text input
+    #[serde(default)]
+    pub kind: ArgumentKind,
+    /// Regular expression every input has to match as a whole
+    #[serde(default)]
+    pub pattern: Option<ArgumentPattern>,
     pub required: bool,
+    /// Redacts the input from logs and diagnostics
+    #[serde(default)]
+    pub sensitive: bool,
+    /// Filled in from the session instead of the request, and hidden from
+    /// clients listing the prompt
+    #[serde(default)]
+    pub session: Option<ArgumentSessionValue>,
     pub title: String,
+    /// Removes trailing whitespace from the input before it is interpolated
+    #[serde(default)]
+    pub trim: bool,
+}
+
+impl Argument {
+    /// Checks the choices and parses the default once, so mistakes in the
+    /// front matter fail the build instead of requests
+    pub fn parse_default(&mut self, name: &str) -> Result<()> {
+        for choice in &self.choices {
+            if let Err(err) = self.parse_input(name, choice.clone()) {
+                return Err(err.context(format!(
+                    "Argument '{name}' has an invalid choice '{choice}'"
+                )));
+            }
+        }
+
+        self.default_input = match &self.default {
+            Some(default) => Some(
+                self.parse_input(
+                    name,
+                    match default {
+                        Value::String(default) => default.clone(),
+                        default => default.to_string(),
+                    },
+                )
+                .with_context(|| format!("Argument '{name}' has an invalid default"))?,
+            ),
+            None => None,
+        };
+
+        Ok(())
+    }
+
+    pub fn parse_input(&self, name: &str, input: String) -> Result<Dynamic> {
+        let input = if self.trim {
+            input.trim_end().to_string()
+        } else {
+            input
+        };
+
+        if !self.choices.is_empty() && !self.choices.contains(&input) {
+            return Err(anyhow!(
+                "Argument '{name}' must be one of [{}], got '{input}'",
+                self.choices.join(", ")
+            ));
+        }
+
+        if let Some(pattern) = &self.pattern
+            && !pattern.is_match(&input)
+        {
+            return Err(anyhow!(
+                "Argument '{name}' must match the pattern '{}'",
+                pattern.pattern
+            ));
+        }
+
+        let input = match self.kind {
+            ArgumentKind::String => self.content_type.format_input(input).into(),
+            kind => kind
+                .parse_input(input)
+                .with_context(|| format!("Argument '{name}' expects {kind} input"))?,
+        };
+
+        if self.required && input.as_array_ref().is_ok_and(|items| items.is_empty()) {
+            return Err(anyhow!("Argument '{name}' requires at least one item"));
+        }
+
+        Ok(input)
+    }
 }
@@ -0,0 +1,43 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::fence_code_block::fence_code_block;
+
+/// Decides how an argument input is interpolated into the prompt
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgumentContentType {
+    /// Wrapped in a fenced code block
+    Code,
+    /// Inserted as-is, so its formatting is kept
+    Markdown,
+    /// Inserted as-is
+    #[default]
+    Text,
+}
+
+impl ArgumentContentType {
+    pub fn format_input(&self, input: String) -> String {
+        match self {
+            ArgumentContentType::Code => fence_code_block(&input, "", '`'),
+            ArgumentContentType::Markdown | ArgumentContentType::Text => input,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_fence_outlasts_backticks_in_input() {
+        assert_eq!(
+            ArgumentContentType::Code.format_input("let a = 1;".to_string()),
+            "```\nlet a = 1;\n```"
+        );
+        assert_eq!(
+            ArgumentContentType::Code.format_input("````".to_string()),
+            "`````\n````\n`````"
+        );
+    }
+}
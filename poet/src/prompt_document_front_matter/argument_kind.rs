@@ -0,0 +1,115 @@
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::anyhow;
+use rhai::Array;
+use rhai::Dynamic;
+use rhai::serde::to_dynamic;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Type the input is converted to before it is exposed to templates.
+/// Clients always send text, so anything but strings is parsed.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgumentKind {
+    /// List of items, either comma-separated or a JSON array
+    Array,
+    /// `true` or `false`
+    Boolean,
+    /// Whole number
+    Integer,
+    /// Floating point number
+    Number,
+    #[default]
+    String,
+}
+
+impl ArgumentKind {
+    pub fn parse_input(&self, input: String) -> Result<Dynamic> {
+        match self {
+            ArgumentKind::Array => {
+                let input = input.trim();
+
+                if input.starts_with('[') {
+                    let items: Vec<Value> =
+                        serde_json::from_str(input).context("Expected a JSON array")?;
+
+                    return to_dynamic(items)
+                        .map_err(|err| anyhow!("Unable to convert the JSON array: {err}"));
+                }
+
+                Ok(input
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| Dynamic::from(item.to_string()))
+                    .collect::<Array>()
+                    .into())
+            }
+            ArgumentKind::Boolean => Ok(Dynamic::from_bool(
+                input
+                    .trim()
+                    .parse::<bool>()
+                    .context("Expected 'true' or 'false'")?,
+            )),
+            ArgumentKind::Integer => Ok(Dynamic::from_int(
+                input
+                    .trim()
+                    .parse::<i64>()
+                    .context("Expected a whole number")?,
+            )),
+            ArgumentKind::Number => {
+                let number = input.trim().parse::<f64>().context("Expected a number")?;
+
+                if !number.is_finite() {
+                    return Err(anyhow!("Expected a finite number"));
+                }
+
+                Ok(Dynamic::from_float(number))
+            }
+            ArgumentKind::String => Ok(input.into()),
+        }
+    }
+}
+
+impl Display for ArgumentKind {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            ArgumentKind::Array => "array",
+            ArgumentKind::Boolean => "boolean",
+            ArgumentKind::Integer => "integer",
+            ArgumentKind::Number => "number",
+            ArgumentKind::String => "string",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_finite_numbers_are_rejected() -> Result<()> {
+        for input in ["NaN", "inf", "-infinity"] {
+            let Err(err) = ArgumentKind::Number.parse_input(input.to_string()) else {
+                return Err(anyhow!("Expected '{input}' to be rejected"));
+            };
+
+            assert_eq!(err.to_string(), "Expected a finite number");
+        }
+
+        assert_eq!(
+            ArgumentKind::Number
+                .parse_input("1e3".to_string())?
+                .as_float(),
+            Ok(1000.0)
+        );
+
+        Ok(())
+    }
+}
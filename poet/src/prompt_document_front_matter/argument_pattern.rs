@@ -0,0 +1,43 @@
+use regex::Regex;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use serde::de::Error as _;
+
+/// Compiled when the front matter is parsed, so an invalid pattern fails the
+/// build and requests reuse the compiled expression. The whole input has to
+/// match, not just a part of it.
+#[derive(Clone, Debug)]
+pub struct ArgumentPattern {
+    pub pattern: String,
+    regex: Regex,
+}
+
+impl ArgumentPattern {
+    pub fn is_match(&self, input: &str) -> bool {
+        self.regex.is_match(input)
+    }
+}
+
+impl<'de> Deserialize<'de> for ArgumentPattern {
+    fn deserialize<TDeserializer: Deserializer<'de>>(
+        deserializer: TDeserializer,
+    ) -> Result<Self, TDeserializer::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        let regex = Regex::new(&format!("^(?:{pattern})$")).map_err(|err| {
+            TDeserializer::Error::custom(format!("Invalid argument pattern '{pattern}': {err}"))
+        })?;
+
+        Ok(Self { pattern, regex })
+    }
+}
+
+impl Serialize for ArgumentPattern {
+    fn serialize<TSerializer: Serializer>(
+        &self,
+        serializer: TSerializer,
+    ) -> Result<TSerializer::Ok, TSerializer::Error> {
+        serializer.serialize_str(&self.pattern)
+    }
+}
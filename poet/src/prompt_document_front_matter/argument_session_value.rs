@@ -0,0 +1,24 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::mcp::jsonrpc::implementation::Implementation;
+
+/// Value captured when the session is initialized, filled in instead of an
+/// input sent with the request
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgumentSessionValue {
+    /// `clientInfo.name` from `initialize`
+    ClientName,
+    /// `clientInfo.version` from `initialize`
+    ClientVersion,
+}
+
+impl ArgumentSessionValue {
+    pub fn resolve(&self, client_info: &Implementation) -> String {
+        match self {
+            ArgumentSessionValue::ClientName => client_info.name.clone(),
+            ArgumentSessionValue::ClientVersion => client_info.version.clone(),
+        }
+    }
+}
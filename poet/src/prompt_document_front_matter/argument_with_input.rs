@@ -1,23 +1,53 @@
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+
 use rhai::CustomType;
+use rhai::Dynamic;
 use rhai::TypeBuilder;
 use serde::Deserialize;
 use serde::Serialize;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+const REDACTED_INPUT: &str = "***";
+
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct ArgumentWithInput {
     pub description: String,
-    pub input: String,
+    /// Parsed according to the kind of the argument
+    pub input: Dynamic,
     pub required: bool,
+    #[serde(default)]
+    pub sensitive: bool,
     pub title: String,
 }
 
+impl Debug for ArgumentWithInput {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ArgumentWithInput")
+            .field("description", &self.description)
+            .field(
+                "input",
+                if self.sensitive {
+                    &REDACTED_INPUT as &dyn Debug
+                } else {
+                    &self.input
+                },
+            )
+            .field("required", &self.required)
+            .field("sensitive", &self.sensitive)
+            .field("title", &self.title)
+            .finish()
+    }
+}
+
 impl ArgumentWithInput {
     pub fn rhai_description(&mut self) -> String {
         self.description.clone()
     }
 
-    pub fn rhai_input(&mut self) -> String {
+    pub fn rhai_input(&mut self) -> Dynamic {
         self.input.clone()
     }
 
@@ -40,3 +70,27 @@ impl CustomType for ArgumentWithInput {
             .with_get("title", Self::rhai_title);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argument_with_input(sensitive: bool) -> ArgumentWithInput {
+        ArgumentWithInput {
+            description: "API token".to_string(),
+            input: "hunter2".into(),
+            required: true,
+            sensitive,
+            title: "Token".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sensitive_input_is_redacted_in_debug_output() {
+        let sensitive_debug_output = format!("{:?}", argument_with_input(true));
+
+        assert!(!sensitive_debug_output.contains("hunter2"));
+        assert!(sensitive_debug_output.contains(REDACTED_INPUT));
+        assert!(format!("{:?}", argument_with_input(false)).contains("hunter2"));
+    }
+}
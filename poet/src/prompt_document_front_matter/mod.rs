@@ -1,64 +1,201 @@
 pub mod argument;
+pub mod argument_content_type;
+pub mod argument_kind;
+pub mod argument_pattern;
+pub mod argument_session_value;
 pub mod argument_with_input;
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 
 use anyhow::Result;
 use anyhow::anyhow;
+use indexmap::IndexMap;
+use rhai::Array;
 use rhai::CustomType;
+use rhai::Dynamic;
+use rhai::Map;
 use rhai::TypeBuilder;
+use rhai::serde::to_dynamic;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
 
 use self::argument::Argument;
+use crate::conform_prop_override::conform_prop_override;
+use crate::content_document_front_matter::excerpt::Excerpt;
+use crate::mcp::jsonrpc::implementation::Implementation;
+use crate::prompt_collection_config::PromptCollectionConfig;
 use crate::prompt_document_front_matter::argument_with_input::ArgumentWithInput;
+use crate::prompt_render_limits::PromptRenderLimits;
 
 #[derive(Clone, Deserialize, Serialize)]
-#[serde(deny_unknown_fields)]
 pub struct PromptDocumentFrontMatter {
-    pub arguments: HashMap<String, Argument>,
+    /// Kept in declaration order, so positional values can be mapped onto it
+    pub arguments: IndexMap<String, Argument>,
+    /// The last message is a partial assistant message that the model should continue
+    #[serde(default)]
+    pub assistant_prefill: bool,
+    /// Include a checksum of the rendered messages in the response metadata
+    #[serde(default)]
+    pub checksum: bool,
+    #[serde(default)]
+    pub collections: Vec<String>,
+    /// Still renders, but is flagged in listings and warned about at build
+    #[serde(default)]
+    pub deprecated: bool,
     pub description: String,
+    /// Snippets components can pull in by type, see `context.excerpt`
+    #[serde(default)]
+    pub excerpts: Vec<Excerpt>,
+    /// Keeps messages without content at the start and end of the prompt
+    #[serde(default)]
+    pub keep_empty_messages: bool,
+    /// Shortcode that renders a preamble before the prompt, checked at build
+    /// time
+    #[serde(default)]
+    pub layout: Option<String>,
+    /// Overrides of the global render limits, up to their hard ceilings
+    #[serde(default)]
+    pub limits: PromptRenderLimits,
+    /// Replaces curly quotes and typographic dashes with ASCII outside of code
+    #[serde(default)]
+    pub normalize_punctuation: bool,
+    #[serde(default)]
+    pub primary_collection: Option<String>,
+    /// Static parameters available as `context.front_matter.props`. Their
+    /// values also declare which props a request can override, and as what
+    /// type.
+    #[serde(default)]
+    pub props: Map,
+    /// Entries added to the `_meta` of every result, unless a `<ResultMeta />`
+    /// component sets the same key
+    #[serde(default)]
+    pub result_meta: BTreeMap<String, Value>,
+    /// Falls back to the first `#` heading of the document when omitted
+    #[serde(default)]
     pub title: String,
 }
 
 impl PromptDocumentFrontMatter {
+    /// Adds the shared arguments of every collection the prompt belongs to,
+    /// keeping the prompt's own declarations when the names clash
+    pub fn inherit_collection_arguments(
+        &mut self,
+        prompt_collection_configs: &HashMap<String, PromptCollectionConfig>,
+    ) {
+        for collection in self.primary_collection.iter().chain(&self.collections) {
+            if let Some(prompt_collection_config) = prompt_collection_configs.get(collection) {
+                for (name, argument) in &prompt_collection_config.arguments {
+                    self.arguments
+                        .entry(name.clone())
+                        .or_insert_with(|| argument.clone());
+                }
+            }
+        }
+    }
+
+    pub fn is_categorized(&self) -> bool {
+        !self.collections.is_empty() || self.primary_collection.is_some()
+    }
+
+    pub fn parse_argument_defaults(&mut self) -> Result<()> {
+        for (name, argument) in &mut self.arguments {
+            argument.parse_default(name)?;
+        }
+
+        Ok(())
+    }
+
     pub fn map_arguments(
         &self,
         inputs: HashMap<String, String>,
+        client_info: Option<&Implementation>,
     ) -> Result<HashMap<String, ArgumentWithInput>> {
         self.arguments
-            .clone()
-            .into_iter()
-            .map(
-                |(
-                    name,
-                    Argument {
-                        description,
-                        required,
-                        title,
+            .iter()
+            .map(|(name, argument)| {
+                // Session-bound arguments cannot be overridden by the request
+                let provided = match &argument.session {
+                    Some(session) => client_info.map(|client_info| session.resolve(client_info)),
+                    None => inputs.get(name).cloned(),
+                };
+                let input = match (provided, &argument.default_input) {
+                    (Some(input), _) => argument.parse_input(name, input)?,
+                    (None, Some(default_input)) => default_input.clone(),
+                    (None, None) => {
+                        return Err(anyhow!("No argument provided for '{name}'"));
+                    }
+                };
+
+                Ok((
+                    name.clone(),
+                    ArgumentWithInput {
+                        description: argument.description.clone(),
+                        input,
+                        required: argument.required,
+                        sensitive: argument.sensitive,
+                        title: argument.title.clone(),
                     },
-                )| {
-                    Ok((
-                        name.clone(),
-                        ArgumentWithInput {
-                            description,
-                            input: inputs
-                                .get(&name)
-                                .ok_or_else(|| anyhow!("No argument provided for '{name}'"))?
-                                .to_string(),
-                            required,
-                            title,
-                        },
-                    ))
-                },
-            )
+                ))
+            })
             .collect()
     }
 
+    /// Names positional argument values after the declared arguments, in
+    /// declaration order, producing inputs that `map_arguments` accepts.
+    /// Session-bound arguments are skipped.
+    pub fn map_positional_arguments(&self, values: Vec<String>) -> Result<HashMap<String, String>> {
+        let names: Vec<String> = self
+            .arguments
+            .iter()
+            .filter(|(_, argument)| argument.session.is_none())
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        if values.len() > names.len() {
+            return Err(anyhow!(
+                "Expected at most {} positional arguments, but {} were provided",
+                names.len(),
+                values.len()
+            ));
+        }
+
+        Ok(names.into_iter().zip(values).collect())
+    }
+
+    pub fn override_props(&mut self, overrides: BTreeMap<String, Value>) -> Result<()> {
+        for (name, value) in overrides {
+            let Some(declared) = self.props.get(name.as_str()) else {
+                return Err(anyhow!(
+                    "Prop '{name}' cannot be overridden, because it is not declared in the front matter"
+                ));
+            };
+            let value: Dynamic = to_dynamic(value)
+                .map_err(|err| anyhow!("Prop '{name}' override is not usable: {err}"))?;
+            let value = conform_prop_override(&name, declared, value)?;
+
+            self.props.insert(name.into(), value);
+        }
+
+        Ok(())
+    }
+
     fn rhai_description(&mut self) -> String {
         self.description.clone()
     }
 
+    fn rhai_excerpts(&mut self) -> Array {
+        self.excerpts
+            .iter()
+            .map(|excerpt| Dynamic::from_map(excerpt.to_rhai_map()))
+            .collect()
+    }
+
+    fn rhai_props(&mut self) -> Map {
+        self.props.clone()
+    }
+
     fn rhai_title(&mut self) -> String {
         self.title.clone()
     }
@@ -69,6 +206,251 @@ impl CustomType for PromptDocumentFrontMatter {
         builder
             .with_name("PromptDocumentFrontMatter")
             .with_get("description", Self::rhai_description)
+            .with_get("excerpts", Self::rhai_excerpts)
+            .with_get("props", Self::rhai_props)
             .with_get("title", Self::rhai_title);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn front_matter_with_argument(trim: bool) -> PromptDocumentFrontMatter {
+        PromptDocumentFrontMatter {
+            arguments: IndexMap::from([(
+                "snippet".to_string(),
+                Argument {
+                    choices: Default::default(),
+                    content_type: Default::default(),
+                    default: None,
+                    default_input: None,
+                    description: "Pasted snippet".to_string(),
+                    example: None,
+                    kind: Default::default(),
+                    pattern: None,
+                    required: true,
+                    sensitive: false,
+                    session: None,
+                    title: "Snippet".to_string(),
+                    trim,
+                },
+            )]),
+            assistant_prefill: false,
+            checksum: false,
+            collections: Default::default(),
+            deprecated: false,
+            description: "trim".to_string(),
+            excerpts: Default::default(),
+            keep_empty_messages: false,
+            layout: None,
+            limits: Default::default(),
+            normalize_punctuation: false,
+            primary_collection: None,
+            props: Default::default(),
+            result_meta: Default::default(),
+            title: "Trim".to_string(),
+        }
+    }
+
+    fn mapped_input(trim: bool) -> Result<String> {
+        let arguments = front_matter_with_argument(trim).map_arguments(
+            HashMap::from([("snippet".to_string(), "fn main() {}\n\n".to_string())]),
+            None,
+        )?;
+
+        arguments
+            .get("snippet")
+            .map(|argument| argument.input.to_string())
+            .ok_or_else(|| anyhow!("Argument was not mapped"))
+    }
+
+    #[test]
+    fn test_trailing_whitespace_is_trimmed_only_when_enabled() -> Result<()> {
+        assert_eq!(mapped_input(true)?, "fn main() {}");
+        assert_eq!(mapped_input(false)?, "fn main() {}\n\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inputs_are_parsed_into_argument_kinds() -> Result<()> {
+        let front_matter: PromptDocumentFrontMatter = toml::from_str(indoc! {r#"
+            description = "typed"
+            title = "Typed"
+
+            [arguments.count]
+            description = "How many"
+            kind = "integer"
+            required = true
+            title = "Count"
+
+            [arguments.ratio]
+            description = "How much"
+            kind = "number"
+            required = true
+            title = "Ratio"
+
+            [arguments.strict]
+            description = "Whether to be strict"
+            kind = "boolean"
+            required = true
+            title = "Strict"
+
+            [arguments.topic]
+            description = "What about"
+            required = true
+            title = "Topic"
+        "#})?;
+        let inputs = |ratio: &str| {
+            HashMap::from([
+                ("count".to_string(), "42".to_string()),
+                ("ratio".to_string(), ratio.to_string()),
+                ("strict".to_string(), "true".to_string()),
+                ("topic".to_string(), "42".to_string()),
+            ])
+        };
+        let arguments = front_matter.map_arguments(inputs("0.5"), None)?;
+        let input = |name: &str| -> Result<Dynamic> {
+            arguments
+                .get(name)
+                .map(|argument| argument.input.clone())
+                .ok_or_else(|| anyhow!("Argument '{name}' was not mapped"))
+        };
+
+        assert_eq!(input("count")?.as_int(), Ok(42));
+        assert_eq!(input("ratio")?.as_float(), Ok(0.5));
+        assert_eq!(input("strict")?.as_bool(), Ok(true));
+        assert_eq!(input("topic")?.into_string(), Ok("42".to_string()));
+
+        let Err(err) = front_matter.map_arguments(inputs("abc"), None) else {
+            return Err(anyhow!("Expected 'abc' not to parse as a number"));
+        };
+
+        assert_eq!(
+            format!("{err:#}"),
+            "Argument 'ratio' expects number input: Expected a number: invalid float literal"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_array_input_is_split_or_parsed() -> Result<()> {
+        let front_matter: PromptDocumentFrontMatter = toml::from_str(indoc! {r#"
+            description = "review"
+            title = "Review"
+
+            [arguments.files]
+            description = "Files to review"
+            kind = "array"
+            required = true
+            title = "Files"
+        "#})?;
+        let map_files = |files: &str| -> Result<Vec<String>> {
+            front_matter
+                .map_arguments(
+                    HashMap::from([("files".to_string(), files.to_string())]),
+                    None,
+                )?
+                .remove("files")
+                .ok_or_else(|| anyhow!("Argument 'files' was not mapped"))?
+                .input
+                .into_array()
+                .map_err(|type_name| anyhow!("Expected an array, got {type_name}"))
+                .map(|items| items.iter().map(Dynamic::to_string).collect())
+        };
+
+        assert_eq!(map_files("main.rs, lib.rs,")?, vec!["main.rs", "lib.rs"]);
+        assert_eq!(
+            map_files(r#"["main.rs", "lib.rs"]"#)?,
+            vec!["main.rs", "lib.rs"]
+        );
+
+        let Err(err) = map_files(" ") else {
+            return Err(anyhow!("Expected an empty required array to be rejected"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Argument 'files' requires at least one item"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_is_validated_against_choices() -> Result<()> {
+        let front_matter: PromptDocumentFrontMatter = toml::from_str(indoc! {r#"
+            description = "triage"
+            title = "Triage"
+
+            [arguments.priority]
+            choices = ["low", "medium", "high"]
+            description = "How urgent it is"
+            required = true
+            title = "Priority"
+        "#})?;
+        let map_priority = |priority: &str| {
+            front_matter.map_arguments(
+                HashMap::from([("priority".to_string(), priority.to_string())]),
+                None,
+            )
+        };
+
+        assert_eq!(
+            map_priority("medium")?
+                .get("priority")
+                .map(|argument| argument.input.to_string()),
+            Some("medium".to_string())
+        );
+
+        let Err(err) = map_priority("urgent") else {
+            return Err(anyhow!(
+                "Expected a value outside of the choices to be rejected"
+            ));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Argument 'priority' must be one of [low, medium, high], got 'urgent'"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_is_validated_against_pattern() -> Result<()> {
+        let front_matter: PromptDocumentFrontMatter = toml::from_str(indoc! {r#"
+            description = "schedule"
+            title = "Schedule"
+
+            [arguments.date]
+            description = "ISO date"
+            pattern = '\d{4}-\d{2}-\d{2}'
+            required = true
+            title = "Date"
+        "#})?;
+        let map_date = |date: &str| {
+            front_matter.map_arguments(
+                HashMap::from([("date".to_string(), date.to_string())]),
+                None,
+            )
+        };
+
+        assert!(map_date("2026-10-15").is_ok());
+
+        let Err(err) = map_date("2026-10-15 or later") else {
+            return Err(anyhow!("Expected a partial match to be rejected"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            r"Argument 'date' must match the pattern '\d{4}-\d{2}-\d{2}'"
+        );
+
+        Ok(())
+    }
+}
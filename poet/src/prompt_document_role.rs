@@ -0,0 +1,34 @@
+use anyhow::Result;
+use anyhow::anyhow;
+
+use crate::mcp::jsonrpc::role::Role;
+
+/// Roles that prompt documents can switch to. MCP messages only come from
+/// the user or the assistant, so system blocks never leave the render.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PromptDocumentRole {
+    Message(Role),
+    /// Lowered into the leading user message once the render is done
+    System,
+}
+
+impl TryFrom<&str> for PromptDocumentRole {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "assistant" => Ok(PromptDocumentRole::Message(Role::Assistant)),
+            "system" => Ok(PromptDocumentRole::System),
+            "user" => Ok(PromptDocumentRole::Message(Role::User)),
+            _ => Err(anyhow!("Unknown role: {value}")),
+        }
+    }
+}
+
+impl TryFrom<String> for PromptDocumentRole {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.as_str().try_into()
+    }
+}
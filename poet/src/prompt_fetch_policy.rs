@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use url::Url;
+
+const DEFAULT_MAX_BYTES: usize = 1024 * 1024;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Limits what `<Fetch>` components can retrieve while a prompt renders.
+/// Nothing can be fetched unless it is allowlisted.
+#[derive(Clone, Debug)]
+pub struct PromptFetchPolicy {
+    /// A URL is allowed when it has the same origin as one of these, and its
+    /// path is that entry's path or lies below it
+    pub allowlist: Vec<Url>,
+    pub max_bytes: usize,
+    pub timeout: Duration,
+}
+
+fn is_path_within(allowed_path: &str, path: &str) -> bool {
+    match path.strip_prefix(allowed_path) {
+        Some(rest) => rest.is_empty() || allowed_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+impl PromptFetchPolicy {
+    pub fn assert_allowed(&self, url: &Url) -> Result<()> {
+        let is_allowed = self.allowlist.iter().any(|allowed_url| {
+            allowed_url.origin() == url.origin() && is_path_within(allowed_url.path(), url.path())
+        });
+
+        if !is_allowed {
+            return Err(anyhow!("URL '{url}' is not on the fetch allowlist"));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for PromptFetchPolicy {
+    fn default() -> Self {
+        Self {
+            allowlist: Vec::new(),
+            max_bytes: DEFAULT_MAX_BYTES,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowlist_matches_whole_path_segments() -> Result<()> {
+        let fetch_policy = PromptFetchPolicy {
+            allowlist: vec![Url::parse("https://internal.example.com/api")?],
+            ..Default::default()
+        };
+
+        fetch_policy.assert_allowed(&Url::parse("https://internal.example.com/api")?)?;
+        fetch_policy.assert_allowed(&Url::parse("https://internal.example.com/api/status")?)?;
+
+        for url in [
+            "https://internal.example.com/api-admin",
+            "https://internal.example.com/apiary",
+        ] {
+            assert!(fetch_policy.assert_allowed(&Url::parse(url)?).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_allowlist_matches_origin_and_path_prefix() -> Result<()> {
+        let fetch_policy = PromptFetchPolicy {
+            allowlist: vec![Url::parse("https://internal.example.com/api/")?],
+            ..Default::default()
+        };
+
+        fetch_policy.assert_allowed(&Url::parse("https://internal.example.com/api/status")?)?;
+
+        assert!(
+            fetch_policy
+                .assert_allowed(&Url::parse("https://internal.example.com/admin")?)
+                .is_err()
+        );
+        assert!(
+            fetch_policy
+                .assert_allowed(&Url::parse("https://internal.example.com.evil.test/api/")?)
+                .is_err()
+        );
+        assert!(
+            fetch_policy
+                .assert_allowed(&Url::parse("http://internal.example.com/api/status")?)
+                .is_err()
+        );
+
+        Ok(())
+    }
+}
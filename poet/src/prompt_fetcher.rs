@@ -0,0 +1,12 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use url::Url;
+
+/// Retrieves text for `<Fetch>` components. The URL is already checked
+/// against the allowlist, and the caller enforces the timeout.
+#[async_trait]
+pub trait PromptFetcher: Send + Sync {
+    /// Should stop reading once the body exceeds `max_bytes`, rather than
+    /// buffering all of it
+    async fn fetch(&self, url: &Url, max_bytes: usize) -> Result<String>;
+}
@@ -0,0 +1,54 @@
+use anyhow::Result;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use reqwest::Client;
+use reqwest::redirect::Policy;
+use url::Url;
+
+use crate::prompt_fetcher::PromptFetcher;
+
+/// Redirects are not followed, since an allowlisted URL could otherwise
+/// bounce the request to any host
+pub struct PromptFetcherHttp {
+    client: Client,
+}
+
+impl PromptFetcherHttp {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: Client::builder().redirect(Policy::none()).build()?,
+        })
+    }
+}
+
+#[async_trait]
+impl PromptFetcher for PromptFetcherHttp {
+    async fn fetch(&self, url: &Url, max_bytes: usize) -> Result<String> {
+        let mut response = self
+            .client
+            .get(url.clone())
+            .send()
+            .await?
+            .error_for_status()?;
+
+        if response.status().is_redirection() {
+            return Err(anyhow!(
+                "Response from '{url}' is a redirect, which is not followed"
+            ));
+        }
+
+        let mut body: Vec<u8> = Vec::new();
+
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > max_bytes {
+                return Err(anyhow!(
+                    "Response from '{url}' exceeds the limit of {max_bytes} bytes"
+                ));
+            }
+
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(String::from_utf8(body)?)
+    }
+}
@@ -0,0 +1,13 @@
+use clap::ValueEnum;
+
+/// Decides what happens with markdown images in the prompt body
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum PromptImagePolicy {
+    /// Embed images as image content blocks (only `data:` URIs carry the image bytes)
+    Inline,
+    /// Keep images as markdown text
+    #[default]
+    Markdown,
+    /// Turn images into resource links
+    ResourceLink,
+}
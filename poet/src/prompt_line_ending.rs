@@ -0,0 +1,58 @@
+use crate::mcp::content_block::ContentBlock;
+use crate::mcp::content_block::text_content::TextContent;
+use crate::mcp::prompt_message::PromptMessage;
+
+/// Line ending that every text message is rewritten to, so content coming
+/// from sources with mixed endings reaches clients consistently
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PromptLineEnding {
+    Crlf,
+    Lf,
+}
+
+impl PromptLineEnding {
+    pub fn apply(&self, prompt_messages: Vec<PromptMessage>) -> Vec<PromptMessage> {
+        prompt_messages
+            .into_iter()
+            .map(|prompt_message| match prompt_message {
+                PromptMessage {
+                    content: ContentBlock::TextContent(TextContent { text }),
+                    role,
+                } => PromptMessage {
+                    content: self.normalize(&text).into(),
+                    role,
+                },
+                prompt_message => prompt_message,
+            })
+            .collect()
+    }
+
+    /// Lone `\r` is treated as a line break too
+    pub fn normalize(&self, text: &str) -> String {
+        let line_feeds_only = text.replace("\r\n", "\n").replace('\r', "\n");
+
+        match self {
+            PromptLineEnding::Crlf => line_feeds_only.replace('\n', "\r\n"),
+            PromptLineEnding::Lf => line_feeds_only,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixed_line_endings_are_normalized() {
+        let text = "```rust\r\nfn main() {}\r\n```\nfirst\r\nsecond\rthird";
+
+        assert_eq!(
+            PromptLineEnding::Lf.normalize(text),
+            "```rust\nfn main() {}\n```\nfirst\nsecond\nthird"
+        );
+        assert_eq!(
+            PromptLineEnding::Crlf.normalize(text),
+            "```rust\r\nfn main() {}\r\n```\r\nfirst\r\nsecond\r\nthird"
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use serde::Serialize;
+
+use crate::mcp::prompt_controller_collection::PromptControllerCollection;
+use crate::prompt_manifest_operation::PromptManifestOperation;
+
+/// Bumped on any change other than adding fields
+const PROMPT_MANIFEST_VERSION: u32 = 2;
+
+/// Every prompt of a collection described as an operation, for API gateways
+/// and other tooling that does not speak MCP. Operations are sorted by
+/// prompt name.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptManifest {
+    pub manifest_version: u32,
+    pub operations: Vec<PromptManifestOperation>,
+}
+
+impl From<&PromptControllerCollection> for PromptManifest {
+    fn from(prompt_controller_collection: &PromptControllerCollection) -> Self {
+        Self {
+            manifest_version: PROMPT_MANIFEST_VERSION,
+            operations: prompt_controller_collection
+                .prompt_controllers
+                .values()
+                .map(|prompt_controller| prompt_controller.describe().into())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use anyhow::Result;
+    use indoc::indoc;
+    use serde_json::json;
+
+    use super::*;
+    use crate::mcp::prompt_controller::PromptController;
+    use crate::prompt_document_controller::PromptDocumentController;
+
+    #[test]
+    fn test_manifest_lists_every_prompt_with_its_argument_schema() -> Result<()> {
+        let prompt_controller_collection: PromptControllerCollection = [
+            (
+                "greeting".to_string(),
+                Arc::new(PromptDocumentController::mock(
+                    "greeting",
+                    indoc! {r#"
+                    +++
+                    description = "Says hello"
+                    title = "Greeting"
+
+                    [arguments]
+                    +++
+
+                    **user**: Hello
+                    "#},
+                )?) as Arc<dyn PromptController>,
+            ),
+            (
+                "review".to_string(),
+                Arc::new(PromptDocumentController::mock(
+                    "review",
+                    indoc! {r#"
+                    +++
+                    description = "Reviews a change"
+                    primary_collection = "code"
+                    title = "Review"
+
+                    [arguments.diff]
+                    description = "Diff to review"
+                    pattern = "[^\\s].*"
+                    required = true
+                    title = "Diff"
+
+                    [arguments.files]
+                    description = "Files to focus on"
+                    kind = "array"
+                    required = false
+                    title = "Files"
+
+                    [arguments.tone]
+                    choices = ["friendly", "strict"]
+                    default = "friendly"
+                    description = "How to phrase it"
+                    required = false
+                    title = "Tone"
+
+                    [arguments.user]
+                    description = "Who asks"
+                    required = true
+                    session = "client_name"
+                    title = "User"
+                    +++
+
+                    **user**: Review {context.arguments.diff.input}
+                    "#},
+                )?),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let manifest = serde_json::to_value(PromptManifest::from(&prompt_controller_collection))?;
+
+        assert_eq!(manifest["manifestVersion"], 2);
+        assert_eq!(
+            manifest["operations"]
+                .as_array()
+                .map(|operations| operations
+                    .iter()
+                    .map(|operation| operation["operationId"].clone())
+                    .collect::<Vec<_>>()),
+            Some(vec![json!("greeting"), json!("review")])
+        );
+        assert_eq!(
+            manifest["operations"][0]["argumentsSchema"],
+            json!({ "properties": {}, "required": [], "type": "object" })
+        );
+        assert_eq!(
+            manifest["operations"][1]["argumentsSchema"],
+            json!({
+                "properties": {
+                    "diff": {
+                        "description": "Diff to review",
+                        "title": "Diff",
+                        "type": "string",
+                        "x-poet-kind": "string",
+                        "x-poet-pattern": "[^\\s].*",
+                    },
+                    "files": {
+                        "description": "Files to focus on",
+                        "title": "Files",
+                        "type": "string",
+                        "x-poet-kind": "array",
+                    },
+                    "tone": {
+                        "default": "friendly",
+                        "description": "How to phrase it",
+                        "enum": ["friendly", "strict"],
+                        "title": "Tone",
+                        "type": "string",
+                        "x-poet-kind": "string",
+                    },
+                },
+                "required": ["diff"],
+                "type": "object",
+            })
+        );
+        assert_eq!(manifest["operations"][1]["summary"], "Review");
+        assert_eq!(manifest["operations"][1]["tags"], json!(["code"]));
+
+        Ok(())
+    }
+}
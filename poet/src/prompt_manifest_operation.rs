@@ -0,0 +1,152 @@
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::mcp::content_block::ContentBlock;
+use crate::mcp::prompt_description::PromptDescription;
+
+fn response_schema() -> Value {
+    json!({
+        "properties": {
+            "description": { "type": "string" },
+            "messages": {
+                "items": {
+                    "properties": {
+                        "content": {
+                            "properties": {
+                                "type": { "enum": ContentBlock::TYPE_NAMES, "type": "string" },
+                            },
+                            "required": ["type"],
+                            "type": "object",
+                        },
+                        "role": { "enum": ["assistant", "user"], "type": "string" },
+                    },
+                    "required": ["content", "role"],
+                    "type": "object",
+                },
+                "type": "array",
+            },
+        },
+        "required": ["messages"],
+        "type": "object",
+    })
+}
+
+/// A prompt described as an operation that takes its arguments and returns
+/// the rendered messages
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptManifestOperation {
+    /// JSON Schema of the arguments object, see
+    /// `PromptDescription::arguments_schema`
+    pub arguments_schema: Value,
+    pub description: String,
+    /// The prompt name
+    pub operation_id: String,
+    /// JSON Schema of the MCP `prompts/get` result
+    pub response_schema: Value,
+    pub summary: String,
+    /// Collections the prompt belongs to, the primary one first
+    pub tags: Vec<String>,
+}
+
+impl From<PromptDescription> for PromptManifestOperation {
+    fn from(prompt_description: PromptDescription) -> Self {
+        let arguments_schema = prompt_description.arguments_schema();
+        let PromptDescription {
+            collections,
+            description,
+            name,
+            title,
+            ..
+        } = prompt_description;
+
+        Self {
+            arguments_schema,
+            description,
+            operation_id: name,
+            response_schema: response_schema(),
+            summary: title,
+            tags: collections,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use indoc::indoc;
+
+    use super::*;
+    use crate::mcp::prompt_controller::PromptController as _;
+    use crate::prompt_document_controller::PromptDocumentController;
+
+    #[test]
+    fn test_argument_example_is_listed_in_its_schema() -> Result<()> {
+        let operation = PromptManifestOperation::from(
+            PromptDocumentController::mock(
+                "translate",
+                indoc! {r#"
+                +++
+                description = "Translates a sentence"
+                title = "Translate"
+
+                [arguments.language]
+                description = "Target language"
+                example = "Polish"
+                required = true
+                title = "Language"
+                +++
+
+                **user**: Translate to {context.arguments.language.input}
+                "#},
+            )?
+            .describe(),
+        );
+
+        assert_eq!(
+            operation.arguments_schema["properties"]["language"]["examples"],
+            json!(["Polish"])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_typed_default_is_described_as_text() -> Result<()> {
+        let operation = PromptManifestOperation::from(
+            PromptDocumentController::mock(
+                "summarize",
+                indoc! {r#"
+                +++
+                description = "Summarizes a text"
+                title = "Summarize"
+
+                [arguments.sentences]
+                default = 3
+                description = "Length of the summary"
+                kind = "integer"
+                required = false
+                title = "Sentences"
+                +++
+
+                **user**: Summarize in {context.arguments.sentences.input} sentences
+                "#},
+            )?
+            .describe(),
+        );
+
+        assert_eq!(
+            operation.arguments_schema["properties"]["sentences"],
+            json!({
+                "default": "3",
+                "description": "Length of the summary",
+                "title": "Sentences",
+                "type": "string",
+                "x-poet-kind": "integer",
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use anyhow::anyhow;
+use markdown::CompileOptions;
+use markdown::Options;
+use markdown::ParseOptions;
+use markdown::to_html_with_options;
+use rhai_components::escape_html_attribute::escape_html_attribute;
+
+use crate::mcp::content_block::ContentBlock;
+use crate::mcp::content_block::audio_content::AudioContent;
+use crate::mcp::content_block::image_content::ImageContent;
+use crate::mcp::content_block::resource_link::ResourceLink;
+use crate::mcp::content_block::text_content::TextContent;
+use crate::mcp::jsonrpc::role::Role;
+use crate::mcp::prompt_message::PromptMessage;
+
+/// Raw HTML is escaped and dangerous link protocols (like `javascript:`) are
+/// dropped, so message content cannot inject anything into the preview
+fn markdown_to_safe_html(markdown: &str) -> Result<String> {
+    to_html_with_options(
+        markdown,
+        &Options {
+            compile: CompileOptions {
+                allow_dangerous_html: false,
+                allow_dangerous_protocol: false,
+                ..CompileOptions::gfm()
+            },
+            parse: ParseOptions::gfm(),
+        },
+    )
+    .map_err(|message| anyhow!("Failed to render message as HTML: {message:?}"))
+}
+
+fn role_header(role: &Role) -> &'static str {
+    match role {
+        Role::Assistant => "Assistant",
+        Role::User => "User",
+    }
+}
+
+/// Renders messages for a browser preview, each in its own section with a
+/// header naming the role. This is not part of the MCP output.
+pub fn prompt_messages_to_html(prompt_messages: &[PromptMessage]) -> Result<String> {
+    let mut html = String::new();
+
+    for PromptMessage { content, role } in prompt_messages {
+        let content_html = match content {
+            ContentBlock::AudioContent(AudioContent { data, mime_type }) => format!(
+                "<p><audio controls src=\"data:{};base64,{}\"></audio></p>",
+                escape_html_attribute(mime_type),
+                escape_html_attribute(data)
+            ),
+            ContentBlock::EmbeddedResource(_) => "<p><em>Embedded resource</em></p>".to_string(),
+            ContentBlock::ImageContent(ImageContent { data, mime_type }) => format!(
+                "<p><img src=\"data:{};base64,{}\" alt=\"\"></p>",
+                escape_html_attribute(mime_type),
+                escape_html_attribute(data)
+            ),
+            ContentBlock::ResourceLink(ResourceLink { name, uri, .. }) => {
+                markdown_to_safe_html(&format!("[{name}](<{uri}>)"))?
+            }
+            ContentBlock::TextContent(TextContent { text }) => markdown_to_safe_html(text)?,
+        };
+
+        html.push_str(&format!(
+            "<section class=\"prompt-message\" data-role=\"{}\">\n<h2>{}</h2>\n{content_html}\n</section>\n",
+            role_header(role).to_lowercase(),
+            role_header(role)
+        ));
+    }
+
+    Ok(html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_and_emphasis_are_rendered_safely() -> Result<()> {
+        let html = prompt_messages_to_html(&[PromptMessage {
+            content: "Read *the* [docs](https://example.com/docs) <script>alert(1)</script> [bad](javascript:alert(1))".into(),
+            role: Role::User,
+        }])?;
+
+        assert_eq!(
+            html,
+            "<section class=\"prompt-message\" data-role=\"user\">\n<h2>User</h2>\n<p>Read <em>the</em> <a href=\"https://example.com/docs\">docs</a> &lt;script&gt;alert(1)&lt;/script&gt; <a href=\"\">bad</a></p>\n</section>\n"
+        );
+
+        Ok(())
+    }
+}
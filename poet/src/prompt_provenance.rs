@@ -0,0 +1,15 @@
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Traces a served prompt back to the file and the build it came from
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PromptProvenance {
+    #[serde(rename = "builtAt")]
+    pub built_at: DateTime<Utc>,
+    #[serde(rename = "poetVersion")]
+    pub poet_version: String,
+    #[serde(rename = "sourcePath")]
+    pub source_path: String,
+}
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use rand::Rng as _;
+use rand::SeedableRng as _;
+use rand::seq::SliceRandom as _;
+use rand_chacha::ChaCha8Rng;
+
+/// Source of randomness for a single render. Renders with the same seed
+/// produce the same output, so they stay reproducible and cacheable.
+#[derive(Clone)]
+pub struct PromptRandom {
+    rng: Arc<Mutex<ChaCha8Rng>>,
+}
+
+impl PromptRandom {
+    /// Falls back to entropy when there is no seed
+    pub fn new(seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_rng(&mut rand::rng()),
+        };
+
+        Self {
+            rng: Arc::new(Mutex::new(rng)),
+        }
+    }
+
+    /// Number in `0..upper_bound`
+    pub fn below(&self, upper_bound: i64) -> Result<i64> {
+        if upper_bound <= 0 {
+            return Err(anyhow!(
+                "random() needs an upper bound above 0, got {upper_bound}"
+            ));
+        }
+
+        Ok(self
+            .rng
+            .lock()
+            .expect("Random number generator lock is poisoned")
+            .random_range(0..upper_bound))
+    }
+
+    pub fn shuffle<TItem>(&self, items: &mut [TItem]) {
+        items.shuffle(
+            &mut *self
+                .rng
+                .lock()
+                .expect("Random number generator lock is poisoned"),
+        );
+    }
+}
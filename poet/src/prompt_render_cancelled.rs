@@ -0,0 +1,21 @@
+use std::error::Error;
+use std::fmt;
+
+/// Rendering stopped because the client cancelled the request, so nobody is
+/// waiting for the result
+#[derive(Debug)]
+pub struct PromptRenderCancelled {
+    pub prompt_name: String,
+}
+
+impl Error for PromptRenderCancelled {}
+
+impl fmt::Display for PromptRenderCancelled {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "Rendering of prompt '{}' was cancelled",
+            self.prompt_name
+        )
+    }
+}
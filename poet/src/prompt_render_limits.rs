@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::mcp::content_block::ContentBlock;
+use crate::mcp::content_block::text_content::TextContent;
+use crate::mcp::prompt_message::PromptMessage;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PromptRenderLimits {
+    #[serde(default)]
+    pub max_message_bytes: Option<usize>,
+    #[serde(default)]
+    pub max_messages: Option<usize>,
+    #[serde(default)]
+    pub render_deadline_ms: Option<u64>,
+}
+
+impl PromptRenderLimits {
+    pub fn assert_message_fits(&self, prompt_message: &PromptMessage) -> Result<()> {
+        if let Some(max_message_bytes) = self.max_message_bytes
+            && let ContentBlock::TextContent(TextContent { text }) = &prompt_message.content
+            && text.len() > max_message_bytes
+        {
+            return Err(anyhow!(
+                "Prompt message is {} bytes long, which exceeds the limit of {max_message_bytes} bytes",
+                text.len()
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn assert_rendered_within_deadline(&self, elapsed: Duration) -> Result<()> {
+        if let Some(render_deadline_ms) = self.render_deadline_ms
+            && elapsed > Duration::from_millis(render_deadline_ms)
+        {
+            return Err(anyhow!(
+                "Prompt took {}ms to render, which exceeds the deadline of {render_deadline_ms}ms",
+                elapsed.as_millis()
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+use anyhow::anyhow;
+
+use crate::prompt_render_limits::PromptRenderLimits;
+
+/// Without a configured ceiling, prompts can only lower the default, so
+/// a prompt never lifts a limit that the server set
+fn resolve_limit<TLimit>(
+    name: &str,
+    default: Option<TLimit>,
+    ceiling: Option<TLimit>,
+    prompt_override: Option<TLimit>,
+) -> Result<Option<TLimit>>
+where
+    TLimit: Copy + Display + PartialOrd,
+{
+    match (prompt_override, ceiling.or(default)) {
+        (Some(prompt_override), Some(ceiling)) if prompt_override > ceiling => Err(anyhow!(
+            "Prompt overrides '{name}' with {prompt_override}, which exceeds the hard ceiling of {ceiling}"
+        )),
+        (Some(prompt_override), _) => Ok(Some(prompt_override)),
+        (None, _) => Ok(default),
+    }
+}
+
+/// Limits applied to every prompt, and the hard ceilings that prompts can
+/// raise them up to in their front matter
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PromptRenderLimitsPolicy {
+    pub ceiling: PromptRenderLimits,
+    pub defaults: PromptRenderLimits,
+}
+
+impl PromptRenderLimitsPolicy {
+    pub fn resolve(&self, prompt_overrides: &PromptRenderLimits) -> Result<PromptRenderLimits> {
+        Ok(PromptRenderLimits {
+            max_message_bytes: resolve_limit(
+                "max_message_bytes",
+                self.defaults.max_message_bytes,
+                self.ceiling.max_message_bytes,
+                prompt_overrides.max_message_bytes,
+            )?,
+            max_messages: resolve_limit(
+                "max_messages",
+                self.defaults.max_messages,
+                self.ceiling.max_messages,
+                prompt_overrides.max_messages,
+            )?,
+            render_deadline_ms: resolve_limit(
+                "render_deadline_ms",
+                self.defaults.render_deadline_ms,
+                self.ceiling.render_deadline_ms,
+                prompt_overrides.render_deadline_ms,
+            )?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> PromptRenderLimitsPolicy {
+        PromptRenderLimitsPolicy {
+            ceiling: PromptRenderLimits {
+                max_message_bytes: Some(1024),
+                max_messages: Some(100),
+                render_deadline_ms: Some(5000),
+            },
+            defaults: PromptRenderLimits {
+                max_message_bytes: Some(128),
+                max_messages: Some(20),
+                render_deadline_ms: Some(500),
+            },
+        }
+    }
+
+    #[test]
+    fn test_defaults_are_used_without_overrides() -> Result<()> {
+        assert_eq!(policy().resolve(&Default::default())?, policy().defaults);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_override_up_to_ceiling() -> Result<()> {
+        let limits = policy().resolve(&PromptRenderLimits {
+            max_message_bytes: Some(1024),
+            max_messages: Some(100),
+            render_deadline_ms: Some(5000),
+        })?;
+
+        assert_eq!(limits.max_message_bytes, Some(1024));
+        assert_eq!(limits.max_messages, Some(100));
+        assert_eq!(limits.render_deadline_ms, Some(5000));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_override_above_ceiling_fails() {
+        assert!(
+            policy()
+                .resolve(&PromptRenderLimits {
+                    max_message_bytes: Some(1025),
+                    max_messages: None,
+                    render_deadline_ms: None,
+                })
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_override_without_ceiling_is_capped_by_default() {
+        let policy = PromptRenderLimitsPolicy {
+            ceiling: Default::default(),
+            ..policy()
+        };
+
+        assert!(
+            policy
+                .resolve(&PromptRenderLimits {
+                    max_message_bytes: None,
+                    max_messages: None,
+                    render_deadline_ms: Some(501),
+                })
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use clap::ValueEnum;
+
+use crate::mcp::content_block::ContentBlock;
+use crate::mcp::content_block::text_content::TextContent;
+use crate::mcp::prompt_message::PromptMessage;
+use crate::normalize_smart_punctuation::normalize_smart_punctuation;
+use crate::prompt_document_front_matter::PromptDocumentFrontMatter;
+use crate::prompt_line_ending::PromptLineEnding;
+
+/// Transformation applied to the messages after the prompt is rendered
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum PromptRenderPass {
+    /// Drop every message without content
+    DropEmptyMessages,
+    /// Join adjacent text messages of the same role into a single message
+    MergeConsecutiveRoles,
+    /// Rewrite every line ending in text messages to `\r\n`
+    NormalizeCrlfLineEndings,
+    /// Rewrite every line ending in text messages to `\n`
+    NormalizeLfLineEndings,
+    /// Replace typographic quotes and dashes outside of code with ASCII, in
+    /// prompts that set `normalize_punctuation`
+    NormalizeSmartPunctuation,
+    /// Drop messages without content from the start and the end
+    TrimEmptyMessages,
+}
+
+impl PromptRenderPass {
+    pub fn apply(&self, prompt_messages: Vec<PromptMessage>) -> Vec<PromptMessage> {
+        match self {
+            PromptRenderPass::DropEmptyMessages => prompt_messages
+                .into_iter()
+                .filter(|prompt_message| !prompt_message.content.is_empty_text())
+                .collect(),
+            PromptRenderPass::MergeConsecutiveRoles => {
+                let mut merged_messages: Vec<PromptMessage> = Vec::new();
+
+                for prompt_message in prompt_messages {
+                    if let Some(PromptMessage {
+                        content: ContentBlock::TextContent(TextContent { text }),
+                        role,
+                    }) = merged_messages.last_mut()
+                        && let ContentBlock::TextContent(TextContent { text: next_text }) =
+                            &prompt_message.content
+                        && *role == prompt_message.role
+                    {
+                        text.push_str("\n\n");
+                        text.push_str(next_text);
+
+                        continue;
+                    }
+
+                    merged_messages.push(prompt_message);
+                }
+
+                merged_messages
+            }
+            PromptRenderPass::NormalizeCrlfLineEndings => {
+                PromptLineEnding::Crlf.apply(prompt_messages)
+            }
+            PromptRenderPass::NormalizeLfLineEndings => PromptLineEnding::Lf.apply(prompt_messages),
+            PromptRenderPass::NormalizeSmartPunctuation => prompt_messages
+                .into_iter()
+                .map(|prompt_message| match prompt_message {
+                    PromptMessage {
+                        content: ContentBlock::TextContent(TextContent { text }),
+                        role,
+                    } => PromptMessage {
+                        content: normalize_smart_punctuation(&text).into(),
+                        role,
+                    },
+                    prompt_message => prompt_message,
+                })
+                .collect(),
+            PromptRenderPass::TrimEmptyMessages => {
+                let mut prompt_messages: Vec<PromptMessage> = prompt_messages
+                    .into_iter()
+                    .skip_while(|prompt_message| prompt_message.content.is_empty_text())
+                    .collect();
+
+                while prompt_messages
+                    .last()
+                    .is_some_and(|prompt_message| prompt_message.content.is_empty_text())
+                {
+                    prompt_messages.pop();
+                }
+
+                prompt_messages
+            }
+        }
+    }
+
+    /// Some passes only run for prompts that opt in, or that did not opt out,
+    /// in their front matter
+    pub fn applies_to(&self, front_matter: &PromptDocumentFrontMatter) -> bool {
+        match self {
+            PromptRenderPass::DropEmptyMessages | PromptRenderPass::TrimEmptyMessages => {
+                !front_matter.keep_empty_messages
+            }
+            PromptRenderPass::NormalizeSmartPunctuation => front_matter.normalize_punctuation,
+            _ => true,
+        }
+    }
+}
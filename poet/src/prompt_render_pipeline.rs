@@ -0,0 +1,136 @@
+use crate::mcp::prompt_message::PromptMessage;
+use crate::prompt_document_front_matter::PromptDocumentFrontMatter;
+use crate::prompt_render_pass::PromptRenderPass;
+use crate::prompt_system_role_lowering::PromptSystemRoleLowering;
+
+/// Ordered passes over the rendered messages, configurable per build
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PromptRenderPipeline {
+    pub passes: Vec<PromptRenderPass>,
+    /// Applied while the document is evaluated, since only then it is
+    /// known where system blocks are
+    pub system_role_lowering: PromptSystemRoleLowering,
+}
+
+impl PromptRenderPipeline {
+    pub fn apply(
+        &self,
+        mut prompt_messages: Vec<PromptMessage>,
+        front_matter: &PromptDocumentFrontMatter,
+    ) -> Vec<PromptMessage> {
+        for pass in &self.passes {
+            if pass.applies_to(front_matter) {
+                prompt_messages = pass.apply(prompt_messages);
+            }
+        }
+
+        prompt_messages
+    }
+}
+
+impl Default for PromptRenderPipeline {
+    fn default() -> Self {
+        Self {
+            passes: vec![
+                PromptRenderPass::NormalizeSmartPunctuation,
+                PromptRenderPass::TrimEmptyMessages,
+            ],
+            system_role_lowering: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::mcp::jsonrpc::role::Role;
+
+    fn front_matter() -> Result<PromptDocumentFrontMatter> {
+        Ok(toml::from_str(
+            "description = \"pipeline\"\ntitle = \"Pipeline\"\n[arguments]",
+        )?)
+    }
+
+    fn render_with(passes: Vec<PromptRenderPass>) -> Result<Vec<PromptMessage>> {
+        Ok(PromptRenderPipeline {
+            passes,
+            system_role_lowering: Default::default(),
+        }
+        .apply(
+            vec![
+                PromptMessage {
+                    content: "First question".into(),
+                    role: Role::User,
+                },
+                PromptMessage {
+                    content: "".into(),
+                    role: Role::Assistant,
+                },
+                PromptMessage {
+                    content: "Second question".into(),
+                    role: Role::User,
+                },
+            ],
+            &front_matter()?,
+        ))
+    }
+
+    #[test]
+    fn test_passes_run_in_configured_order() -> Result<()> {
+        let dropped_then_merged = render_with(vec![
+            PromptRenderPass::DropEmptyMessages,
+            PromptRenderPass::MergeConsecutiveRoles,
+        ])?;
+
+        assert_eq!(dropped_then_merged.len(), 1);
+        assert_eq!(
+            dropped_then_merged[0].content,
+            "First question\n\nSecond question".into()
+        );
+
+        let merged_then_dropped = render_with(vec![
+            PromptRenderPass::MergeConsecutiveRoles,
+            PromptRenderPass::DropEmptyMessages,
+        ])?;
+
+        assert_eq!(merged_then_dropped.len(), 2);
+        assert_eq!(merged_then_dropped[0].content, "First question".into());
+        assert_eq!(merged_then_dropped[1].content, "Second question".into());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_ending_is_normalized_after_merging() -> Result<()> {
+        let prompt_messages = PromptRenderPipeline {
+            passes: vec![
+                PromptRenderPass::MergeConsecutiveRoles,
+                PromptRenderPass::NormalizeLfLineEndings,
+            ],
+            system_role_lowering: Default::default(),
+        }
+        .apply(
+            vec![
+                PromptMessage {
+                    content: "First line\r\nSecond line".into(),
+                    role: Role::User,
+                },
+                PromptMessage {
+                    content: "```\r\ncode\r\n```\n".into(),
+                    role: Role::User,
+                },
+            ],
+            &front_matter()?,
+        );
+
+        assert_eq!(prompt_messages.len(), 1);
+        assert_eq!(
+            prompt_messages[0].content,
+            "First line\nSecond line\n\n```\ncode\n```\n".into()
+        );
+
+        Ok(())
+    }
+}
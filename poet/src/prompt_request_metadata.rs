@@ -0,0 +1,39 @@
+use rhai::Dynamic;
+use rhai::INT;
+use rhai::Map;
+
+use crate::mcp::jsonrpc::id::Id;
+use crate::mcp::jsonrpc::request::prompts_get::PromptsGet;
+
+/// Metadata about the request being rendered, exposed to templates as
+/// `context.request`. Details that are not known are left out of the map.
+pub fn prompt_request_metadata(
+    PromptsGet {
+        client_info, id, ..
+    }: &PromptsGet,
+) -> Map {
+    let mut request_metadata = Map::new();
+
+    request_metadata.insert(
+        "id".into(),
+        match id {
+            Id::Number(number) => Dynamic::from(*number as INT),
+            Id::String(string) => Dynamic::from(string.clone()),
+        },
+    );
+
+    if let Some(client_info) = client_info {
+        let mut client = Map::new();
+
+        client.insert("name".into(), client_info.name.clone().into());
+        client.insert("version".into(), client_info.version.clone().into());
+
+        if let Some(title) = &client_info.title {
+            client.insert("title".into(), title.clone().into());
+        }
+
+        request_metadata.insert("client".into(), client.into());
+    }
+
+    request_metadata
+}
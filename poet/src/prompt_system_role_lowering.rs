@@ -0,0 +1,12 @@
+use clap::ValueEnum;
+
+/// MCP prompt messages can only come from the user or the assistant, so
+/// system blocks are rewritten before the prompt is sent
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum PromptSystemRoleLowering {
+    /// Prepend system blocks to the leading user message
+    #[default]
+    MergeIntoFirstUserMessage,
+    /// Keep system blocks in place, as user messages with a prefix
+    SeparateUserMessage,
+}
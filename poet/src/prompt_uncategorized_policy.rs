@@ -0,0 +1,12 @@
+use clap::ValueEnum;
+
+/// Decides what happens with prompts that do not belong to any collection
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum PromptUncategorizedPolicy {
+    #[default]
+    Allow,
+    /// Fail the build
+    Deny,
+    /// Log a warning
+    Warn,
+}
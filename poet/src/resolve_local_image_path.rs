@@ -0,0 +1,71 @@
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use percent_encoding::percent_decode_str;
+
+/// Resolves an image url relative to the document that refers to it. The
+/// result never leaves the project files.
+pub fn resolve_local_image_path(document_path: &Path, url: &str) -> Result<PathBuf> {
+    let decoded_url = percent_decode_str(url).decode_utf8()?;
+    let mut resolved_path = document_path
+        .parent()
+        .unwrap_or(Path::new(""))
+        .to_path_buf();
+
+    for component in Path::new(decoded_url.as_ref()).components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(segment) => resolved_path.push(segment),
+            Component::ParentDir | Component::Prefix(_) | Component::RootDir => {
+                return Err(anyhow!(
+                    "Image '{url}' has to be a relative path that stays within the document directory"
+                ));
+            }
+        }
+    }
+
+    Ok(resolved_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolves_relative_to_the_document() -> Result<()> {
+        assert_eq!(
+            resolve_local_image_path(Path::new("prompts/guides/intro.md"), "./images/logo.png")?,
+            PathBuf::from("prompts/guides/images/logo.png")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decodes_percent_encoded_urls() -> Result<()> {
+        assert_eq!(
+            resolve_local_image_path(Path::new("prompts/intro.md"), "my%20logo.png")?,
+            PathBuf::from("prompts/my logo.png")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_parent_directories() {
+        assert!(resolve_local_image_path(Path::new("prompts/intro.md"), "../secret.png").is_err());
+        assert!(
+            resolve_local_image_path(Path::new("prompts/intro.md"), "%2E%2E/secret.png").is_err()
+        );
+    }
+
+    #[test]
+    fn test_rejects_absolute_paths() {
+        assert!(
+            resolve_local_image_path(Path::new("prompts/intro.md"), "/etc/secret.png").is_err()
+        );
+    }
+}
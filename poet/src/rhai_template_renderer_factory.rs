@@ -1,12 +1,18 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use anyhow::Context as _;
 use anyhow::Result;
 use rhai::Engine;
+use rhai::Module;
+use rhai::Scope;
 use rhai::module_resolvers::FileModuleResolver;
+use rhai::module_resolvers::ModuleResolversCollection;
+use rhai::module_resolvers::StaticModuleResolver;
 use rhai_components::builds_engine::BuildsEngine;
 use rhai_components::component_syntax::component_reference::ComponentReference;
 use rhai_components::component_syntax::component_registry::ComponentRegistry;
+use rhai_components::component_syntax::tag_name::TagName;
 use rhai_components::rhai_template_renderer::RhaiTemplateRenderer;
 use rhai_components::rhai_template_renderer_params::RhaiTemplateRendererParams;
 
@@ -14,13 +20,16 @@ use crate::asset_manager::AssetManager;
 use crate::author::Author;
 use crate::author_collection::AuthorCollection;
 use crate::author_data::AuthorData;
+use crate::component_schema::ComponentSchema;
 use crate::content_document_collection_ranked::ContentDocumentCollectionRanked;
 use crate::content_document_component_context::ContentDocumentComponentContext;
 use crate::content_document_front_matter::ContentDocumentFrontMatter;
 use crate::content_document_hierarchy::ContentDocumentHierarchy;
 use crate::content_document_reference::ContentDocumentReference;
 use crate::content_document_tree_node::ContentDocumentTreeNode;
+use crate::fence_code_block::fence_code_block;
 use crate::filesystem::file_entry::FileEntry;
+use crate::prompt_builtin_components::PROMPT_BUILTIN_COMPONENTS;
 use crate::prompt_document_component_context::PromptDocumentComponentContext;
 use crate::prompt_document_front_matter::PromptDocumentFrontMatter;
 use crate::prompt_document_front_matter::argument_with_input::ArgumentWithInput;
@@ -43,14 +52,39 @@ impl RhaiTemplateRendererFactory {
         }
     }
 
-    pub fn register_component_file(&self, file_entry: FileEntry) {
-        let component_name = file_entry.get_stem_relative_to(&self.shortcodes_subdirectory);
+    pub fn register_component_file(&self, file_entry: FileEntry) -> Result<()> {
+        let module_path = file_entry.get_stem_relative_to(&self.shortcodes_subdirectory);
+
+        self.register_component_source(
+            TagName::from_module_path(&module_path).name,
+            module_path,
+            &file_entry.contents,
+        )
+    }
+
+    /// Built-ins are registered last, so shortcodes with the same name keep
+    /// their place
+    fn register_builtin_components(&self) -> Result<()> {
+        for (name, source) in PROMPT_BUILTIN_COMPONENTS {
+            if self.component_registry.get(name).is_none() {
+                self.register_component_source(name.to_string(), name.to_string(), source)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn register_component_source(&self, name: String, path: String, source: &str) -> Result<()> {
+        let ComponentSchema { description, props } = ComponentSchema::from_rhai_source(source)
+            .with_context(|| format!("Component '{path}' has an invalid schema"))?;
 
         self.component_registry
             .register_component(ComponentReference {
-                name: component_name.clone(),
-                path: component_name,
-            });
+                description,
+                name,
+                path,
+                props,
+            })
     }
 }
 
@@ -60,10 +94,6 @@ impl BuildsEngine for RhaiTemplateRendererFactory {
     }
 
     fn prepare_engine(&self, engine: &mut Engine) -> Result<()> {
-        engine.set_module_resolver(FileModuleResolver::new_with_path(
-            self.base_directory.join(&self.shortcodes_subdirectory),
-        ));
-
         engine.build_type::<ArgumentWithInput>();
         engine.build_type::<AssetManager>();
         engine.build_type::<Author>();
@@ -81,8 +111,35 @@ impl BuildsEngine for RhaiTemplateRendererFactory {
         engine.build_type::<PromptDocumentFrontMatter>();
         engine.build_type::<TableOfContents>();
 
+        engine.register_fn("code_block", |content: &str, info_string: &str| {
+            fence_code_block(content, info_string, '`')
+        });
+        engine.register_fn(
+            "code_block",
+            |content: &str, info_string: &str, fence_character: char| {
+                fence_code_block(content, info_string, fence_character)
+            },
+        );
         engine.register_fn("render_hierarchy", render_hierarchy);
 
+        let mut builtin_module_resolver = StaticModuleResolver::new();
+
+        for (name, source) in PROMPT_BUILTIN_COMPONENTS {
+            builtin_module_resolver.insert(
+                name,
+                Module::eval_ast_as_new(Scope::new(), &engine.compile(source)?, engine)?,
+            );
+        }
+
+        let mut module_resolvers = ModuleResolversCollection::new();
+
+        module_resolvers.push(FileModuleResolver::new_with_path(
+            self.base_directory.join(&self.shortcodes_subdirectory),
+        ));
+        module_resolvers.push(builtin_module_resolver);
+
+        engine.set_module_resolver(module_resolvers);
+
         Ok(())
     }
 }
@@ -91,6 +148,8 @@ impl TryInto<RhaiTemplateRenderer> for RhaiTemplateRendererFactory {
     type Error = anyhow::Error;
 
     fn try_into(self) -> Result<RhaiTemplateRenderer, Self::Error> {
+        self.register_builtin_components()?;
+
         let expression_engine = self.create_engine()?;
 
         RhaiTemplateRenderer::build(RhaiTemplateRendererParams {
@@ -96,6 +96,7 @@ impl SearchIndex {
 
 #[cfg(test)]
 mod tests {
+
     use super::*;
     use crate::asset_path_renderer::AssetPathRenderer;
     use crate::build_authors::build_authors;
@@ -103,13 +104,17 @@ mod tests {
     use crate::build_project::build_project_params::BuildProjectParams;
     use crate::build_project::build_project_result_stub::BuildProjectResultStub;
     use crate::compile_shortcodes::compile_shortcodes;
+    use crate::filesystem::storage::DEFAULT_MAX_FILE_BYTES;
     use crate::filesystem::storage::Storage;
+    use crate::prompt_discovery::PromptDiscovery;
     use crate::search_index_query_params::SearchIndexQueryParams;
 
     async fn do_build_project() -> Result<BuildProjectResultStub> {
         let public_path: String = "https://example.com".to_string();
         let source_filesystem = Arc::new(Storage {
             base_directory: env!("CARGO_MANIFEST_DIR").into(),
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            source_encodings: Default::default(),
         });
         let rhai_template_renderer = compile_shortcodes(source_filesystem.clone()).await?;
         let authors = build_authors(source_filesystem.clone()).await?;
@@ -123,8 +128,10 @@ mod tests {
             generated_page_base_path: public_path,
             generate_sitemap: false,
             is_watching: false,
+            prompt_discovery: PromptDiscovery::new(&["prompts/**/*.md".to_string()], &[])?,
             rhai_template_renderer,
             source_filesystem,
+            unknown_fields_policy: Default::default(),
         })
         .await
     }
@@ -0,0 +1,6 @@
+/// Mime type and data of a base64 data URI
+pub fn split_base64_data_uri(url: &str) -> Option<(&str, &str)> {
+    url.strip_prefix("data:")
+        .and_then(|data_uri| data_uri.split_once(','))
+        .and_then(|(header, data)| Some((header.strip_suffix(";base64")?, data)))
+}
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use markdown::mdast::Heading;
+use markdown::mdast::Node;
+use markdown::mdast::Root;
+
+use crate::find_text_content_in_mdast::find_text_content_in_mdast;
+
+/// Removes the `#` heading the document starts with, since it only serves as
+/// the prompt title, and returns its text
+pub fn take_title_heading_from_mdast(mdast: &mut Node) -> Result<Option<String>> {
+    let Node::Root(Root { children, .. }) = mdast else {
+        return Ok(None);
+    };
+
+    let Some(index) = children
+        .iter()
+        .position(|child| !matches!(child, Node::Toml(_) | Node::Yaml(_)))
+    else {
+        return Ok(None);
+    };
+
+    if !matches!(children[index], Node::Heading(Heading { depth: 1, .. })) {
+        return Ok(None);
+    }
+
+    let title = find_text_content_in_mdast(&children.remove(index))?;
+
+    if title.trim().is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(title.trim().to_string()))
+}
@@ -0,0 +1,5 @@
+/// Counts tokens for budgeting. Implementations can wrap the tokenizer of a
+/// particular model when the heuristic is not precise enough.
+pub trait TokenCounter: Send + Sync {
+    fn count_tokens(&self, text: &str) -> usize;
+}
@@ -0,0 +1,23 @@
+use crate::token_counter::TokenCounter;
+
+/// Rough token count, assuming about four characters per token. It does not
+/// match any particular tokenizer.
+pub struct TokenCounterHeuristic;
+
+impl TokenCounter for TokenCounterHeuristic {
+    fn count_tokens(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_tokens_round_up() {
+        assert_eq!(TokenCounterHeuristic.count_tokens(""), 0);
+        assert_eq!(TokenCounterHeuristic.count_tokens("abcd"), 1);
+        assert_eq!(TokenCounterHeuristic.count_tokens("abcde"), 2);
+    }
+}
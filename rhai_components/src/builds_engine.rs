@@ -44,23 +44,18 @@ pub trait BuildsEngine {
 
         let templates: DashMap<String, ComponentReference> = DashMap::new();
 
-        for entry in &self.component_registry().components {
-            let component_reference = entry.value();
-
+        for component_reference in self.component_registry().component_references() {
             let module_resolver = engine.module_resolver();
             let module = module_resolver.resolve(
                 &engine,
                 None,
-                &component_reference.name,
+                &component_reference.path,
                 Position::NONE,
             )?;
 
-            engine.register_static_module(component_reference.name.clone(), module);
+            engine.register_static_module(&component_reference.name, module);
 
-            templates.insert(
-                component_reference.name.clone(),
-                component_reference.clone(),
-            );
+            templates.insert(component_reference.name.clone(), component_reference);
         }
 
         Ok(engine)
@@ -0,0 +1,17 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentProp {
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub name: String,
+    /// Usages that do not set the prop fail at build time
+    #[serde(default)]
+    pub required: bool,
+    #[serde(rename = "type")]
+    pub type_name: String,
+}
@@ -1,5 +1,12 @@
-#[derive(Clone)]
+use serde::Serialize;
+
+use super::component_prop::ComponentProp;
+
+#[derive(Clone, Serialize)]
 pub struct ComponentReference {
+    pub description: Option<String>,
     pub name: String,
+    #[serde(skip)]
     pub path: String,
+    pub props: Vec<ComponentProp>,
 }
@@ -1,22 +1,202 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use anyhow::anyhow;
 use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use serde_json::Value;
 
 use super::component_reference::ComponentReference;
 
 pub struct ComponentRegistry {
+    /// Registry whose components are used unless this one shadows them by name
+    pub base: Option<Arc<ComponentRegistry>>,
+    /// Components keyed by their full name, namespace included
     pub components: DashMap<String, ComponentReference>,
 }
 
 impl ComponentRegistry {
-    pub fn register_component(&self, component_reference: ComponentReference) {
-        self.components
-            .insert(component_reference.name.clone(), component_reference);
+    pub fn layered_over(base: Arc<ComponentRegistry>) -> Self {
+        Self {
+            base: Some(base),
+            components: DashMap::new(),
+        }
+    }
+
+    /// Components from this registry and its bases, sorted by name
+    pub fn component_references(&self) -> Vec<ComponentReference> {
+        let mut component_references: BTreeMap<String, ComponentReference> = match &self.base {
+            Some(base) => base
+                .component_references()
+                .into_iter()
+                .map(|component_reference| (component_reference.name.clone(), component_reference))
+                .collect(),
+            None => BTreeMap::new(),
+        };
+
+        for entry in &self.components {
+            component_references.insert(entry.key().clone(), entry.value().clone());
+        }
+
+        component_references.into_values().collect()
+    }
+
+    /// Lists registered components with their props, sorted by name, so
+    /// docs generators can build a component reference page
+    pub fn export_documentation(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self.component_references())?)
+    }
+
+    pub fn get(&self, name: &str) -> Option<ComponentReference> {
+        match self.components.get(name) {
+            Some(component_reference) => Some(component_reference.value().clone()),
+            None => self.base.as_ref().and_then(|base| base.get(name)),
+        }
+    }
+
+    /// Module path that the component's template function is resolved from
+    pub fn get_path(&self, name: &str) -> Option<String> {
+        self.get(name)
+            .map(|component_reference| component_reference.path)
+    }
+
+    /// Components can shadow the ones from base registries, but two with the
+    /// same name in one registry would leave one of them unreachable
+    pub fn register_component(&self, component_reference: ComponentReference) -> Result<()> {
+        match self.components.entry(component_reference.name.clone()) {
+            Entry::Occupied(registered) => Err(anyhow!(
+                "Component '{}' from '{}' collides with the one from '{}'",
+                component_reference.name,
+                component_reference.path,
+                registered.get().path
+            )),
+            Entry::Vacant(vacant) => {
+                vacant.insert(component_reference);
+
+                Ok(())
+            }
+        }
     }
 }
 
 impl Default for ComponentRegistry {
     fn default() -> Self {
         Self {
+            base: None,
             components: DashMap::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::component_syntax::component_prop::ComponentProp;
+
+    #[test]
+    fn test_export_documentation() -> Result<()> {
+        let component_registry = ComponentRegistry::default();
+
+        component_registry.register_component(ComponentReference {
+            description: Some("Highlighted side note".to_string()),
+            name: "Note".to_string(),
+            path: "Note".to_string(),
+            props: vec![ComponentProp {
+                default: Some("info".to_string()),
+                description: Some("Visual style of the note".to_string()),
+                name: "variant".to_string(),
+                required: false,
+                type_name: "string".to_string(),
+            }],
+        })?;
+
+        assert_eq!(
+            component_registry.export_documentation()?,
+            json!([{
+                "description": "Highlighted side note",
+                "name": "Note",
+                "props": [{
+                    "default": "info",
+                    "description": "Visual style of the note",
+                    "name": "variant",
+                    "required": false,
+                    "type": "string",
+                }],
+            }])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_colliding_names_fail() -> Result<()> {
+        let component_registry = ComponentRegistry::default();
+
+        component_registry.register_component(ComponentReference {
+            description: None,
+            name: "ui:Button".to_string(),
+            path: "ui/Button".to_string(),
+            props: Default::default(),
+        })?;
+
+        let Err(err) = component_registry.register_component(ComponentReference {
+            description: None,
+            name: "ui:Button".to_string(),
+            path: "ui:Button".to_string(),
+            props: Default::default(),
+        }) else {
+            return Err(anyhow!("Colliding component was registered"));
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Component 'ui:Button' from 'ui:Button' collides with the one from 'ui/Button'"
+        );
+        assert_eq!(
+            component_registry.get_path("ui:Button"),
+            Some("ui/Button".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_overlay_shadows_base() -> Result<()> {
+        let base = Arc::new(ComponentRegistry::default());
+
+        for name in ["Button", "Note"] {
+            base.register_component(ComponentReference {
+                description: None,
+                name: name.to_string(),
+                path: format!("base/{name}"),
+                props: Default::default(),
+            })?;
+        }
+
+        let overlay = ComponentRegistry::layered_over(base);
+
+        overlay.register_component(ComponentReference {
+            description: None,
+            name: "Note".to_string(),
+            path: "local/Note".to_string(),
+            props: Default::default(),
+        })?;
+
+        assert_eq!(overlay.get_path("Note"), Some("local/Note".to_string()));
+        assert_eq!(overlay.get_path("Button"), Some("base/Button".to_string()));
+        assert_eq!(overlay.get_path("Missing"), None);
+        assert_eq!(
+            overlay
+                .component_references()
+                .into_iter()
+                .map(|component_reference| component_reference.path)
+                .collect::<Vec<_>>(),
+            vec!["base/Button".to_string(), "local/Note".to_string()]
+        );
+
+        Ok(())
+    }
+}
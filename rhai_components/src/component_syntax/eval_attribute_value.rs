@@ -0,0 +1,21 @@
+use rhai::Dynamic;
+use rhai::EvalAltResult;
+use rhai::EvalContext;
+
+use super::attribute::Attribute;
+use super::attribute_value::AttributeValue;
+use super::expression_collection::ExpressionCollection;
+
+pub fn eval_attribute_value(
+    eval_context: &mut EvalContext,
+    expression_collection: &mut ExpressionCollection,
+    attribute: &Attribute,
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    Ok(match &attribute.value {
+        Some(AttributeValue::Expression(expression_reference)) => {
+            expression_collection.eval_expression(eval_context, expression_reference)?
+        }
+        Some(AttributeValue::Text(text)) => text.into(),
+        None => true.into(),
+    })
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use rhai::EvalAltResult;
+use rhai::EvalContext;
+use rhai::Position;
+
+use super::component_registry::ComponentRegistry;
+use super::eval_attribute_value::eval_attribute_value;
+use super::eval_tag_stack_node::eval_tag_stack_node;
+use super::expression_collection::ExpressionCollection;
+use super::tag::Tag;
+use super::tag_stack_node::TagStackNode;
+
+fn eval_attribute_string(
+    eval_context: &mut EvalContext,
+    expression_collection: &mut ExpressionCollection,
+    tag: &Tag,
+    attribute_name: &str,
+) -> Result<String, Box<EvalAltResult>> {
+    match tag
+        .attributes
+        .iter()
+        .find(|attribute| attribute.name == attribute_name)
+    {
+        Some(attribute) => {
+            Ok(eval_attribute_value(eval_context, expression_collection, attribute)?.to_string())
+        }
+        None => Err(EvalAltResult::ErrorRuntime(
+            format!(
+                "<{}> requires the '{attribute_name}' attribute",
+                tag.tag_name.name
+            )
+            .into(),
+            Position::NONE,
+        )
+        .into()),
+    }
+}
+
+fn eval_children(
+    component_registry: Arc<ComponentRegistry>,
+    eval_context: &mut EvalContext,
+    children: &[TagStackNode],
+    expression_collection: &mut ExpressionCollection,
+) -> Result<String, Box<EvalAltResult>> {
+    let mut result = String::new();
+
+    for child in children {
+        result.push_str(&eval_tag_stack_node(
+            component_registry.clone(),
+            eval_context,
+            child,
+            expression_collection,
+        )?);
+    }
+
+    Ok(result)
+}
+
+/// Renders only the first `<Case>` whose `value` matches the `on` attribute
+/// of `<Switch>`, or the `<Default>` branch if none of them match
+pub fn eval_switch(
+    component_registry: Arc<ComponentRegistry>,
+    eval_context: &mut EvalContext,
+    switch_tag: &Tag,
+    children: &[TagStackNode],
+    expression_collection: &mut ExpressionCollection,
+) -> Result<String, Box<EvalAltResult>> {
+    let on = eval_attribute_string(eval_context, expression_collection, switch_tag, "on")?;
+    let mut default_children: Option<&Vec<TagStackNode>> = None;
+
+    for child in children {
+        if let TagStackNode::Tag {
+            children: case_children,
+            opening_tag: Some(opening_tag),
+            ..
+        } = child
+        {
+            match opening_tag.tag_name.name.as_str() {
+                "Case" => {
+                    let value = eval_attribute_string(
+                        eval_context,
+                        expression_collection,
+                        opening_tag,
+                        "value",
+                    )?;
+
+                    if value == on {
+                        return eval_children(
+                            component_registry,
+                            eval_context,
+                            case_children,
+                            expression_collection,
+                        );
+                    }
+                }
+                "Default" => {
+                    default_children = Some(case_children);
+                }
+                other => {
+                    return Err(EvalAltResult::ErrorRuntime(
+                        format!("<Switch> can only contain <Case> and <Default>, found <{other}>")
+                            .into(),
+                        Position::NONE,
+                    )
+                    .into());
+                }
+            }
+        }
+    }
+
+    match default_children {
+        Some(default_children) => eval_children(
+            component_registry,
+            eval_context,
+            default_children,
+            expression_collection,
+        ),
+        None => Ok(String::new()),
+    }
+}
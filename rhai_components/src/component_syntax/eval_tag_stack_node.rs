@@ -1,13 +1,15 @@
 use std::sync::Arc;
 
+use log::trace;
 use rhai::Array;
 use rhai::Dynamic;
 use rhai::EvalAltResult;
 use rhai::EvalContext;
 use rhai::Map;
 
-use super::attribute_value::AttributeValue;
 use super::component_registry::ComponentRegistry;
+use super::eval_attribute_value::eval_attribute_value;
+use super::eval_switch::eval_switch;
 use super::eval_tag::eval_tag;
 use super::expression_collection::ExpressionCollection;
 use super::tag_stack_node::TagStackNode;
@@ -42,6 +44,18 @@ pub fn eval_tag_stack_node(
             is_closed,
             opening_tag,
         } => {
+            if let Some(opening_tag) = &opening_tag
+                && opening_tag.tag_name.name == "Switch"
+            {
+                return eval_switch(
+                    component_registry,
+                    eval_context,
+                    opening_tag,
+                    children,
+                    expression_collection,
+                );
+            }
+
             let mut result = String::new();
 
             if let Some(opening_tag) = &opening_tag
@@ -77,17 +91,7 @@ pub fn eval_tag_stack_node(
                     for attribute in &opening_tag.attributes {
                         props.insert(
                             attribute.name.clone().into(),
-                            if let Some(value) = &attribute.value {
-                                match value {
-                                    AttributeValue::Expression(expression_reference) => {
-                                        expression_collection
-                                            .eval_expression(eval_context, expression_reference)?
-                                    }
-                                    AttributeValue::Text(text) => text.into(),
-                                }
-                            } else {
-                                true.into()
-                            },
+                            eval_attribute_value(eval_context, expression_collection, attribute)?,
                         );
                     }
 
@@ -105,9 +109,18 @@ pub fn eval_tag_stack_node(
                     }
                 };
 
-                Ok(rhai_call_template_function(
+                let component_path = component_registry
+                    .get_path(&opening_tag.tag_name.name)
+                    .unwrap_or_else(|| opening_tag.tag_name.name.clone());
+
+                trace!(
+                    "Calling component '{}' with props: {props:?}",
+                    opening_tag.tag_name.name
+                );
+
+                Ok(rhai_call_template_function::<String>(
                     eval_context.engine(),
-                    &opening_tag.tag_name.name,
+                    &component_path,
                     (
                         context,
                         Dynamic::from_map(props),
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use log::trace;
 use rhai::Dynamic;
 use rhai::EvalAltResult;
 use rhai::EvalContext;
@@ -29,15 +30,19 @@ impl EvaluatorFactory {
                 expressions: inputs.to_vec(),
             };
 
+            let tag_stack_node = state.clone().try_cast::<TagStackNode>().ok_or_else(|| {
+                EvalAltResult::ErrorRuntime(
+                    "Expected TagStackNode in tag state".into(),
+                    Position::NONE,
+                )
+            })?;
+
+            trace!("Evaluating tag stack: {tag_stack_node:#?}");
+
             let rendered_tag_stack = eval_tag_stack_node(
                 component_registry_clone.clone(),
                 eval_context,
-                &state.clone().try_cast::<TagStackNode>().ok_or_else(|| {
-                    EvalAltResult::ErrorRuntime(
-                        "Expected TagStackNode in tag state".into(),
-                        Position::NONE,
-                    )
-                })?,
+                &tag_stack_node,
                 &mut expression_collection,
             )?;
 
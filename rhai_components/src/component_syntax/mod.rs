@@ -2,8 +2,11 @@ mod attribute;
 mod attribute_value;
 mod combine_output_symbols;
 mod combine_tag_stack;
+pub mod component_prop;
 pub mod component_reference;
 pub mod component_registry;
+mod eval_attribute_value;
+mod eval_switch;
 mod eval_tag;
 mod eval_tag_stack_node;
 pub mod evaluator_factory;
@@ -36,6 +39,7 @@ mod tests {
     use super::component_registry::ComponentRegistry;
     use super::evaluator_factory::EvaluatorFactory;
     use super::parse_component::parse_component;
+    use super::tag_name::TagName;
 
     #[derive(Clone, Default)]
     struct DummyAssetCollection {
@@ -81,14 +85,18 @@ mod tests {
         let component_registry = Arc::new(ComponentRegistry::default());
 
         component_registry.register_component(ComponentReference {
+            description: None,
             name: "LayoutHomepage".to_string(),
             path: "LayoutHomepage".to_string(),
-        });
+            props: Default::default(),
+        })?;
 
         component_registry.register_component(ComponentReference {
+            description: None,
             name: "Note".to_string(),
             path: "Note".to_string(),
-        });
+            props: Default::default(),
+        })?;
 
         let evaluator_factory = EvaluatorFactory {
             component_registry: component_registry.clone(),
@@ -187,4 +195,105 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_switch() -> Result<()> {
+        let evaluator_factory = EvaluatorFactory {
+            component_registry: Arc::new(ComponentRegistry::default()),
+        };
+
+        let mut engine = Engine::new();
+
+        engine.register_custom_syntax_without_look_ahead_raw(
+            "component",
+            parse_component,
+            true,
+            evaluator_factory.create_component_evaluator(),
+        );
+
+        let renderer = Func::<(Dynamic, Dynamic, Dynamic), String>::create_from_script(
+            engine,
+            r#"
+                fn template(context, props, content) {
+                    component {
+                        <Switch on={props.tone}>
+                            <Case value="formal">Dear Sir or Madam</Case>
+                            <Case value="casual">Hey there</Case>
+                            <Default>Hello</Default>
+                        </Switch>
+                    }
+                }
+            "#,
+            "template",
+        )?;
+
+        let render_with_tone = |tone: &str| {
+            let mut props = Map::new();
+
+            props.insert("tone".into(), tone.into());
+
+            renderer(Dynamic::UNIT, Dynamic::from_map(props), Dynamic::from(""))
+        };
+
+        assert_eq!(render_with_tone("formal")?.trim(), "Dear Sir or Madam");
+        assert_eq!(render_with_tone("casual")?.trim(), "Hey there");
+        assert_eq!(render_with_tone("technical")?.trim(), "Hello");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_namespaced_components_with_the_same_name() -> Result<()> {
+        let component_registry = ComponentRegistry::default();
+
+        for module_path in ["forms/Button", "ui/Button"] {
+            component_registry.register_component(ComponentReference {
+                description: None,
+                name: TagName::from_module_path(module_path).name,
+                path: module_path.to_string(),
+                props: Default::default(),
+            })?;
+        }
+
+        let evaluator_factory = EvaluatorFactory {
+            component_registry: Arc::new(component_registry),
+        };
+
+        let mut engine = Engine::new();
+
+        engine.set_module_resolver(FileModuleResolver::new_with_path(format!(
+            "{}/src/component_syntax/fixtures",
+            env!("CARGO_MANIFEST_DIR")
+        )));
+        engine.register_custom_syntax_without_look_ahead_raw(
+            "component",
+            parse_component,
+            true,
+            evaluator_factory.create_component_evaluator(),
+        );
+
+        let renderer = Func::<(Dynamic, Dynamic, Dynamic), String>::create_from_script(
+            engine,
+            r#"
+                fn template(context, props, content) {
+                    component {
+                        <ui:Button>Save</ui:Button>
+                        <forms:Button>Send</forms:Button>
+                    }
+                }
+            "#,
+            "template",
+        )?;
+
+        let rendered = renderer(
+            Dynamic::UNIT,
+            Dynamic::from_map(Map::new()),
+            Dynamic::from(""),
+        )?;
+
+        assert!(rendered.contains(r#"<button class="ui-button">Save</button>"#));
+        assert!(rendered.contains(r#"<button class="forms-button" type="submit">Send</button>"#));
+
+        Ok(())
+    }
 }
@@ -1,11 +1,27 @@
+/// Separates the library namespace from the component name, like in
+/// `<ui:Button>`
+const NAMESPACE_SEPARATOR: char = ':';
+
+/// Separates the directories of a component module path, like in
+/// `ui/Button`
+const MODULE_PATH_SEPARATOR: char = '/';
+
 #[derive(Clone, Debug, Hash)]
 pub struct TagName {
     pub name: String,
 }
 
 impl TagName {
+    /// Components in subdirectories are namespaced by the directory, so
+    /// `ui/Button` is used as `<ui:Button>`
+    pub fn from_module_path(module_path: &str) -> Self {
+        Self {
+            name: module_path.replace(MODULE_PATH_SEPARATOR, &NAMESPACE_SEPARATOR.to_string()),
+        }
+    }
+
     pub fn is_component(&self) -> bool {
-        self.name
+        self.local_name()
             .chars()
             .next()
             .is_some_and(|first_character| first_character.is_uppercase())
@@ -28,4 +44,12 @@ impl TagName {
             || self.name == "track"
             || self.name == "wbr"
     }
+
+    /// Name without the namespace
+    pub fn local_name(&self) -> &str {
+        match self.name.rsplit_once(NAMESPACE_SEPARATOR) {
+            Some((_, local_name)) => local_name,
+            None => &self.name,
+        }
+    }
 }
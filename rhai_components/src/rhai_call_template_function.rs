@@ -4,12 +4,13 @@ use rhai::Engine;
 use rhai::FuncArgs;
 use rhai::Position;
 use rhai::Scope;
+use rhai::Variant;
 
-pub fn rhai_call_template_function(
+pub fn rhai_call_template_function<TOutput: Clone + Variant>(
     engine: &Engine,
     component_name: &str,
     args: impl FuncArgs,
-) -> Result<String> {
+) -> Result<TOutput> {
     let module = engine
         .module_resolver()
         .resolve(engine, None, component_name, Position::NONE)?;
@@ -29,23 +29,18 @@ impl RhaiTemplateRenderer {
     ) -> Result<Self> {
         let templates: DashMap<String, ComponentReference> = DashMap::new();
 
-        for entry in &component_registry.components {
-            let component_reference = entry.value();
-
+        for component_reference in component_registry.component_references() {
             let module_resolver = expression_engine.module_resolver();
             let module = module_resolver.resolve(
                 &expression_engine,
                 None,
-                &component_reference.name,
+                &component_reference.path,
                 Position::NONE,
             )?;
 
-            expression_engine.register_static_module(component_reference.name.clone(), module);
+            expression_engine.register_static_module(&component_reference.name, module);
 
-            templates.insert(
-                component_reference.name.clone(),
-                component_reference.clone(),
-            );
+            templates.insert(component_reference.name.clone(), component_reference);
         }
 
         Ok(Self {
@@ -54,6 +49,16 @@ impl RhaiTemplateRenderer {
         })
     }
 
+    pub fn get_component_reference(&self, name: &str) -> Option<ComponentReference> {
+        self.templates
+            .get(name)
+            .map(|component_reference| component_reference.value().clone())
+    }
+
+    pub fn has_template(&self, name: &str) -> bool {
+        self.templates.contains_key(name)
+    }
+
     pub fn render<TComponentContext>(
         &self,
         name: &str,
@@ -61,13 +66,32 @@ impl RhaiTemplateRenderer {
         props: Dynamic,
         content: Dynamic,
     ) -> Result<String>
+    where
+        TComponentContext: CustomType,
+    {
+        self.render_dynamic(name, context, props, content)?
+            .into_string()
+            .map_err(|type_name| {
+                anyhow!("Template '{name}' returned {type_name} instead of a string")
+            })
+    }
+
+    /// Unlike `render`, keeps whatever the component returned, so callers
+    /// can accept structured output such as maps
+    pub fn render_dynamic<TComponentContext>(
+        &self,
+        name: &str,
+        context: TComponentContext,
+        props: Dynamic,
+        content: Dynamic,
+    ) -> Result<Dynamic>
     where
         TComponentContext: CustomType,
     {
         if let Some(component_reference) = self.templates.get(name) {
             rhai_call_template_function(
                 &self.expression_engine,
-                &component_reference.name,
+                &component_reference.path,
                 (context, props, content),
             )
         } else {